@@ -0,0 +1,265 @@
+//! A binary cache of loaded fonts, see [`Font::load_cached`].
+//!
+//! The cache file starts with [`MAGIC`], followed by the length of the
+//! [`Header`] as a little-endian `u32`, the header and the [`CachedFont`],
+//! both encoded as CBOR. It is only ever read back by the same version of
+//! norad, so the layout is free to change.
+
+use std::borrow::Cow;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use serde::{Deserialize, Serialize};
+
+use crate::datastore::{DataStore, ImageStore};
+use crate::font::{MetaInfo, DATA_DIR, IMAGES_DIR};
+use crate::layer::{Layer, LayerContents};
+use crate::{Color, Font, FontInfo, Glyph, Groups, Kerning, Name, Plist};
+
+const MAGIC: &[u8; 8] = b"NORADCCH";
+/// Bumped whenever the layout changes without a norad version bump.
+const FORMAT_VERSION: u32 = 1;
+
+/// Identifies the source UFO a cache was written for.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct Header {
+    format_version: u32,
+    norad_version: String,
+    files: Vec<FileStamp>,
+}
+
+/// The path, relative to the UFO directory, modification time and size of a
+/// file of the source UFO.
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+pub(crate) struct FileStamp {
+    path: PathBuf,
+    modified: (u64, u32),
+    len: u64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedFont<'a> {
+    meta: Cow<'a, MetaInfo>,
+    /// The font info as a binary plist, as its serialization relies on plist
+    /// deserializers reading integers as floats.
+    font_info: Vec<u8>,
+    /// The libs of the global guidelines, which the fontinfo serialization
+    /// leaves out.
+    guideline_libs: Vec<Option<Cow<'a, Plist>>>,
+    layers: Vec<CachedLayer<'a>>,
+    lib: Cow<'a, Plist>,
+    groups: Cow<'a, Groups>,
+    kerning: Cow<'a, Kerning>,
+    features: Cow<'a, str>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedLayer<'a> {
+    name: Cow<'a, Name>,
+    path: Cow<'a, Path>,
+    contents: Cow<'a, BTreeMap<Name, PathBuf>>,
+    glyphs: Vec<Cow<'a, Glyph>>,
+    color: Option<Cow<'a, Color>>,
+    lib: Cow<'a, Plist>,
+}
+
+/// Describes the files of the UFO at `path`, to check whether it changed
+/// since a cache was written.
+pub(crate) fn stamp(path: &Path) -> io::Result<Vec<FileStamp>> {
+    let mut files = Vec::new();
+    let mut dirs = vec![path.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let metadata = entry.metadata()?;
+            if metadata.is_dir() {
+                dirs.push(entry.path());
+                continue;
+            }
+            let modified = metadata.modified()?.duration_since(UNIX_EPOCH).unwrap_or_default();
+            files.push(FileStamp {
+                path: entry.path().strip_prefix(path).unwrap_or(&entry.path()).to_owned(),
+                modified: (modified.as_secs(), modified.subsec_nanos()),
+                len: metadata.len(),
+            });
+        }
+    }
+    files.sort_unstable_by(|a, b| a.path.cmp(&b.path));
+    Ok(files)
+}
+
+/// Reads the cache at `cache_path` for the UFO at `path`, returning `None`
+/// if it is missing, unreadable or out of date.
+pub(crate) fn read(path: &Path, cache_path: &Path) -> Option<Font> {
+    let data = fs::read(cache_path).ok()?;
+    let data = data.strip_prefix(MAGIC)?;
+    let (header_len, data) = data.split_first_chunk::<4>()?;
+    let (header, body) = data.split_at_checked(u32::from_le_bytes(*header_len) as usize)?;
+    let header: Header = ciborium::from_reader(header).ok()?;
+    if header != Header::new(stamp(path).ok()?) {
+        return None;
+    }
+    let cached: CachedFont = ciborium::from_reader(body).ok()?;
+
+    let mut font_info: FontInfo = plist::from_bytes(&cached.font_info).ok()?;
+    for (guideline, lib) in font_info.guidelines.iter_mut().flatten().zip(cached.guideline_libs) {
+        guideline.lib = lib.map(Cow::into_owned);
+    }
+    let layers = cached
+        .layers
+        .into_iter()
+        .map(|layer| {
+            let glyphs = layer
+                .glyphs
+                .into_iter()
+                .map(|glyph| (glyph.name().clone(), glyph.into_owned()))
+                .collect();
+            Layer::from_parts(
+                layer.name.into_owned(),
+                layer.path.into_owned(),
+                layer.contents.into_owned(),
+                glyphs,
+                layer.color.map(Cow::into_owned),
+                layer.lib.into_owned(),
+            )
+        })
+        .collect();
+    // The stores only list their files until an item is requested.
+    let data = match path.join(DATA_DIR).exists() {
+        true => DataStore::new(path).ok()?,
+        false => Default::default(),
+    };
+    let images = match path.join(IMAGES_DIR).exists() {
+        true => ImageStore::new(path).ok()?,
+        false => Default::default(),
+    };
+
+    Some(Font {
+        meta: cached.meta.into_owned(),
+        font_info,
+        layers: LayerContents::from_layers(layers),
+        lib: cached.lib.into_owned(),
+        groups: cached.groups.into_owned(),
+        kerning: cached.kerning.into_owned(),
+        features: cached.features.into_owned(),
+        data,
+        images,
+        creator: None,
+        images_skipped: false,
+    })
+}
+
+/// Writes `font`, as loaded from a UFO described by `files`, to a cache at
+/// `cache_path`.
+pub(crate) fn write(font: &Font, files: Vec<FileStamp>, cache_path: &Path) -> io::Result<()> {
+    let guidelines = font.guidelines();
+    let mut libs = font
+        .lib
+        .values()
+        .chain(guidelines.iter().filter_map(|guideline| guideline.lib()).flat_map(Plist::values))
+        .chain(font.layers.iter().flat_map(|layer| layer.lib.values()))
+        .chain(font.iter_all_glyphs().flat_map(|(_, glyph)| glyph.libs()).flat_map(Plist::values));
+    if libs.any(has_lossy_value) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "dates and UIDs in libs cannot be cached",
+        ));
+    }
+
+    let mut font_info = Vec::new();
+    plist::to_writer_binary(&mut font_info, &font.font_info).map_err(io::Error::other)?;
+    let cached = CachedFont {
+        meta: Cow::Borrowed(&font.meta),
+        font_info,
+        guideline_libs: guidelines.iter().map(|g| g.lib().map(Cow::Borrowed)).collect(),
+        layers: font
+            .layers
+            .iter()
+            .map(|layer| CachedLayer {
+                name: Cow::Borrowed(layer.name()),
+                path: Cow::Borrowed(layer.path()),
+                contents: Cow::Borrowed(layer.contents()),
+                glyphs: layer.iter().map(Cow::Borrowed).collect(),
+                color: layer.color.as_ref().map(Cow::Borrowed),
+                lib: Cow::Borrowed(&layer.lib),
+            })
+            .collect(),
+        lib: Cow::Borrowed(&font.lib),
+        groups: Cow::Borrowed(&font.groups),
+        kerning: Cow::Borrowed(&font.kerning),
+        features: Cow::Borrowed(&font.features),
+    };
+
+    let mut data = MAGIC.to_vec();
+    let mut header = Vec::new();
+    ciborium::into_writer(&Header::new(files), &mut header).map_err(io::Error::other)?;
+    data.extend((header.len() as u32).to_le_bytes());
+    data.append(&mut header);
+    ciborium::into_writer(&cached, &mut data).map_err(io::Error::other)?;
+    fs::write(cache_path, data)
+}
+
+impl Header {
+    fn new(files: Vec<FileStamp>) -> Self {
+        Header {
+            format_version: FORMAT_VERSION,
+            norad_version: env!("CARGO_PKG_VERSION").into(),
+            files,
+        }
+    }
+}
+
+/// Whether `value` is or contains a date or UID, which serde formats other
+/// than plist cannot tell apart from strings and integers.
+fn has_lossy_value(value: &plist::Value) -> bool {
+    match value {
+        plist::Value::Date(_) | plist::Value::Uid(_) => true,
+        plist::Value::Array(array) => array.iter().any(has_lossy_value),
+        plist::Value::Dictionary(dict) => dict.values().any(has_lossy_value),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn load_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let ufo = dir.path().join("font.ufo");
+        let cache = dir.path().join("font.cache");
+        for source in ["MutatorSansLightWide.ufo", "identifiers.ufo", "fontinfotest.ufo"] {
+            Font::load(Path::new("testdata").join(source)).unwrap().save(&ufo).unwrap();
+            let font = Font::load(&ufo).unwrap();
+
+            assert!(read(&ufo, &cache).is_none());
+            assert_eq!(Font::load_cached(&ufo, &cache).unwrap(), font);
+            let cached = read(&ufo, &cache).unwrap();
+            assert_eq!(cached, font, "{source}");
+            assert_eq!(cached.guidelines(), font.guidelines());
+            assert_eq!(Font::load_cached(&ufo, &cache).unwrap(), font);
+
+            fs::write(ufo.join("features.fea"), "# changed").unwrap();
+            assert!(read(&ufo, &cache).is_none());
+            assert_eq!(Font::load_cached(&ufo, &cache).unwrap().features, "# changed");
+        }
+    }
+
+    #[test]
+    fn lossy_libs_are_not_cached() {
+        let dir = tempfile::tempdir().unwrap();
+        let ufo = dir.path().join("font.ufo");
+        let cache = dir.path().join("font.cache");
+        let mut font = Font::new();
+        let date = plist::Date::from(std::time::SystemTime::UNIX_EPOCH);
+        font.lib.insert("com.example.date".into(), date.into());
+        font.save(&ufo).unwrap();
+
+        assert_eq!(Font::load_cached(&ufo, &cache).unwrap().lib, font.lib);
+        assert!(!cache.exists());
+    }
+}
@@ -53,6 +53,9 @@ pub struct DataRequest<'a> {
     pub data: bool,
     /// Load images
     pub images: bool,
+    /// Fail on `public.objectLibs` entries that match no object, instead of
+    /// dropping them
+    pub strict_object_libs: bool,
 }
 
 type FilterFn<'a> = dyn Fn(&str, &Path) -> bool + 'a;
@@ -91,6 +94,7 @@ impl<'a> DataRequest<'a> {
             features: b,
             data: b,
             images: b,
+            strict_object_libs: false,
         }
     }
 
@@ -191,6 +195,24 @@ impl<'a> DataRequest<'a> {
         self.images = b;
         self
     }
+
+    /// Request that loading fail if an entry of a `public.objectLibs` lib key
+    /// matches no object.
+    ///
+    /// Such entries are left behind by tools that delete an object but not its
+    /// lib. By default they are dropped, as there is nothing to attach them
+    /// to; with this option, loading instead fails with
+    /// [`GlifLoadError::OrphanedObjectLib`] or
+    /// [`FontInfoLoadError::OrphanedObjectLib`], naming the identifier.
+    ///
+    /// This is not affected by [`DataRequest::all`] and [`DataRequest::none`].
+    ///
+    /// [`GlifLoadError::OrphanedObjectLib`]: crate::error::GlifLoadError::OrphanedObjectLib
+    /// [`FontInfoLoadError::OrphanedObjectLib`]: crate::error::FontInfoLoadError::OrphanedObjectLib
+    pub fn strict_object_libs(mut self, b: bool) -> Self {
+        self.strict_object_libs = b;
+        self
+    }
 }
 
 impl Default for DataRequest<'_> {
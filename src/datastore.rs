@@ -471,6 +471,32 @@ mod tests {
         );
     }
 
+    #[test]
+    fn nested_binary_data_roundtripping() {
+        let path = PathBuf::from("com.example.build/nested/deeper/blob.bin");
+        let bytes: Vec<u8> = (0..=255u8).chain([0, 0xff, b'\r', b'\n', 0]).collect();
+
+        let mut ufo = crate::Font::new();
+        ufo.data.insert(path.clone(), bytes.clone()).unwrap();
+        let dir = TempDir::new().unwrap();
+        ufo.save(&dir).unwrap();
+
+        let on_disk = std::fs::read(dir.path().join(crate::font::DATA_DIR).join(&path)).unwrap();
+        assert_eq!(on_disk, bytes);
+
+        // Save a freshly loaded (still lazy) font again, in place and elsewhere.
+        let loaded = crate::Font::load(&dir).unwrap();
+        loaded.save(&dir).unwrap();
+        let other_dir = TempDir::new().unwrap();
+        loaded.save(&other_dir).unwrap();
+
+        for dir in [&dir, &other_dir] {
+            let ufo_rt = crate::Font::load(dir).unwrap();
+            assert_eq!(ufo_rt.data.keys().collect::<Vec<_>>(), vec![&path]);
+            assert_eq!(&*ufo_rt.data.get(&path).unwrap().unwrap(), &bytes[..]);
+        }
+    }
+
     #[test]
     fn lazy_data_loading() {
         let mut store = DataStore::new(UFO_DATA_IMAGE_TEST_PATH.as_ref()).unwrap();
@@ -10,7 +10,7 @@ use thiserror::Error;
 
 pub use crate::shared_types::ColorError;
 use crate::write::CustomSerializationError;
-use crate::Name;
+use crate::{Identifier, Name, PointType};
 
 /// An error that occurs while attempting to read a designspace file from disk.
 #[derive(Debug, Error)]
@@ -80,6 +80,94 @@ pub enum GlifLoadError {
     /// The entry with the given identifier within the glyph lib's `public.objectLibs` dictionary was not a dictionary.
     #[error("the glyph lib's 'public.objectLibs' entry for the object with identifier '{0}' must be a dictionary")]
     ObjectLibMustBeDictionary(String),
+    /// The glyph lib's `public.objectLibs` dictionary has an entry for an
+    /// identifier no object of the glyph has.
+    ///
+    /// Only returned when loading with
+    /// [`DataRequest::strict_object_libs`](crate::DataRequest::strict_object_libs).
+    #[error("the glyph lib's 'public.objectLibs' entry for identifier '{0}' matches no object")]
+    OrphanedObjectLib(String),
+}
+
+impl GlifLoadError {
+    /// Returns a stable, machine-readable code for this error.
+    ///
+    /// Parse errors return the [code of their kind](ErrorKind::code); the other
+    /// variants return `"io"`, `"xml"`, `"xml-attribute"`,
+    /// `"public-object-libs-must-be-dictionary"`,
+    /// `"object-lib-must-be-dictionary"` and `"orphaned-object-lib"`. The same
+    /// stability guarantee applies.
+    pub fn code(&self) -> &'static str {
+        match self {
+            GlifLoadError::Io(_) => "io",
+            GlifLoadError::Xml(_) => "xml",
+            GlifLoadError::XmlAttr(_) => "xml-attribute",
+            GlifLoadError::Parse(kind) => kind.code(),
+            GlifLoadError::PublicObjectLibsMustBeDictionary => {
+                "public-object-libs-must-be-dictionary"
+            }
+            GlifLoadError::ObjectLibMustBeDictionary(_) => "object-lib-must-be-dictionary",
+            GlifLoadError::OrphanedObjectLib(_) => "orphaned-object-lib",
+        }
+    }
+}
+
+/// A [`GlifLoadError`] along with the position in the glif data at which it
+/// occurred.
+///
+/// Returned by [`Glyph::from_bytes`](crate::Glyph::from_bytes).
+#[derive(Debug, Error)]
+#[error("failed to parse glyph data at byte {position}")]
+pub struct GlifError {
+    pub(crate) source: GlifLoadError,
+    pub(crate) position: usize,
+}
+
+impl GlifError {
+    /// Returns the underlying error.
+    pub fn error(&self) -> &GlifLoadError {
+        &self.source
+    }
+
+    /// Returns the underlying error, consuming `self`.
+    pub fn into_error(self) -> GlifLoadError {
+        self.source
+    }
+
+    /// Returns the byte offset in the glif data at which parsing stopped.
+    ///
+    /// For most errors this is just past the offending element; for XML
+    /// syntax errors, it is the start of the malformed markup.
+    pub fn position(&self) -> usize {
+        self.position
+    }
+
+    /// Returns the lines of `data` around the error, for display to the user.
+    ///
+    /// `data` must be the data that was parsed. Up to two lines before and
+    /// after the offending one are included, each prefixed with its line
+    /// number; the offending line is marked with `>`:
+    ///
+    /// ```text
+    ///   3 |     <contour>
+    /// > 4 |       <point x="0" y="0" type="bogus"/>
+    ///   5 |     </contour>
+    /// ```
+    pub fn context(&self, data: &[u8]) -> String {
+        const CONTEXT_LINES: usize = 2;
+        let text = String::from_utf8_lossy(data);
+        let position = self.position.min(data.len());
+        let error_line = data[..position].iter().filter(|b| **b == b'\n').count();
+        let first = error_line.saturating_sub(CONTEXT_LINES);
+        let last = error_line + CONTEXT_LINES;
+        let width = (last + 1).to_string().len();
+        let mut result = String::new();
+        for (idx, line) in text.lines().enumerate().take(last + 1).skip(first) {
+            let marker = if idx == error_line { '>' } else { ' ' };
+            result.push_str(&format!("{marker} {:>width$} | {line}\n", idx + 1));
+        }
+        result
+    }
 }
 
 /// An error that occurs while attempting to read a UFO package from disk.
@@ -126,6 +214,17 @@ pub enum FontLoadError {
     /// The UFO does not have a default layer.
     #[error("missing the default layer ('glyphs' subdirectory)")]
     MissingDefaultLayer,
+    /// The requested glyph does not exist in the layer.
+    #[error("glyph '{glyph}' does not exist in layer '{layer}'")]
+    MissingGlyph {
+        /// The layer name.
+        layer: String,
+        /// The glyph name.
+        glyph: String,
+    },
+    /// The requested layer does not exist in the UFO.
+    #[error("layer '{0}' does not exist")]
+    MissingLayer(String),
     /// The UFO does not have a default layer.
     #[error("cannot find the layercontents.plist file")]
     MissingLayerContentsFile,
@@ -172,6 +271,24 @@ pub enum LayerLoadError {
     },
 }
 
+/// A glyph left out of a font loaded with [`Font::load_lenient`], because
+/// its `.glif` file could not be loaded.
+///
+/// [`Font::load_lenient`]: crate::Font::load_lenient
+#[derive(Debug, Error)]
+#[non_exhaustive]
+#[error("failed to load glyph '{name}' of layer '{layer}' from '{path}'")]
+pub struct GlyphLoadError {
+    /// The name of the layer the glyph is in.
+    pub layer: Name,
+    /// The glyph name.
+    pub name: Name,
+    /// The path to the glif file.
+    pub path: PathBuf,
+    /// The underlying error.
+    pub source: GlifLoadError,
+}
+
 /// An error that occurs while attempting to read a UFO fontinfo.plist file from disk.
 #[derive(Debug, Error)]
 #[non_exhaustive]
@@ -191,6 +308,13 @@ pub enum FontInfoLoadError {
     /// The font lib's `public.objectLibs` value was something other than a dictionary.
     #[error("the lib.plist file's 'public.objectLibs' value must be a dictionary")]
     PublicObjectLibsMustBeDictionary,
+    /// The UFO lib.plist's `public.objectLibs` dictionary has an entry for an
+    /// identifier no global guideline has.
+    ///
+    /// Only returned when loading with
+    /// [`DataRequest::strict_object_libs`](crate::DataRequest::strict_object_libs).
+    #[error("the lib.plist file's 'public.objectLibs' entry for identifier '{0}' matches no global guideline")]
+    OrphanedObjectLib(String),
 }
 
 /// An error pointing to invalid data in the font's info.
@@ -367,6 +491,149 @@ impl ConvertContourError {
     }
 }
 
+/// An error returned when the components of a glyph cannot be resolved.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum ComponentError {
+    /// A component references a glyph that does not exist.
+    #[error("component base glyph '{0}' does not exist")]
+    MissingBase(Name),
+    /// Components reference each other in a cycle.
+    #[error("glyph '{0}' is part of a component cycle")]
+    Cycle(Name),
+}
+
+/// An error returned when the mark color palette in the font lib is malformed.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum PaletteError {
+    /// The palette is not an array.
+    #[error("the mark color palette must be an array")]
+    NotAnArray,
+    /// A palette entry is not a dictionary with string `name` and `color`
+    /// values.
+    #[error("mark color palette entry {0} must be a dictionary with a name and a color")]
+    MalformedEntry(usize),
+    /// A palette entry has an invalid color string.
+    #[error("mark color palette entry {index} has an invalid color")]
+    InvalidColor {
+        /// The index of the entry.
+        index: usize,
+        /// The underlying error.
+        source: ColorError,
+    },
+}
+
+/// An error returned by [`Glyph::dedup_anchors`] when a glyph has several
+/// anchors with the same name and [`DuplicateAnchors::Error`] was requested.
+///
+/// [`Glyph::dedup_anchors`]: crate::Glyph::dedup_anchors
+/// [`DuplicateAnchors::Error`]: crate::DuplicateAnchors::Error
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[error("anchor names used more than once: {}", names.join(", "))]
+pub struct DuplicateAnchorsError {
+    /// The duplicated anchor names, in the order they first appear.
+    pub names: Vec<Name>,
+}
+
+/// An error returned when a selection of glyph objects cannot be resolved.
+#[derive(Debug, Error)]
+#[non_exhaustive]
+pub enum SelectionError {
+    /// A contour index is out of range.
+    #[error("contour index {0} is out of range")]
+    ContourIndexOutOfRange(usize),
+    /// A point index is out of range for its contour.
+    #[error("point index {point} is out of range for contour {contour}")]
+    PointIndexOutOfRange {
+        /// The contour index.
+        contour: usize,
+        /// The point index.
+        point: usize,
+    },
+    /// No object in the glyph has the given identifier.
+    #[error("no object has the identifier '{0}'")]
+    UnknownIdentifier(Identifier),
+}
+
+/// The reason two glyphs are not compatible for interpolation, as returned by
+/// [`Glyph::is_compatible`] and [`Glyph::interpolate`].
+///
+/// [`Glyph::is_compatible`]: crate::Glyph::is_compatible
+/// [`Glyph::interpolate`]: crate::Glyph::interpolate
+#[derive(Debug, Clone, PartialEq, Eq, Error)]
+#[non_exhaustive]
+pub enum IncompatibilityReason {
+    /// The glyphs have a different number of contours.
+    #[error("glyphs have {expected} and {found} contours")]
+    ContourCount {
+        /// The number of contours in the first glyph.
+        expected: usize,
+        /// The number of contours in the second glyph.
+        found: usize,
+    },
+    /// A contour has a different number of points in each glyph.
+    #[error("contour {contour} has {expected} and {found} points")]
+    PointCount {
+        /// The contour index.
+        contour: usize,
+        /// The number of points in the first glyph's contour.
+        expected: usize,
+        /// The number of points in the second glyph's contour.
+        found: usize,
+    },
+    /// A point has a different type in each glyph.
+    #[error("point {point} of contour {contour} is {expected} and {found}")]
+    PointType {
+        /// The contour index.
+        contour: usize,
+        /// The point index.
+        point: usize,
+        /// The point type in the first glyph.
+        expected: PointType,
+        /// The point type in the second glyph.
+        found: PointType,
+    },
+    /// The glyphs have a different number of components.
+    #[error("glyphs have {expected} and {found} components")]
+    ComponentCount {
+        /// The number of components in the first glyph.
+        expected: usize,
+        /// The number of components in the second glyph.
+        found: usize,
+    },
+    /// A component references a different base glyph in each glyph.
+    #[error("component {component} references '{expected}' and '{found}'")]
+    ComponentBase {
+        /// The component index.
+        component: usize,
+        /// The base glyph of the first glyph's component.
+        expected: Name,
+        /// The base glyph of the second glyph's component.
+        found: Name,
+    },
+    /// The glyphs have a different number of anchors.
+    #[error("glyphs have {expected} and {found} anchors")]
+    AnchorCount {
+        /// The number of anchors in the first glyph.
+        expected: usize,
+        /// The number of anchors in the second glyph.
+        found: usize,
+    },
+    /// The glyphs have a different number of guidelines.
+    #[error("glyphs have {expected} and {found} guidelines")]
+    GuidelineCount {
+        /// The number of guidelines in the first glyph.
+        expected: usize,
+        /// The number of guidelines in the second glyph.
+        found: usize,
+    },
+    /// A guideline is vertical, horizontal or angled in one glyph but not in
+    /// the other.
+    #[error("guideline {0} has a different orientation in each glyph")]
+    GuidelineKind(usize),
+}
+
 /// An error that occurs while attempting to write a UFO package to disk.
 #[derive(Debug, Error)]
 #[non_exhaustive]
@@ -374,7 +641,7 @@ pub enum FontWriteError {
     /// Cannot clean up previous UFO package before writing out new one.
     #[error("failed to remove target directory before overwriting")]
     Cleanup(#[source] IoError),
-    /// Failed to create the images directory.
+    /// Failed to create a directory of the data or images store.
     #[error("failed to create store directory '{path}'")]
     CreateStoreDir {
         /// The path to the entry.
@@ -429,6 +696,10 @@ pub enum FontWriteError {
         /// The underlying error.
         source: StoreError,
     },
+    /// Failed to list the files of an existing UFO package, when planning to
+    /// overwrite it.
+    #[error("failed to list the files of the existing font directory")]
+    ListExisting(#[source] IoError),
     /// Failed to write out a layer.
     #[error("failed to write layer '{name}' to '{path}'")]
     Layer {
@@ -467,6 +738,10 @@ pub enum LayerWriteError {
     /// Failed to write out the layerinfo.plist file
     #[error("failed to write layerinfo.plist file")]
     LayerInfo(#[source] CustomSerializationError),
+    /// Failed to list the files already in the layer directory, which a
+    /// [`LayerWriter`](crate::LayerWriter) avoids clashing with.
+    #[error("failed to list the files of the existing layer directory")]
+    ListExisting(#[source] IoError),
 }
 
 /// An error when attempting to write a .glif file.
@@ -486,6 +761,10 @@ pub enum GlifWriteError {
     /// Failed to write a .glif file to disk.
     #[error("failed to write .glif file")]
     Io(#[source] IoError),
+    /// An anchor, guideline, contour, point or component has a lib but no
+    /// identifier to store it under in `public.objectLibs`.
+    #[error("an object with a lib must have an identifier")]
+    MissingObjectLibIdentifier,
     /// Plist serialization error. Wraps a [`PlistError`].
     #[error("error serializing glyph lib data internally")]
     Plist(#[source] PlistError),
@@ -580,6 +859,71 @@ pub enum ErrorKind {
     LibMustBeDictionary,
     /// An angle was out of bounds.
     BadAngle,
+    /// There is content other than comments, processing instructions and
+    /// whitespace after the closing `glyph` tag.
+    TrailingContent,
+    /// The XML declaration specifies an encoding other than UTF-8.
+    UnsupportedEncoding,
+    /// An object has a lib but no identifier to store it under.
+    LibWithoutIdentifier,
+}
+
+impl ErrorKind {
+    /// Returns a stable, machine-readable code for this kind of error, such as
+    /// `"bad-point"`.
+    ///
+    /// Unlike the [`Display`](std::fmt::Display) message, which may be
+    /// reworded, codes are part of the public API: a code is never changed or
+    /// reused for another kind of error. New kinds get new codes. This makes
+    /// them suitable as keys for localized or UI-specific messages.
+    pub fn code(&self) -> &'static str {
+        use ErrorKind::*;
+        match self {
+            UnsupportedGlifVersion => "unsupported-glif-version",
+            UnknownPointType => "unknown-point-type",
+            WrongFirstElement => "wrong-first-element",
+            MissingCloseTag => "missing-close-tag",
+            BadHexValue => "bad-hex-value",
+            BadNumber => "bad-number",
+            BadColor => "bad-color",
+            BadAnchor => "bad-anchor",
+            BadPoint => "bad-point",
+            BadGuideline => "bad-guideline",
+            BadComponent => "bad-component",
+            BadImage => "bad-image",
+            BadIdentifier => "bad-identifier",
+            InvalidName => "invalid-name",
+            BadLib => "bad-lib",
+            UnexpectedDuplicate => "unexpected-duplicate",
+            UnexpectedMove => "unexpected-move",
+            UnexpectedSmooth => "unexpected-smooth",
+            UnexpectedElement => "unexpected-element",
+            UnexpectedAttribute => "unexpected-attribute",
+            UnexpectedEof => "unexpected-eof",
+            UnexpectedPointAfterOffCurve => "unexpected-point-after-off-curve",
+            TooManyOffCurves => "too-many-off-curves",
+            PenPathNotStarted => "pen-path-not-started",
+            TrailingOffCurves => "trailing-off-curves",
+            DuplicateIdentifier => "duplicate-identifier",
+            UnexpectedDrawing => "unexpected-drawing",
+            UnfinishedDrawing => "unfinished-drawing",
+            UnexpectedPointField => "unexpected-point-field",
+            UnexpectedComponentField => "unexpected-component-field",
+            UnexpectedAnchorField => "unexpected-anchor-field",
+            UnexpectedGuidelineField => "unexpected-guideline-field",
+            UnexpectedImageField => "unexpected-image-field",
+            DuplicateElement(_) => "duplicate-element",
+            UnexpectedV1Element(_) => "unexpected-v1-element",
+            UnexpectedV1Attribute(_) => "unexpected-v1-attribute",
+            ComponentEmptyBase => "component-empty-base",
+            ComponentMissingBase => "component-missing-base",
+            LibMustBeDictionary => "lib-must-be-dictionary",
+            BadAngle => "bad-angle",
+            TrailingContent => "trailing-content",
+            UnsupportedEncoding => "unsupported-encoding",
+            LibWithoutIdentifier => "lib-without-identifier",
+        }
+    }
 }
 
 impl std::fmt::Display for ErrorKind {
@@ -634,6 +978,9 @@ impl std::fmt::Display for ErrorKind {
             }
             LibMustBeDictionary => write!(f, "the glyph lib must be a dictionary"),
             BadAngle => write!(f, "an angle must be between 0 and 360°"),
+            TrailingContent => write!(f, "unexpected content after the closing 'glyph' tag"),
+            UnsupportedEncoding => write!(f, "glif files must be encoded in UTF-8"),
+            LibWithoutIdentifier => write!(f, "an object with a lib must have an identifier"),
         }
     }
 }
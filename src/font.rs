@@ -2,35 +2,53 @@
 
 #![deny(rustdoc::broken_intra_doc_links)]
 
+use std::collections::{BTreeMap, BTreeSet, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Mutex;
 
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 
-use crate::data_request::LayerFilter;
 use crate::datastore::{DataStore, ImageStore};
-use crate::error::{FontLoadError, FontWriteError};
+use crate::error::{
+    ComponentError, ErrorKind, ExpectedPositiveValue, FontInfoErrorKind, FontLoadError,
+    FontWriteError, GlyphLoadError, GroupsValidationError, IncompatibilityReason, LayerLoadError,
+    LayerWriteError, NamingError, PaletteError,
+};
 use crate::fontinfo::FontInfo;
-use crate::glyph::Glyph;
+use crate::glyph::builder::validate_points;
+use crate::glyph::{Contour, ContourPoint, Glyph, OutlineFilter, PointType};
 use crate::groups::{validate_groups, Groups};
 use crate::guideline::Guideline;
+#[cfg(feature = "object-libs")]
+use crate::identifier::UuidV4Identifiers;
+use crate::identifier::{Identifier, IdentifierSource};
 use crate::kerning::Kerning;
-use crate::layer::{Layer, LayerContents, LAYER_CONTENTS_FILE};
+use crate::layer::{
+    GlyphMut, Layer, LayerContents, DEFAULT_GLYPHS_DIRNAME, DEFAULT_LAYER_NAME, LAYER_CONTENTS_FILE,
+};
 use crate::name::Name;
 use crate::names::NameList;
-use crate::shared_types::{Plist, PUBLIC_OBJECT_LIBS_KEY};
+use crate::shared_types::{
+    GlyphCategory, PaletteColor, Plist, PUBLIC_GLYPH_ORDER_KEY, PUBLIC_OBJECT_LIBS_KEY,
+    PUBLIC_OPENTYPE_CATEGORIES_KEY, PUBLIC_SKIP_EXPORT_GLYPHS_KEY,
+};
 use crate::upconversion;
-use crate::write::{self, WriteOptions};
+use crate::write::{self, CustomSerializationError, WriteOptions};
 use crate::DataRequest;
 
 static METAINFO_FILE: &str = "metainfo.plist";
+static NOTDEF: &str = ".notdef";
 static FONTINFO_FILE: &str = "fontinfo.plist";
 pub(crate) static LIB_FILE: &str = "lib.plist";
 static GROUPS_FILE: &str = "groups.plist";
 static KERNING_FILE: &str = "kerning.plist";
 static FEATURES_FILE: &str = "features.fea";
 static DEFAULT_METAINFO_CREATOR: &str = "org.linebender.norad";
+static MARK_COLOR_PALETTE_KEY: &str = "org.linebender.norad.markColorPalette";
+static PALETTE_NAME_KEY: &str = "name";
+static PALETTE_COLOR_KEY: &str = "color";
 pub(crate) static DATA_DIR: &str = "data";
 pub(crate) static IMAGES_DIR: &str = "images";
 
@@ -90,6 +108,13 @@ pub struct Font {
     ///
     /// [dir]: https://unifiedfontobject.org/versions/ufo3/images/
     pub images: ImageStore,
+    /// The creator written to `metainfo.plist` when saving, set with
+    /// [`Font::set_creator`]. Norad's own identifier is written if `None`.
+    pub(crate) creator: Option<String>,
+    /// Whether the font was loaded without its images, see
+    /// [`DataRequest::images`], in which case [`Font::validate`] cannot tell
+    /// whether the images referenced by glyphs exist.
+    pub(crate) images_skipped: bool,
 }
 
 /// A version of the [UFO spec].
@@ -121,6 +146,176 @@ pub struct MetaInfo {
     pub format_version_minor: u32,
 }
 
+/// A problem found by [`Font::check_layer_consistency`].
+#[derive(Debug, Clone, PartialEq)]
+#[non_exhaustive]
+pub enum LayerIssue {
+    /// A glyph exists in a non-default layer but not in the default layer.
+    MissingFromDefaultLayer {
+        /// The layer containing the glyph.
+        layer: Name,
+        /// The glyph's name.
+        glyph: Name,
+    },
+    /// A component references a base glyph that does not exist in the same layer.
+    MissingComponentBase {
+        /// The layer containing the composite glyph.
+        layer: Name,
+        /// The composite glyph's name.
+        glyph: Name,
+        /// The name of the missing base glyph.
+        base: Name,
+    },
+    /// A glyph's advance width differs from that of the default layer's glyph.
+    AdvanceWidthMismatch {
+        /// The non-default layer containing the glyph.
+        layer: Name,
+        /// The glyph's name.
+        glyph: Name,
+        /// The advance width in the default layer.
+        default_width: f64,
+        /// The advance width in `layer`.
+        width: f64,
+    },
+}
+
+/// The files saving a [`Font`] would change, as returned by
+/// [`Font::save_plan`].
+///
+/// All paths are relative to the UFO directory and sorted.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SavePlan {
+    /// The files that do not exist yet.
+    pub created: Vec<PathBuf>,
+    /// The existing files whose contents would change.
+    pub overwritten: Vec<PathBuf>,
+    /// The existing files that would be written with identical contents.
+    pub unchanged: Vec<PathBuf>,
+    /// The existing files that would be deleted.
+    pub deleted: Vec<PathBuf>,
+}
+
+impl SavePlan {
+    /// Whether saving would change any file.
+    pub fn has_changes(&self) -> bool {
+        !(self.created.is_empty() && self.overwritten.is_empty() && self.deleted.is_empty())
+    }
+}
+
+/// A problem found by [`Font::validate`].
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum ValidationIssue {
+    /// The font info is invalid. Only the first problem found is reported.
+    FontInfo(FontInfoErrorKind),
+    /// The groups are invalid. Only the first problem found is reported.
+    Groups(GroupsValidationError),
+    /// A kerning pair references a kerning group that does not exist.
+    MissingKerningGroup {
+        /// The first side of the pair.
+        first: Name,
+        /// The second side of the pair.
+        second: Name,
+        /// The missing group, one of `first` and `second`.
+        group: Name,
+    },
+    /// A problem with the layers, see [`Font::check_layer_consistency`].
+    Layer(LayerIssue),
+    /// A glyph's image references a file missing from the font's images.
+    MissingImage {
+        /// The layer containing the glyph.
+        layer: Name,
+        /// The glyph's name.
+        glyph: Name,
+        /// The image's file name.
+        file_name: PathBuf,
+    },
+    /// An identifier is used by more than one object of a glyph.
+    DuplicateIdentifier {
+        /// The layer containing the glyph.
+        layer: Name,
+        /// The glyph's name.
+        glyph: Name,
+        /// The repeated identifier.
+        identifier: Identifier,
+    },
+    /// A contour's points do not form a valid outline, for example because
+    /// of a misplaced move point or too many off-curve points.
+    InvalidContour {
+        /// The layer containing the glyph.
+        layer: Name,
+        /// The glyph's name.
+        glyph: Name,
+        /// The index of the contour in the glyph.
+        contour: usize,
+        /// What is wrong with the contour.
+        error: ErrorKind,
+    },
+}
+
+/// The result of [`Font::interpolation_report`].
+///
+/// It displays as one line per problem, glyphs missing from either font
+/// first.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct InterpolationReport {
+    /// The glyphs present in both fonts that cannot be interpolated, with the
+    /// first reason found.
+    pub incompatible: BTreeMap<Name, IncompatibilityReason>,
+    /// The glyphs only present in the first font, in sorted order.
+    pub only_in_first: Vec<Name>,
+    /// The glyphs only present in the second font, in sorted order.
+    pub only_in_second: Vec<Name>,
+}
+
+impl InterpolationReport {
+    /// Whether no problems were found.
+    pub fn is_compatible(&self) -> bool {
+        self.incompatible.is_empty()
+            && self.only_in_first.is_empty()
+            && self.only_in_second.is_empty()
+    }
+}
+
+impl std::fmt::Display for InterpolationReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.is_compatible() {
+            return writeln!(f, "fonts are compatible");
+        }
+        for name in &self.only_in_first {
+            writeln!(f, "glyph '{name}' is missing from the second font")?;
+        }
+        for name in &self.only_in_second {
+            writeln!(f, "glyph '{name}' is missing from the first font")?;
+        }
+        for (name, reason) in &self.incompatible {
+            writeln!(f, "glyph '{name}': {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Returns the paths of all files under `root`, relative to it.
+///
+/// Symlinks are listed as files, even if they point to a directory: they are
+/// not followed.
+fn list_files(root: &Path) -> std::io::Result<BTreeSet<PathBuf>> {
+    let mut files = BTreeSet::new();
+    let mut dirs = vec![root.to_owned()];
+    while let Some(dir) = dirs.pop() {
+        for entry in fs::read_dir(&dir)? {
+            let entry = entry?;
+            let path = entry.path();
+            if entry.file_type()?.is_dir() {
+                dirs.push(path);
+            } else {
+                files.insert(path.strip_prefix(root).unwrap_or(&path).to_owned());
+            }
+        }
+    }
+    Ok(files)
+}
+
 fn is_zero(v: &u32) -> bool {
     *v == 0
 }
@@ -166,6 +361,44 @@ impl Font {
         Self::load_requested_data(path, DataRequest::all())
     }
 
+    /// Returns a [`Font`] loaded from the UFO directory `path`, going through a
+    /// binary cache at `cache_path` to skip parsing when the UFO is unchanged.
+    ///
+    /// If the cache exists and was written by the same version of norad for
+    /// the UFO's current files, as told by their names, sizes and
+    /// modification times, the font is read from it. Otherwise, the UFO is
+    /// loaded like with [`Font::load`] and the cache is written for next
+    /// time. The `data` and `images` directories are not cached, but listed
+    /// again from the UFO directory; their contents are loaded lazily anyway.
+    ///
+    /// The cache is not a font format: it is only meant to be read back by
+    /// this method, and may be rewritten at any time. A missing, stale or
+    /// unreadable cache is silently replaced. It is not written at all if a
+    /// lib contains a date or UID value, as the cache could not tell them
+    /// apart from strings and integers. `cache_path` should be outside of the
+    /// UFO directory, which would otherwise always appear to have changed.
+    ///
+    /// Requires the `cache` feature.
+    #[cfg(feature = "cache")]
+    pub fn load_cached(
+        path: impl AsRef<Path>,
+        cache_path: impl AsRef<Path>,
+    ) -> Result<Font, FontLoadError> {
+        let (path, cache_path) = (path.as_ref(), cache_path.as_ref());
+        if let Some(font) = crate::cache::read(path, cache_path) {
+            return Ok(font);
+        }
+        // Take the stamp first, so that changes made while loading invalidate
+        // the cache.
+        let files = crate::cache::stamp(path);
+        let font = Font::load(path)?;
+        if let Ok(files) = files {
+            // The cache is only an optimization, failing to write it is fine.
+            let _ = crate::cache::write(&font, files, cache_path);
+        }
+        Ok(font)
+    }
+
     /// Returns a [`Font`] object with custom data inclusion/exclusion
     /// criteria from a UFO directory `path`.
     ///
@@ -209,10 +442,42 @@ impl Font {
         path: impl AsRef<Path>,
         request: DataRequest,
     ) -> Result<Font, FontLoadError> {
-        Self::load_impl(path.as_ref(), request)
+        Self::load_impl(path.as_ref(), request, None)
+    }
+
+    /// Returns a [`Font`] loaded from the UFO directory `path` like with
+    /// [`Font::load`], leaving out the glyphs that fail to load instead of
+    /// failing altogether.
+    ///
+    /// The glyphs left out are returned along with the font, each with the
+    /// error that occurred, so that a font with a few corrupt `.glif` files
+    /// can still be opened and repaired. They are also left out of their
+    /// layer's `contents.plist`: saving the font over `path` deletes their
+    /// files. Errors in any other file still fail the load.
+    pub fn load_lenient(
+        path: impl AsRef<Path>,
+    ) -> Result<(Font, Vec<GlyphLoadError>), FontLoadError> {
+        Self::load_lenient_requested_data(path, DataRequest::all())
+    }
+
+    /// Returns a [`Font`] loaded from the UFO directory `path` like with
+    /// [`Font::load_lenient`], loading only the data specified by `request`.
+    ///
+    /// See [`Font::load_requested_data`] for details on the request.
+    pub fn load_lenient_requested_data(
+        path: impl AsRef<Path>,
+        request: DataRequest,
+    ) -> Result<(Font, Vec<GlyphLoadError>), FontLoadError> {
+        let mut skipped = Vec::new();
+        let font = Self::load_impl(path.as_ref(), request, Some(&mut skipped))?;
+        Ok((font, skipped))
     }
 
-    fn load_impl(path: &Path, request: DataRequest) -> Result<Font, FontLoadError> {
+    fn load_impl(
+        path: &Path,
+        request: DataRequest,
+        skipped: Option<&mut Vec<GlyphLoadError>>,
+    ) -> Result<Font, FontLoadError> {
         let metadata = path.metadata().map_err(FontLoadError::AccessUfoDir)?;
         if !metadata.is_dir() {
             return Err(FontLoadError::UfoNotADir);
@@ -231,7 +496,7 @@ impl Font {
 
         let fontinfo_path = path.join(FONTINFO_FILE);
         let mut font_info = if fontinfo_path.exists() {
-            load_fontinfo(&fontinfo_path, &meta, &mut lib)?
+            load_fontinfo(&fontinfo_path, &meta, &mut lib, request.strict_object_libs)?
         } else {
             Default::default()
         };
@@ -258,7 +523,7 @@ impl Font {
         };
 
         let glyph_names = NameList::default();
-        let layers = load_layer_set(path, &meta, &glyph_names, &request.layers)?;
+        let layers = load_layer_set(path, &meta, &glyph_names, &request, skipped)?;
 
         let data = if request.data && path.join(DATA_DIR).exists() {
             DataStore::new(path).map_err(FontLoadError::DataStore)?
@@ -309,6 +574,8 @@ impl Font {
             features,
             data,
             images,
+            creator: None,
+            images_skipped: !request.images,
         })
     }
 
@@ -421,6 +688,140 @@ impl Font {
     }
 
     fn save_impl(&self, path: &Path, options: &WriteOptions) -> Result<(), FontWriteError> {
+        self.check_before_writing()?;
+        if options.only_write_changes && path.is_dir() {
+            return self.save_changes(path, options);
+        }
+
+        // TODO: run glif validation up front?
+
+        // Now do the actual writing.
+        if path.exists() {
+            fs::remove_dir_all(path).map_err(FontWriteError::Cleanup)?;
+        }
+        fs::create_dir(path).map_err(FontWriteError::CreateUfoDir)?;
+
+        self.create_dirs_for_writing(path)?;
+        self.visit_files_for_writing(path, options, |file, data| {
+            close_already::fs::write(file, data)
+        })
+    }
+
+    /// Saves over the existing UFO at `path`, only writing the files whose
+    /// contents changed and deleting those no longer needed.
+    fn save_changes(&self, path: &Path, options: &WriteOptions) -> Result<(), FontWriteError> {
+        let mut stale = list_files(path).map_err(FontWriteError::ListExisting)?;
+        // Saving never writes symlinks: delete them before writing, rather
+        // than writing through them.
+        let links: Vec<PathBuf> =
+            stale.iter().filter(|file| path.join(file).is_symlink()).cloned().collect();
+        for link in &links {
+            fs::remove_file(path.join(link)).map_err(FontWriteError::Cleanup)?;
+            stale.remove(link);
+        }
+        let stale = Mutex::new(stale);
+        self.create_dirs_for_writing(path)?;
+        self.visit_files_for_writing(path, options, |file, data| {
+            let relative = file.strip_prefix(path).expect("files are written under the UFO");
+            let existed = stale.lock().unwrap().remove(relative);
+            if existed && fs::read(file).is_ok_and(|current| current == data) {
+                return Ok(());
+            }
+            close_already::fs::write(file, data)
+        })?;
+        let stale = stale.into_inner().unwrap();
+
+        for file in &stale {
+            fs::remove_file(path.join(file)).map_err(FontWriteError::Cleanup)?;
+        }
+        // Remove the directories left empty, such as those of removed layers,
+        // deepest first.
+        let dirs: BTreeSet<&Path> = stale
+            .iter()
+            .chain(&links)
+            .flat_map(|file| file.ancestors().skip(1))
+            .filter(|dir| !dir.as_os_str().is_empty())
+            .collect();
+        let mut dirs: Vec<_> = dirs.into_iter().collect();
+        dirs.sort_by_key(|dir| std::cmp::Reverse(dir.components().count()));
+        for dir in dirs {
+            let dir = path.join(dir);
+            if fs::read_dir(&dir).map_err(FontWriteError::Cleanup)?.next().is_none() {
+                fs::remove_dir(&dir).map_err(FontWriteError::Cleanup)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Returns the files [`Font::save`] would create, overwrite and delete when
+    /// saving to `path`, without changing anything on disk.
+    ///
+    /// Every file is serialized and compared with the existing one, so that
+    /// files which would be rewritten with identical contents are told apart.
+    /// Files of an existing UFO at `path` that saving would not write again
+    /// are deleted, as saving replaces the whole directory.
+    ///
+    /// The same checks are run as when saving, so this fails if saving would
+    /// fail before touching the file system.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use std::path::Path;
+    /// use norad::Font;
+    ///
+    /// let mut ufo = Font::load("path/to/font.ufo").expect("failed to load");
+    /// ufo.features.push_str("\n# a new comment\n");
+    /// let plan = ufo.save_plan("path/to/font.ufo").expect("failed to plan");
+    /// assert_eq!(plan.overwritten, [Path::new("features.fea")]);
+    /// ```
+    pub fn save_plan(&self, path: impl AsRef<Path>) -> Result<SavePlan, FontWriteError> {
+        self.save_plan_with_options(path, &Default::default())
+    }
+
+    /// Returns the files [`Font::save_with_options`] would create, overwrite
+    /// and delete when saving to `path` with `options`, without changing
+    /// anything on disk.
+    ///
+    /// See [`Font::save_plan`] for details.
+    pub fn save_plan_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        options: &WriteOptions,
+    ) -> Result<SavePlan, FontWriteError> {
+        let path = path.as_ref();
+        self.check_before_writing()?;
+
+        let existing = Mutex::new(match path.exists() {
+            true => list_files(path).map_err(FontWriteError::ListExisting)?,
+            false => BTreeSet::new(),
+        });
+        let plan = Mutex::new(SavePlan::default());
+        self.visit_files_for_writing(path, options, |file, data| {
+            let relative = file.strip_prefix(path).expect("files are written under the UFO");
+            let existed = existing.lock().unwrap().remove(relative);
+            let unchanged = existed
+                && !file.is_symlink()
+                && fs::read(file).is_ok_and(|current| current == data);
+            let mut plan = plan.lock().unwrap();
+            match (existed, unchanged) {
+                (false, _) => plan.created.push(relative.to_owned()),
+                (true, true) => plan.unchanged.push(relative.to_owned()),
+                // Unreadable files are overwritten as well.
+                (true, false) => plan.overwritten.push(relative.to_owned()),
+            }
+            Ok(())
+        })?;
+        let mut plan = plan.into_inner().unwrap();
+        plan.deleted = existing.into_inner().unwrap().into_iter().collect();
+        plan.created.sort_unstable();
+        plan.overwritten.sort_unstable();
+        plan.unchanged.sort_unstable();
+        Ok(plan)
+    }
+
+    /// Runs the checks that must pass before anything is written to disk.
+    fn check_before_writing(&self) -> Result<(), FontWriteError> {
         if self.meta.format_version != FormatVersion::V3 {
             return Err(FontWriteError::Downgrade);
         }
@@ -440,122 +841,185 @@ impl Font {
                 return Err(FontWriteError::InvalidStoreEntry { path: path.clone(), source });
             };
         }
+        Ok(())
+    }
 
-        // TODO: run glif validation up front?
-
-        // Now do the actual writing.
-        if path.exists() {
-            fs::remove_dir_all(path).map_err(FontWriteError::Cleanup)?;
+    /// Creates the directories of the layers, data and images written when
+    /// saving to `root`.
+    fn create_dirs_for_writing(&self, root: &Path) -> Result<(), FontWriteError> {
+        for layer in self.layers.iter() {
+            let path = root.join(&layer.path);
+            fs::create_dir_all(&path).map_err(|source| FontWriteError::Layer {
+                name: layer.name.to_string(),
+                path: path.clone(),
+                source: Box::new(LayerWriteError::CreateDir(source)),
+            })?;
         }
-        fs::create_dir(path).map_err(FontWriteError::CreateUfoDir)?;
-
-        // we want to always set ourselves as the creator when serializing,
-        // but we also don't have mutable access to self.
-        let metainfo_path = path.join(METAINFO_FILE);
-        if self.meta.creator == Some(DEFAULT_METAINFO_CREATOR.into()) {
-            write::write_xml_to_file(&metainfo_path, &self.meta, options)
-                .map_err(|source| FontWriteError::CustomFile { name: METAINFO_FILE, source })?;
-        } else {
-            write::write_xml_to_file(&metainfo_path, &MetaInfo::default(), options)
-                .map_err(|source| FontWriteError::CustomFile { name: METAINFO_FILE, source })?;
+        let data_dirs = self.data.keys().filter_map(|file| file.parent());
+        let data_dirs = data_dirs.map(|dir| root.join(DATA_DIR).join(dir));
+        let images_dir = (!self.images.is_empty()).then(|| root.join(IMAGES_DIR));
+        for path in data_dirs.chain(images_dir) {
+            fs::create_dir_all(&path)
+                .map_err(|source| FontWriteError::CreateStoreDir { path, source })?;
         }
+        Ok(())
+    }
 
-        if !self.font_info.is_empty() {
-            write::write_xml_to_file(&path.join(FONTINFO_FILE), &self.font_info, options)
-                .map_err(|source| FontWriteError::CustomFile { name: FONTINFO_FILE, source })?;
+    /// Calls `f` with the path, under the UFO directory `root`, and the
+    /// contents of each file written when saving.
+    ///
+    /// The directories the files go in must already exist, see
+    /// [`Font::create_dirs_for_writing`]. Glyph files are visited in parallel
+    /// if the `rayon` feature is enabled. Errors returned by `f` are reported
+    /// as failures to write the file.
+    fn visit_files_for_writing(
+        &self,
+        root: &Path,
+        options: &WriteOptions,
+        f: impl Fn(&Path, &[u8]) -> std::io::Result<()> + Sync,
+    ) -> Result<(), FontWriteError> {
+        fn plist(
+            name: &'static str,
+            value: &impl Serialize,
+            options: &WriteOptions,
+        ) -> Result<Vec<u8>, FontWriteError> {
+            write::write_xml_to_bytes(value, options)
+                .map_err(|source| FontWriteError::CustomFile { name, source })
         }
 
-        // Object libs are treated specially. The UFO v3 format won't allow us
-        // to store them inline, so they have to be placed into the font's lib
-        // under the public.objectLibs parent key. To avoid mutation behind the
-        // client's back, object libs are written out but not stored in
-        // font.lib in-memory. Instead we clone the lib, add the object libs, and
-        // write out that.
-
-        let mut lib = self.lib.clone();
-        let font_object_libs = self.font_info.dump_object_libs();
-        if !font_object_libs.is_empty() {
-            lib.insert(PUBLIC_OBJECT_LIBS_KEY.into(), font_object_libs.into());
+        let meta = self.meta_for_writing();
+        let mut files = vec![(METAINFO_FILE, plist(METAINFO_FILE, &meta, options)?)];
+        if !self.font_info.is_empty() {
+            let font_info =
+                self.font_info.to_plist().map_err(|source| FontWriteError::CustomFile {
+                    name: FONTINFO_FILE,
+                    source: CustomSerializationError::SerializePlist(source),
+                })?;
+            files.push((FONTINFO_FILE, plist(FONTINFO_FILE, &font_info, options)?));
         }
+        let lib = self.lib_for_writing();
         if !lib.is_empty() {
-            crate::util::recursive_sort_plist_keys(&mut lib);
-            write::write_xml_to_file(&path.join(LIB_FILE), &lib, options)
-                .map_err(|source| FontWriteError::CustomFile { name: LIB_FILE, source })?;
+            files.push((LIB_FILE, plist(LIB_FILE, &lib, options)?));
         }
-
         if !self.groups.is_empty() {
-            write::write_xml_to_file(&path.join(GROUPS_FILE), &self.groups, options)
-                .map_err(|source| FontWriteError::CustomFile { name: GROUPS_FILE, source })?;
+            files.push((GROUPS_FILE, plist(GROUPS_FILE, &self.groups, options)?));
         }
-
         if !self.kerning.is_empty() {
-            let kerning_serializer = crate::kerning::KerningSerializer { kerning: &self.kerning };
-            write::write_xml_to_file(&path.join(KERNING_FILE), &kerning_serializer, options)
-                .map_err(|source| FontWriteError::CustomFile { name: KERNING_FILE, source })?;
+            let kerning = crate::kerning::KerningSerializer { kerning: &self.kerning };
+            files.push((KERNING_FILE, plist(KERNING_FILE, &kerning, options)?));
         }
-
         if !self.features.is_empty() {
-            // Normalize feature files with line feed line endings
-            // This is consistent with the line endings serialized in glif and plist files
-            let feature_file_path = path.join(FEATURES_FILE);
-            if self.features.as_bytes().contains(&b'\r') {
-                close_already::fs::write(&feature_file_path, self.features.replace("\r\n", "\n"))
-                    .map_err(FontWriteError::FeatureFile)?;
-            } else {
-                close_already::fs::write(&feature_file_path, &self.features)
-                    .map_err(FontWriteError::FeatureFile)?;
-            }
+            files.push((FEATURES_FILE, self.features.replace("\r\n", "\n").into_bytes()));
         }
-
         let contents: Vec<(&str, &PathBuf)> =
             self.layers.iter().map(|l| (l.name.as_ref(), &l.path)).collect();
-        write::write_xml_to_file(&path.join(LAYER_CONTENTS_FILE), &contents, options)
-            .map_err(|source| FontWriteError::CustomFile { name: LAYER_CONTENTS_FILE, source })?;
+        files.push((LAYER_CONTENTS_FILE, plist(LAYER_CONTENTS_FILE, &contents, options)?));
+
+        for (name, data) in files {
+            f(&root.join(name), &data).map_err(|source| match name == FEATURES_FILE {
+                true => FontWriteError::FeatureFile(source),
+                false => FontWriteError::CustomFile {
+                    name,
+                    source: CustomSerializationError::WriteFile(source),
+                },
+            })?;
+        }
 
         for layer in self.layers.iter() {
-            let layer_path = path.join(&layer.path);
-            layer.save_with_options(&layer_path, options).map_err(|source| {
+            let path = root.join(&layer.path);
+            layer.visit_files_for_writing(&path, options, &f).map_err(|source| {
                 FontWriteError::Layer {
                     name: layer.name.to_string(),
-                    path: layer_path,
+                    path,
                     source: Box::new(source),
                 }
             })?;
         }
 
-        if !self.data.is_empty() {
-            let data_dir = path.join(DATA_DIR);
-            for (data_path, contents) in self.data.iter() {
-                let data = contents.expect("internal error: should have been checked");
-                let destination = data_dir.join(data_path);
-                let destination_parent = destination.parent().unwrap();
-                fs::create_dir_all(destination_parent).map_err(|source| {
-                    FontWriteError::CreateStoreDir { path: destination_parent.into(), source }
-                })?;
-                close_already::fs::write(&destination, &*data)
-                    .map_err(|source| FontWriteError::Data { path: destination, source })?;
-            }
+        for (data_path, contents) in self.data.iter() {
+            let data = contents.expect("internal error: should have been checked");
+            let path = root.join(DATA_DIR).join(data_path);
+            f(&path, &data).map_err(|source| FontWriteError::Data { path, source })?;
         }
+        for (image_path, contents) in self.images.iter() {
+            let data = contents.expect("internal error: should have been checked");
+            let path = root.join(IMAGES_DIR).join(image_path);
+            f(&path, &data).map_err(|source| FontWriteError::Image { path, source })?;
+        }
+        Ok(())
+    }
 
-        if !self.images.is_empty() {
-            let images_dir = path.join(IMAGES_DIR);
-            fs::create_dir(&images_dir) // Only a flat directory.
-                .map_err(|source| FontWriteError::CreateStoreDir {
-                    path: images_dir.clone(),
-                    source,
-                })?;
-            for (image_path, contents) in self.images.iter() {
-                let data = contents.expect("internal error: should have been checked");
-                let destination = images_dir.join(image_path);
-                close_already::fs::write(&destination, &*data)
-                    .map_err(|source| FontWriteError::Image { path: destination, source })?;
-            }
+    /// Returns the metainfo as it is written to disk, stamped with the
+    /// [creator](Font::set_creator). The metainfo of fonts last written by
+    /// another tool is replaced by that of a UFO 3 written by this one.
+    fn meta_for_writing(&self) -> MetaInfo {
+        let creator = self.creator.as_deref().unwrap_or(DEFAULT_METAINFO_CREATOR);
+        if self.meta.creator.as_deref() == Some(creator) {
+            self.meta.clone()
+        } else {
+            MetaInfo { creator: Some(creator.into()), ..Default::default() }
         }
+    }
 
-        Ok(())
+    /// Returns the font lib as it is written to disk: with the object libs of
+    /// the global guidelines added and all keys sorted.
+    fn lib_for_writing(&self) -> Plist {
+        let mut lib = self.lib.clone();
+        let font_object_libs = self.font_info.dump_object_libs();
+        if !font_object_libs.is_empty() {
+            lib.insert(PUBLIC_OBJECT_LIBS_KEY.into(), font_object_libs.into());
+        }
+        crate::util::recursive_sort_plist_keys(&mut lib);
+        lib
+    }
+
+    /// Serializes the font info to the contents of a `fontinfo.plist` file.
+    ///
+    /// The font info is validated first, as when saving.
+    pub fn font_info_to_bytes(&self, options: &WriteOptions) -> Result<Vec<u8>, FontWriteError> {
+        self.font_info.validate().map_err(FontWriteError::InvalidFontInfo)?;
+        self.font_info
+            .to_plist()
+            .map_err(CustomSerializationError::SerializePlist)
+            .and_then(|font_info| write::write_xml_to_bytes(&font_info, options))
+            .map_err(|source| FontWriteError::CustomFile { name: FONTINFO_FILE, source })
+    }
+
+    /// Serializes the groups to the contents of a `groups.plist` file.
+    ///
+    /// The groups are validated first, as when saving.
+    pub fn groups_to_bytes(&self, options: &WriteOptions) -> Result<Vec<u8>, FontWriteError> {
+        validate_groups(&self.groups).map_err(FontWriteError::InvalidGroups)?;
+        write::write_xml_to_bytes(&self.groups, options)
+            .map_err(|source| FontWriteError::CustomFile { name: GROUPS_FILE, source })
+    }
+
+    /// Serializes the kerning to the contents of a `kerning.plist` file.
+    pub fn kerning_to_bytes(&self, options: &WriteOptions) -> Result<Vec<u8>, FontWriteError> {
+        let kerning_serializer = crate::kerning::KerningSerializer { kerning: &self.kerning };
+        write::write_xml_to_bytes(&kerning_serializer, options)
+            .map_err(|source| FontWriteError::CustomFile { name: KERNING_FILE, source })
+    }
+
+    /// Serializes the font lib to the contents of a `lib.plist` file.
+    ///
+    /// As when saving, the libs of global guidelines are included under the
+    /// `public.objectLibs` key, and this fails if the lib already contains
+    /// that key.
+    pub fn lib_to_bytes(&self, options: &WriteOptions) -> Result<Vec<u8>, FontWriteError> {
+        if self.lib.contains_key(PUBLIC_OBJECT_LIBS_KEY) {
+            return Err(FontWriteError::PreexistingPublicObjectLibsKey);
+        }
+        write::write_xml_to_bytes(&self.lib_for_writing(), options)
+            .map_err(|source| FontWriteError::CustomFile { name: LIB_FILE, source })
     }
 
     /// Returns a reference to the default layer.
+    ///
+    /// This is the layer stored in the `glyphs` directory, which every font
+    /// has: loading a UFO without one fails with
+    /// [`FontLoadError::MissingDefaultLayer`]. Its name is usually
+    /// `public.default`.
     pub fn default_layer(&self) -> &Layer {
         self.layers.default_layer()
     }
@@ -566,10 +1030,35 @@ impl Font {
     }
 
     /// Returns an iterator over all layers in this font object.
+    ///
+    /// Layers are yielded in `layercontents.plist` order, starting with the
+    /// default layer.
     pub fn iter_layers(&self) -> impl Iterator<Item = &Layer> {
         self.layers.iter()
     }
 
+    /// Returns an iterator over the glyphs of all layers, along with the name
+    /// of the layer each belongs to.
+    ///
+    /// Layers are visited in the order of [`Font::iter_layers`], and the glyphs of
+    /// each layer in name order.
+    pub fn iter_all_glyphs(&self) -> impl Iterator<Item = (&Name, &Glyph)> {
+        self.layers.iter().flat_map(|layer| layer.iter().map(move |glyph| (layer.name(), glyph)))
+    }
+
+    /// Returns an iterator over the glyphs of all layers, mutably, along with
+    /// the name of the layer each belongs to.
+    ///
+    /// The order is the same as for [`Font::iter_all_glyphs`]. A glyph shared
+    /// with a clone of the font is only copied if it is modified through its
+    /// [`GlyphMut`].
+    pub fn iter_all_glyphs_mut(&mut self) -> impl Iterator<Item = (&Name, GlyphMut<'_>)> {
+        self.layers.iter_mut().flat_map(|layer| {
+            let name = &layer.name;
+            layer.glyphs.values_mut().map(move |glyph| (name, GlyphMut(glyph)))
+        })
+    }
+
     /// Returns an iterator over all the glyph names _in the default layer_.
     pub fn iter_names(&self) -> impl Iterator<Item = Name> + '_ {
         //FIXME: why not &Name here?
@@ -588,79 +1077,808 @@ impl Font {
         self.default_layer_mut().get_glyph_mut(key)
     }
 
-    /// Returns the total number of glyphs _in the default layer_.
-    pub fn glyph_count(&self) -> usize {
-        self.default_layer().len()
+    /// Returns a reference to the glyph with the given name in the layer
+    /// named `layer`, if both exist.
+    pub fn glyph_in_layer(&self, layer: &str, name: &str) -> Option<&Glyph> {
+        self.layers.get(layer)?.get_glyph(name)
     }
 
-    /// Return the font's global guidelines, stored in [`FontInfo`].
-    pub fn guidelines(&self) -> &[Guideline] {
-        self.font_info.guidelines.as_deref().unwrap_or(&[])
+    /// Returns the names of the layers containing a glyph named `glyph_name`,
+    /// in the order of [`Font::iter_layers`].
+    pub fn layers_containing(&self, glyph_name: &str) -> Vec<&Name> {
+        self.layers
+            .iter()
+            .filter(|layer| layer.contains_glyph(glyph_name))
+            .map(Layer::name)
+            .collect()
     }
 
-    /// Returns a mutable reference to the font's global guidelines.
+    /// Adds a new glyph _to the default layer_.
     ///
-    /// These will be created if they do not already exist.
-    pub fn guidelines_mut(&mut self) -> &mut Vec<Guideline> {
-        self.font_info.guidelines.get_or_insert_with(Default::default)
+    /// Returns an error, leaving the layer unchanged, if a glyph with the same
+    /// name already exists; use [`Layer::insert_glyph`] to replace glyphs.
+    pub fn insert_glyph(&mut self, glyph: impl Into<Glyph>) -> Result<(), NamingError> {
+        let glyph = glyph.into();
+        let layer = self.default_layer_mut();
+        if layer.contains_glyph(&glyph.name) {
+            return Err(NamingError::Duplicate(glyph.name.to_string()));
+        }
+        layer.insert_glyph(glyph);
+        Ok(())
     }
-}
 
-fn load_lib(lib_path: &Path) -> Result<plist::Dictionary, FontLoadError> {
-    plist::Value::from_file(lib_path)
-        .map_err(|source| FontLoadError::ParsePlist { name: LIB_FILE, source })?
-        .into_dictionary()
-        .ok_or(FontLoadError::LibFileMustBeDictionary)
-}
+    /// Removes the glyph with the given name _from the default layer_ and
+    /// returns it.
+    ///
+    /// Returns an error if no such glyph exists.
+    pub fn remove_glyph(&mut self, name: &str) -> Result<Glyph, NamingError> {
+        self.default_layer_mut().remove_glyph(name).ok_or_else(|| NamingError::Missing(name.into()))
+    }
 
-fn load_fontinfo(
-    fontinfo_path: &Path,
-    meta: &MetaInfo,
-    lib: &mut plist::Dictionary,
-) -> Result<FontInfo, FontLoadError> {
-    let font_info: FontInfo = FontInfo::from_file(fontinfo_path, meta.format_version, lib)
-        .map_err(FontLoadError::FontInfo)?;
-    Ok(font_info)
-}
+    /// Returns the glyphs _in the default layer_ that are mapped to
+    /// `codepoint`, in glyph name order.
+    ///
+    /// Codepoints should be unique within a layer, so more than one glyph
+    /// points to a conflict in the font's character map. This scans every
+    /// glyph: to look up many codepoints, build a map from
+    /// [`Glyph::codepoints`] instead.
+    pub fn glyphs_for_codepoint(&self, codepoint: char) -> Vec<&Glyph> {
+        self.default_layer().iter().filter(|glyph| glyph.codepoints.contains(codepoint)).collect()
+    }
 
-fn load_groups(groups_path: &Path) -> Result<Groups, FontLoadError> {
-    let groups: Groups = plist::from_file(groups_path)
-        .map_err(|source| FontLoadError::ParsePlist { name: GROUPS_FILE, source })?;
-    validate_groups(&groups).map_err(FontLoadError::InvalidGroups)?;
-    Ok(groups)
-}
+    /// Returns the total number of glyphs _in the default layer_.
+    pub fn glyph_count(&self) -> usize {
+        self.default_layer().len()
+    }
 
-fn load_kerning(kerning_path: &Path) -> Result<Kerning, FontLoadError> {
-    let kerning: Kerning = plist::from_file(kerning_path)
-        .map_err(|source| FontLoadError::ParsePlist { name: KERNING_FILE, source })?;
-    Ok(kerning)
-}
+    /// Checks the layers of this font for structural inconsistencies.
+    ///
+    /// The following are reported, in layer order:
+    ///
+    /// - glyphs in a non-default layer that are missing from the default layer;
+    /// - components whose base glyph is missing from the component's own layer;
+    /// - glyphs in a non-default layer whose advance width differs from the
+    ///   default layer's glyph of the same name. A width of zero is taken to
+    ///   mean "unset" and is not reported.
+    ///
+    /// Returns an empty list if no problems were found.
+    pub fn check_layer_consistency(&self) -> Vec<LayerIssue> {
+        let default_layer = self.default_layer();
+        let mut issues = Vec::new();
+        for layer in self.layers.iter() {
+            let is_default = layer.name() == default_layer.name();
+            for glyph in layer.iter() {
+                if !is_default {
+                    match default_layer.get_glyph(glyph.name()) {
+                        None => issues.push(LayerIssue::MissingFromDefaultLayer {
+                            layer: layer.name().clone(),
+                            glyph: glyph.name().clone(),
+                        }),
+                        Some(default_glyph)
+                            if glyph.width != 0. && glyph.width != default_glyph.width =>
+                        {
+                            issues.push(LayerIssue::AdvanceWidthMismatch {
+                                layer: layer.name().clone(),
+                                glyph: glyph.name().clone(),
+                                default_width: default_glyph.width,
+                                width: glyph.width,
+                            })
+                        }
+                        Some(_) => (),
+                    }
+                }
+                for component in &glyph.components {
+                    if !layer.contains_glyph(&component.base) {
+                        issues.push(LayerIssue::MissingComponentBase {
+                            layer: layer.name().clone(),
+                            glyph: glyph.name().clone(),
+                            base: component.base.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        issues
+    }
 
-fn load_features(features_path: &Path) -> Result<String, FontLoadError> {
-    let features = fs::read_to_string(features_path).map_err(FontLoadError::FeatureFile)?;
-    Ok(features)
-}
+    /// Checks the whole font for problems, returning all that were found.
+    ///
+    /// The following are checked, in order:
+    ///
+    /// - the font info, as when saving;
+    /// - the groups, as when saving;
+    /// - that the kerning groups used in kerning pairs exist;
+    /// - the consistency of the layers, see [`Font::check_layer_consistency`];
+    /// - for each glyph of each layer, that its image file exists, that its
+    ///   identifiers are unique and that its contours are well-formed.
+    ///
+    /// Image files are not checked if the font was loaded without its images,
+    /// see [`DataRequest::images`].
+    ///
+    /// Returns an empty list if no problems were found.
+    pub fn validate(&self) -> Vec<ValidationIssue> {
+        let mut issues = Vec::new();
+        if let Err(error) = self.font_info.validate() {
+            issues.push(ValidationIssue::FontInfo(error));
+        }
+        if let Err(error) = validate_groups(&self.groups) {
+            issues.push(ValidationIssue::Groups(error));
+        }
+        for (first, seconds) in &self.kerning {
+            for second in seconds.keys() {
+                let groups = [(first, "public.kern1."), (second, "public.kern2.")];
+                for (group, prefix) in groups {
+                    if group.starts_with(prefix) && !self.groups.contains_key(group) {
+                        issues.push(ValidationIssue::MissingKerningGroup {
+                            first: first.clone(),
+                            second: second.clone(),
+                            group: group.clone(),
+                        });
+                    }
+                }
+            }
+        }
+        issues.extend(self.check_layer_consistency().into_iter().map(ValidationIssue::Layer));
 
-fn load_layer_set(
-    ufo_path: &Path,
-    meta: &MetaInfo,
-    glyph_names: &NameList,
-    filter: &LayerFilter,
-) -> Result<LayerContents, FontLoadError> {
-    let layercontents_path = ufo_path.join(LAYER_CONTENTS_FILE);
-    if meta.format_version == FormatVersion::V3 && !layercontents_path.exists() {
-        return Err(FontLoadError::MissingLayerContentsFile);
+        let check_images = !self.images_skipped;
+        for layer in self.layers.iter() {
+            for glyph in layer.iter() {
+                if let Some(image) = glyph.image.as_ref().filter(|_| check_images) {
+                    if !self.images.contains_key(image.file_name()) {
+                        issues.push(ValidationIssue::MissingImage {
+                            layer: layer.name().clone(),
+                            glyph: glyph.name().clone(),
+                            file_name: image.file_name().to_owned(),
+                        });
+                    }
+                }
+                for identifier in glyph.duplicate_identifiers() {
+                    issues.push(ValidationIssue::DuplicateIdentifier {
+                        layer: layer.name().clone(),
+                        glyph: glyph.name().clone(),
+                        identifier: identifier.clone(),
+                    });
+                }
+                for (idx, contour) in glyph.contours.iter().enumerate() {
+                    if let Err(error) = validate_points(&contour.points) {
+                        issues.push(ValidationIssue::InvalidContour {
+                            layer: layer.name().clone(),
+                            glyph: glyph.name().clone(),
+                            contour: idx,
+                            error,
+                        });
+                    }
+                }
+            }
+        }
+        issues
     }
-    LayerContents::load(ufo_path, glyph_names, filter)
-}
 
-#[cfg(test)]
-mod tests {
-    use std::ops::Deref;
+    /// Checks whether the glyphs of the default layer can be interpolated with
+    /// those of `other`, as masters of a variable font must.
+    ///
+    /// Glyphs present in both fonts are checked with
+    /// [`Glyph::is_compatible`]; glyphs present in only one are reported as
+    /// well. Print the report for a summary of the problems.
+    pub fn interpolation_report(&self, other: &Font) -> InterpolationReport {
+        let (ours, theirs) = (self.default_layer(), other.default_layer());
+        let mut report = InterpolationReport::default();
+        for glyph in ours.iter() {
+            match theirs.get_glyph(glyph.name()) {
+                Some(other) => {
+                    if let Err(reason) = glyph.is_compatible(other) {
+                        report.incompatible.insert(glyph.name().clone(), reason);
+                    }
+                }
+                None => report.only_in_first.push(glyph.name().clone()),
+            }
+        }
+        report.only_in_second =
+            theirs.glyph_names().filter(|name| !ours.contains_glyph(name)).cloned().collect();
+        report
+    }
+
+    /// Returns the lib keys used anywhere in the font.
+    ///
+    /// This includes the keys of the font lib, the libs of the font's global
+    /// guidelines, the layer libs and, for the glyphs of every layer, the keys
+    /// returned by [`Glyph::lib_keys`].
+    pub fn all_lib_keys(&self) -> BTreeSet<&str> {
+        let mut keys: BTreeSet<&str> = self.lib.keys().map(String::as_str).collect();
+        let guideline_libs = self.guidelines().iter().filter_map(Guideline::lib);
+        let layer_libs = self.layers.iter().map(|layer| &layer.lib);
+        for lib in guideline_libs.chain(layer_libs) {
+            keys.extend(lib.keys().map(String::as_str));
+        }
+        for glyph in self.layers.iter().flat_map(Layer::iter) {
+            keys.extend(glyph.lib_keys());
+        }
+        keys
+    }
+
+    /// Prepares the outlines of the default layer for compiling to a binary
+    /// font: all components are decomposed, then `overlap_filter` is run on
+    /// every glyph that has contours.
+    ///
+    /// Nested components are resolved down to contours, see
+    /// [`Glyph::flatten_components_once`]. Pulled-in objects that carry an
+    /// identifier get a new UUID v4 identifier; see
+    /// [`Font::flatten_for_export_with`] to control how they are generated.
+    ///
+    /// Returns the names of the glyphs that were decomposed or changed by the
+    /// filter, in sorted order. Returns an error, leaving the font unchanged,
+    /// if a component base glyph is missing or components form a cycle.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// # use norad::{Font, Glyph};
+    /// let mut font = Font::load("MyFont.ufo")?;
+    /// let changed = font.flatten_for_export(&mut |glyph: &mut Glyph| {
+    ///     // Remove overlaps with the geometry crate of your choice.
+    ///     false
+    /// })?;
+    /// println!("{} glyphs changed", changed.len());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[cfg(feature = "object-libs")]
+    pub fn flatten_for_export(
+        &mut self,
+        overlap_filter: &mut impl OutlineFilter,
+    ) -> Result<Vec<Name>, ComponentError> {
+        self.flatten_for_export_with(overlap_filter, &mut UuidV4Identifiers)
+    }
+
+    /// Like [`Font::flatten_for_export`], but takes new identifiers from
+    /// `ids`.
+    pub fn flatten_for_export_with(
+        &mut self,
+        overlap_filter: &mut impl OutlineFilter,
+        ids: &mut impl IdentifierSource,
+    ) -> Result<Vec<Name>, ComponentError> {
+        // Decompose against the unmodified layer, so that the font is left
+        // untouched if any glyph fails.
+        let layer = self.default_layer();
+        let mut decomposed = Vec::new();
+        for glyph in layer.iter().filter(|glyph| !glyph.components.is_empty()) {
+            let mut glyph = glyph.clone();
+            while !glyph.components.is_empty() {
+                glyph.flatten_components_once_with(layer, ids)?;
+            }
+            decomposed.push(glyph);
+        }
+
+        let mut changed: BTreeSet<Name> =
+            decomposed.iter().map(|glyph| glyph.name().clone()).collect();
+        let layer = self.default_layer_mut();
+        for glyph in decomposed {
+            layer.insert_glyph(glyph);
+        }
+        // Glyphs without contours have no outline to filter, and are left
+        // shared with clones of the font.
+        for mut glyph in layer.iter_mut().filter(|glyph| !glyph.contours.is_empty()) {
+            if overlap_filter.filter(&mut glyph) {
+                changed.insert(glyph.name().clone());
+            }
+        }
+        Ok(changed.into_iter().collect())
+    }
+
+    /// Returns the identifiers used anywhere in the font: those of the font's
+    /// global guidelines and, for the glyphs of every layer, those returned
+    /// by [`Glyph::identifiers`].
+    ///
+    /// Identifiers only need to be unique within a glyph or within the global
+    /// guidelines, but avoiding all of these when minting new ones is simpler
+    /// and makes user-supplied identifiers collide less.
+    pub fn used_identifiers(&self) -> HashSet<&str> {
+        let mut identifiers: HashSet<&str> = self
+            .guidelines()
+            .iter()
+            .filter_map(Guideline::identifier)
+            .map(|id| id.as_str())
+            .collect();
+        for glyph in self.layers.iter().flat_map(Layer::iter) {
+            identifiers.extend(glyph.identifiers());
+        }
+        identifiers
+    }
+
+    /// Returns an iterator over the kerning pairs as `(first, second, value)`
+    /// triples.
+    ///
+    /// Pairs are sorted by their first, then their second name, which is also
+    /// the order in which they are written to `kerning.plist`.
+    pub fn kerning_entries(&self) -> impl Iterator<Item = (&Name, &Name, f64)> {
+        self.kerning.iter().flat_map(|(first, seconds)| {
+            seconds.iter().map(move |(second, value)| (first, second, *value))
+        })
+    }
+
+    /// Migrates kerning groups to the `public.kern1.` and `public.kern2.`
+    /// prefixes required by UFO v3, based on the side of the kerning pairs
+    /// they are used on.
+    ///
+    /// Groups with the legacy `@MMK_L_` and `@MMK_R_` prefixes, and groups
+    /// without a kerning prefix that are used in kerning, get a copy with the
+    /// new prefix, and the kerning pairs are updated to use it. The old groups
+    /// are kept, as the features may still refer to them. This is the same
+    /// migration that is done when loading a UFO v1 or v2 font, but it can be
+    /// run again without creating further copies.
+    ///
+    /// Returns the names used in kerning pairs that could not be classified,
+    /// in sorted order: names that are neither a glyph in the default layer nor
+    /// a group, and kerning groups used on the wrong side of a pair.
+    pub fn canonicalize_group_names(&mut self) -> Vec<Name> {
+        let glyph_names: NameList = self.default_layer().glyph_names().cloned().collect();
+        let (groups, kerning, unclassified) =
+            upconversion::canonicalize_kerning_groups(&self.groups, &self.kerning, &glyph_names);
+        self.groups = groups;
+        self.kerning = kerning;
+        unclassified
+    }
+
+    /// Returns the font's mark color palette, the named colors offered for
+    /// [`Glyph::set_mark_color`].
+    ///
+    /// The palette is stored in the font lib under the
+    /// `org.linebender.norad.markColorPalette` key, as an array of
+    /// dictionaries with a `name` string and a `color` [color string][colors].
+    /// A missing key is an empty palette.
+    ///
+    /// The key is private to norad: the UFO specification defines no mark
+    /// color palette, and other tools neither read nor write this one.
+    ///
+    /// Returns an error if the lib entry is malformed or contains an invalid
+    /// color string.
+    ///
+    /// [colors]: https://unifiedfontobject.org/versions/ufo3/conventions/#colors
+    pub fn mark_color_palette(&self) -> Result<Vec<PaletteColor>, PaletteError> {
+        let Some(palette) = self.lib.get(MARK_COLOR_PALETTE_KEY) else {
+            return Ok(Vec::new());
+        };
+        let palette = palette.as_array().ok_or(PaletteError::NotAnArray)?;
+        palette
+            .iter()
+            .enumerate()
+            .map(|(index, entry)| {
+                let entry = entry.as_dictionary().ok_or(PaletteError::MalformedEntry(index))?;
+                let get = |key| entry.get(key).and_then(plist::Value::as_string);
+                let (Some(name), Some(color)) = (get(PALETTE_NAME_KEY), get(PALETTE_COLOR_KEY))
+                else {
+                    return Err(PaletteError::MalformedEntry(index));
+                };
+                let color =
+                    color.parse().map_err(|source| PaletteError::InvalidColor { index, source })?;
+                Ok(PaletteColor { name: name.to_owned(), color })
+            })
+            .collect()
+    }
+
+    /// Stores `palette` as the font's mark color palette, removing the lib
+    /// entry if it is empty.
+    ///
+    /// See [`Font::mark_color_palette`] for how the palette is stored.
+    pub fn set_mark_color_palette(&mut self, palette: &[PaletteColor]) {
+        if palette.is_empty() {
+            self.lib.remove(MARK_COLOR_PALETTE_KEY);
+            return;
+        }
+        let palette = palette
+            .iter()
+            .map(|entry| {
+                let mut dict = Plist::new();
+                dict.insert(PALETTE_NAME_KEY.into(), entry.name.clone().into());
+                dict.insert(PALETTE_COLOR_KEY.into(), entry.color.to_rgba_string().into());
+                dict.into()
+            })
+            .collect::<Vec<plist::Value>>();
+        self.lib.insert(MARK_COLOR_PALETTE_KEY.into(), palette.into());
+    }
+
+    /// Returns the OpenType category of the glyph `name`, as stored in the
+    /// [`public.openTypeCategories`] font lib key.
+    ///
+    /// Returns `None` if the key is missing or is not a dictionary, or if it
+    /// has no string entry for the glyph. Categories not defined by the spec
+    /// are returned as [`GlyphCategory::Unknown`].
+    ///
+    /// [`public.openTypeCategories`]: https://unifiedfontobject.org/versions/ufo3/lib.plist/#publicopentypecategories
+    pub fn glyph_category(&self, name: &str) -> Option<GlyphCategory> {
+        let categories = self.lib.get(PUBLIC_OPENTYPE_CATEGORIES_KEY)?.as_dictionary()?;
+        categories.get(name)?.as_string().map(GlyphCategory::from)
+    }
+
+    /// Sets or, if `category` is `None`, removes the OpenType category of the
+    /// glyph `name`.
+    ///
+    /// The categories of other glyphs are left untouched, while a lib value
+    /// that is not a dictionary is discarded. The key is removed when its last
+    /// entry is.
+    pub fn set_glyph_category(&mut self, name: &str, category: Option<GlyphCategory>) {
+        let mut categories = match self.lib.remove(PUBLIC_OPENTYPE_CATEGORIES_KEY) {
+            Some(plist::Value::Dictionary(categories)) => categories,
+            _ => Plist::new(),
+        };
+        match category {
+            Some(category) => categories.insert(name.into(), category.as_str().into()),
+            None => categories.remove(name),
+        };
+        if !categories.is_empty() {
+            self.lib.insert(PUBLIC_OPENTYPE_CATEGORIES_KEY.into(), categories.into());
+        }
+    }
+
+    /// Returns the names of the glyphs of the default layer that end up in the
+    /// compiled font, in sorted order.
+    ///
+    /// These are the glyphs that have codepoints or are listed under the
+    /// `public.glyphOrder` lib key, and, recursively, the base glyphs of their
+    /// components. Glyphs listed under `public.skipExportGlyphs` are left out,
+    /// even when used as components: compilers decompose those components
+    /// instead. Names not in the default layer are ignored.
+    pub fn export_set(&self) -> BTreeSet<Name> {
+        let layer = self.default_layer();
+        let names_under = |key| {
+            let names = self.lib.get(key).and_then(plist::Value::as_array);
+            names.into_iter().flatten().filter_map(plist::Value::as_string)
+        };
+        let skipped: HashSet<&str> = names_under(PUBLIC_SKIP_EXPORT_GLYPHS_KEY).collect();
+        let exported = |glyph: &&Glyph| !skipped.contains(glyph.name().as_str());
+
+        let mut pending: Vec<&Glyph> = layer
+            .iter()
+            .filter(|glyph| !glyph.codepoints.is_empty())
+            .chain(names_under(PUBLIC_GLYPH_ORDER_KEY).filter_map(|name| layer.get_glyph(name)))
+            .filter(exported)
+            .collect();
+        let mut export_set = BTreeSet::new();
+        while let Some(glyph) = pending.pop() {
+            if export_set.insert(glyph.name().clone()) {
+                let bases = glyph.components.iter().filter_map(|c| layer.get_glyph(&c.base));
+                pending.extend(bases.filter(exported));
+            }
+        }
+        export_set
+    }
+
+    /// Gives the glyphs of the default layer that have no advance width, i.e.
+    /// a width of zero, the width `default_width`, see
+    /// [`Glyph::ensure_advance`].
+    ///
+    /// Glyphs whose [category](Font::glyph_category) is
+    /// [`GlyphCategory::Mark`] are skipped, as well as those that
+    /// [`Glyph::ensure_advance`] considers meant to have a zero width. Returns
+    /// the names of the changed glyphs, in sorted order.
+    pub fn ensure_advances(&mut self, default_width: f64) -> Vec<Name> {
+        let marks: HashSet<Name> = self
+            .default_layer()
+            .glyph_names()
+            .filter(|name| self.glyph_category(name) == Some(GlyphCategory::Mark))
+            .cloned()
+            .collect();
+        let mut changed: Vec<Name> = self
+            .default_layer_mut()
+            .iter_mut()
+            .filter(|glyph| !marks.contains(glyph.name()) && glyph.is_missing_advance())
+            .map(|mut glyph| {
+                glyph.ensure_advance(default_width);
+                glyph.name().clone()
+            })
+            .collect();
+        changed.sort_unstable();
+        changed
+    }
+
+    /// Copies the advance width and height of each glyph in the default layer
+    /// to the glyphs of the same name in the other layers, such as the
+    /// masters of an interpolation source.
+    ///
+    /// If `only_missing` is true, only advances of zero are replaced, as in
+    /// [`Glyph::ensure_advance`], keeping those that differ on purpose.
+    /// Returns the layer and glyph names of the changed glyphs, in layer
+    /// order and then sorted by glyph name.
+    pub fn sync_advances_from_default(&mut self, only_missing: bool) -> Vec<(Name, Name)> {
+        let advances: BTreeMap<Name, (f64, f64)> = self
+            .default_layer()
+            .iter()
+            .map(|glyph| (glyph.name().clone(), (glyph.width, glyph.height)))
+            .collect();
+        let synced = |value: f64, target: f64| {
+            if value == target || (only_missing && value != 0.0) {
+                value
+            } else {
+                target
+            }
+        };
+        let mut changed = Vec::new();
+        for layer in self.layers.iter_mut().filter(|layer| !layer.is_default()) {
+            let updates: Vec<(Name, f64, f64)> = layer
+                .iter()
+                .filter_map(|glyph| {
+                    let &(width, height) = advances.get(glyph.name())?;
+                    let (width, height) =
+                        (synced(glyph.width, width), synced(glyph.height, height));
+                    (width != glyph.width || height != glyph.height)
+                        .then(|| (glyph.name().clone(), width, height))
+                })
+                .collect();
+            for (name, width, height) in updates {
+                let glyph = layer.get_glyph_mut(&name).unwrap();
+                (glyph.width, glyph.height) = (width, height);
+                changed.push((layer.name().clone(), name));
+            }
+        }
+        changed
+    }
+
+    /// Returns the units per em, the size of the em square in font units,
+    /// from the [`FontInfo`].
+    pub fn units_per_em(&self) -> Option<f64> {
+        self.font_info.units_per_em.map(|upem| upem.as_f64())
+    }
+
+    /// Sets the units per em in the [`FontInfo`].
+    ///
+    /// Returns an error, leaving the font info unchanged, if `units_per_em`
+    /// is zero, negative or not finite.
+    pub fn set_units_per_em(&mut self, units_per_em: f64) -> Result<(), ExpectedPositiveValue> {
+        if !units_per_em.is_finite() || units_per_em <= 0.0 {
+            return Err(ExpectedPositiveValue);
+        }
+        self.font_info.units_per_em = Some(units_per_em.try_into()?);
+        Ok(())
+    }
+
+    /// Whether the default layer has a `.notdef` glyph, which font compilers
+    /// require.
+    pub fn has_notdef(&self) -> bool {
+        self.default_layer().contains_glyph(NOTDEF)
+    }
+
+    /// Adds a `.notdef` glyph to the default layer if it has none, returning
+    /// whether it was added.
+    ///
+    /// The glyph is drawn as by fontmake: a box outline with a stroke of a
+    /// twentieth of the em, spanning from the descender to the ascender, with
+    /// an advance width of half the em. The units per em default to 1000,
+    /// the ascender to 0.8 and the descender to -0.2 em if they are not set in
+    /// the [`FontInfo`].
+    pub fn ensure_notdef(&mut self) -> bool {
+        if self.has_notdef() {
+            return false;
+        }
+        let upem = self.units_per_em().unwrap_or(1000.0);
+        let ascender = self.font_info.ascender.unwrap_or((upem * 0.8).round());
+        let descender = self.font_info.descender.unwrap_or(-(upem * 0.2).round());
+        let width = (upem * 0.5).round();
+        let stroke = (upem * 0.05).round();
+        let rect = |x_min: f64, y_min: f64, x_max: f64, y_max: f64, clockwise: bool| {
+            let mut corners = [(x_min, y_min), (x_max, y_min), (x_max, y_max), (x_min, y_max)];
+            if clockwise {
+                corners.reverse();
+            }
+            let points = corners
+                .into_iter()
+                .map(|(x, y)| ContourPoint::new(x, y, PointType::Line, false, None, None))
+                .collect();
+            Contour::new(points, None)
+        };
+
+        let mut notdef = Glyph::new(NOTDEF);
+        notdef.width = width;
+        notdef.contours = vec![
+            rect(stroke, descender, width - stroke, ascender, false),
+            rect(2.0 * stroke, descender + stroke, width - 2.0 * stroke, ascender - stroke, true),
+        ];
+        self.default_layer_mut().insert_glyph(notdef);
+        true
+    }
+
+    /// Adds `delta` to the advance width of every glyph in the default layer,
+    /// see [`Glyph::adjust_tracking`].
+    ///
+    /// If `center` is true, the outlines are also moved right by half of
+    /// `delta`, otherwise they stay in place. Components whose base glyph is
+    /// in the default layer keep their offsets, as the base glyph's outline
+    /// already moves. Glyphs in other layers are left alone.
+    pub fn adjust_tracking(&mut self, delta: f64, center: bool) {
+        let layer = self.default_layer_mut();
+        let names: HashSet<Name> = layer.iter().map(|glyph| glyph.name().clone()).collect();
+        for mut glyph in layer.iter_mut() {
+            let offsets: Vec<f64> =
+                glyph.components.iter().map(|component| component.transform.x_offset).collect();
+            glyph.adjust_tracking(delta, center);
+            for (component, offset) in glyph.components.iter_mut().zip(offsets) {
+                if names.contains(&component.base) {
+                    component.transform.x_offset = offset;
+                }
+            }
+        }
+    }
+
+    /// Removes the layers other than the default one that contain no glyphs,
+    /// returning their names in layer order.
+    ///
+    /// See [`LayerContents::remove_empty_layers`].
+    pub fn remove_empty_layers(&mut self) -> Vec<Name> {
+        self.layers.remove_empty_layers()
+    }
+
+    /// Sets the creator written to `metainfo.plist` when saving, identifying
+    /// the tool that last wrote the UFO, e.g. `com.example.fontTool`.
+    ///
+    /// Norad's own identifier, `org.linebender.norad`, is written by default.
+    /// The creator of a loaded font, as found in its `metainfo.plist`, is in
+    /// [`MetaInfo::creator`]; this also updates it.
+    pub fn set_creator(&mut self, creator: &str) {
+        self.creator = Some(creator.into());
+        self.meta.creator = Some(creator.into());
+    }
+
+    /// Return the font's global guidelines, stored in [`FontInfo`].
+    pub fn guidelines(&self) -> &[Guideline] {
+        self.font_info.guidelines()
+    }
+
+    /// Returns a mutable reference to the font's global guidelines.
+    ///
+    /// These will be created if they do not already exist.
+    pub fn guidelines_mut(&mut self) -> &mut Vec<Guideline> {
+        self.font_info.guidelines_mut()
+    }
+
+    /// Appends `guideline` to the font's global guidelines, returning its
+    /// identifier.
+    ///
+    /// A guideline without an identifier is given a new UUID v4 one.
+    ///
+    /// Returns [`FontInfoErrorKind::DuplicateGuidelineIdentifiers`], leaving
+    /// the guidelines unchanged, if another global guideline has the same
+    /// identifier, which would otherwise only be caught when saving.
+    #[cfg(feature = "object-libs")]
+    pub fn add_global_guideline(
+        &mut self,
+        guideline: Guideline,
+    ) -> Result<Identifier, FontInfoErrorKind> {
+        self.add_global_guideline_with(guideline, &mut UuidV4Identifiers)
+    }
+
+    /// Appends `guideline` to the font's global guidelines, like
+    /// [`Font::add_global_guideline`], but takes a missing identifier from
+    /// `ids`.
+    pub fn add_global_guideline_with(
+        &mut self,
+        mut guideline: Guideline,
+        ids: &mut impl IdentifierSource,
+    ) -> Result<Identifier, FontInfoErrorKind> {
+        let identifier = guideline.identifier.get_or_insert_with(|| ids.next_identifier()).clone();
+        if self.guidelines().iter().any(|other| other.identifier() == Some(&identifier)) {
+            return Err(FontInfoErrorKind::DuplicateGuidelineIdentifiers);
+        }
+        self.guidelines_mut().push(guideline);
+        Ok(identifier)
+    }
+}
+
+fn load_lib(lib_path: &Path) -> Result<plist::Dictionary, FontLoadError> {
+    plist::Value::from_file(lib_path)
+        .map_err(|source| FontLoadError::ParsePlist { name: LIB_FILE, source })?
+        .into_dictionary()
+        .ok_or(FontLoadError::LibFileMustBeDictionary)
+}
+
+fn load_fontinfo(
+    fontinfo_path: &Path,
+    meta: &MetaInfo,
+    lib: &mut plist::Dictionary,
+    strict_object_libs: bool,
+) -> Result<FontInfo, FontLoadError> {
+    let font_info: FontInfo =
+        FontInfo::from_file(fontinfo_path, meta.format_version, lib, strict_object_libs)
+            .map_err(FontLoadError::FontInfo)?;
+    Ok(font_info)
+}
+
+fn load_groups(groups_path: &Path) -> Result<Groups, FontLoadError> {
+    let groups: Groups = plist::from_file(groups_path)
+        .map_err(|source| FontLoadError::ParsePlist { name: GROUPS_FILE, source })?;
+    validate_groups(&groups).map_err(FontLoadError::InvalidGroups)?;
+    Ok(groups)
+}
+
+fn load_kerning(kerning_path: &Path) -> Result<Kerning, FontLoadError> {
+    let kerning: Kerning = plist::from_file(kerning_path)
+        .map_err(|source| FontLoadError::ParsePlist { name: KERNING_FILE, source })?;
+    Ok(kerning)
+}
+
+fn load_features(features_path: &Path) -> Result<String, FontLoadError> {
+    let features = fs::read_to_string(features_path).map_err(FontLoadError::FeatureFile)?;
+    Ok(features)
+}
+
+fn load_layer_set(
+    ufo_path: &Path,
+    meta: &MetaInfo,
+    glyph_names: &NameList,
+    request: &DataRequest,
+    skipped: Option<&mut Vec<GlyphLoadError>>,
+) -> Result<LayerContents, FontLoadError> {
+    let layercontents_path = ufo_path.join(LAYER_CONTENTS_FILE);
+    if meta.format_version == FormatVersion::V3 && !layercontents_path.exists() {
+        return Err(FontLoadError::MissingLayerContentsFile);
+    }
+    let strict_object_libs = request.strict_object_libs;
+    LayerContents::load(ufo_path, glyph_names, &request.layers, strict_object_libs, skipped)
+}
+
+/// Loads a single glyph from the UFO at `ufo_path`, without loading the rest
+/// of the font.
+///
+/// Only `layercontents.plist`, the layer's `contents.plist` and the glyph's
+/// `.glif` file are read. If `layer` is `None`, the glyph is loaded from the
+/// default layer.
+///
+/// # Examples
+///
+/// ```no_run
+/// let glyph = norad::load_glyph_from_ufo("path/to/font.ufo", Some("background"), "A")
+///     .expect("failed to load glyph");
+/// ```
+pub fn load_glyph_from_ufo(
+    ufo_path: impl AsRef<Path>,
+    layer: Option<&str>,
+    glyph_name: &str,
+) -> Result<Glyph, FontLoadError> {
+    let ufo_path = ufo_path.as_ref();
+    let metadata = ufo_path.metadata().map_err(FontLoadError::AccessUfoDir)?;
+    if !metadata.is_dir() {
+        return Err(FontLoadError::UfoNotADir);
+    }
+
+    let layer_contents_path = ufo_path.join(LAYER_CONTENTS_FILE);
+    let layers: Vec<(Name, PathBuf)> = if layer_contents_path.exists() {
+        plist::from_file(&layer_contents_path)
+            .map_err(|source| FontLoadError::ParsePlist { name: LAYER_CONTENTS_FILE, source })?
+    } else {
+        vec![(Name::new_raw(DEFAULT_LAYER_NAME), PathBuf::from(DEFAULT_GLYPHS_DIRNAME))]
+    };
+    let (layer_name, layer_path) = match layer {
+        Some(layer) => layers.into_iter().find(|(name, _)| name.as_str() == layer),
+        None => layers.into_iter().find(|(_, path)| path.as_os_str() == DEFAULT_GLYPHS_DIRNAME),
+    }
+    .ok_or_else(|| match layer {
+        Some(layer) => FontLoadError::MissingLayer(layer.into()),
+        None => FontLoadError::MissingDefaultLayer,
+    })?;
+    let layer_path = ufo_path.join(layer_path);
+    let layer_error = |source| FontLoadError::Layer {
+        name: layer_name.to_string(),
+        path: layer_path.clone(),
+        source: Box::new(source),
+    };
+
+    let contents = Layer::load_contents(&layer_path).map_err(layer_error)?;
+    let (name, glyph_path) = contents.get_key_value(glyph_name).ok_or_else(|| {
+        FontLoadError::MissingGlyph { layer: layer_name.to_string(), glyph: glyph_name.into() }
+    })?;
+    let glyph_path = layer_path.join(glyph_path);
+    let mut glyph =
+        Glyph::load_with_names(&glyph_path, &NameList::default(), false).map_err(|source| {
+            layer_error(LayerLoadError::Glyph { name: name.to_string(), path: glyph_path, source })
+        })?;
+    glyph.name = name.clone();
+    Ok(glyph)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ops::Deref;
 
     use tempfile::TempDir;
 
-    use crate::error::LayerLoadError;
+    use crate::error::{FontInfoLoadError, GlifLoadError, LayerLoadError};
 
     use super::*;
 
@@ -704,6 +1922,880 @@ mod tests {
         assert_eq!(font_obj.features, "# this is the feature from lightWide\n");
     }
 
+    #[test]
+    fn layers_and_glyph_names() {
+        let font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        let names: Vec<_> = font.iter_layers().map(|layer| layer.name().as_str()).collect();
+        assert_eq!(names, ["foreground", "background"]);
+
+        let background = font.iter_layers().nth(1).unwrap();
+        let only_in_background: Vec<_> = background
+            .glyph_names()
+            .filter(|name| !font.default_layer().contains_glyph(name))
+            .collect();
+        assert!(only_in_background.is_empty());
+        let glyph_names: Vec<_> = background.glyph_names().map(|n| n.as_str()).collect();
+        let mut sorted = glyph_names.clone();
+        sorted.sort_unstable();
+        assert_eq!(glyph_names, sorted);
+        assert_eq!(glyph_names.len(), background.len());
+    }
+
+    #[test]
+    fn layers_keep_file_order() {
+        let dir = TempDir::new().unwrap();
+        let ufo = dir.path().join("font.ufo");
+        let mut font = Font::new();
+        for name in ["a", "b", "c", "d"] {
+            font.layers.new_layer(name).unwrap();
+        }
+        font.save(&ufo).unwrap();
+
+        // The default layer does not have to come first on disk.
+        let order = ["c", "a", "public.default", "d", "b"];
+        let contents: Vec<_> = order
+            .iter()
+            .map(|&name| {
+                let path = font.layers.get(name).unwrap().path().to_string_lossy().into_owned();
+                vec![name.to_owned(), path]
+            })
+            .collect();
+        plist::to_file_xml(ufo.join("layercontents.plist"), &contents).unwrap();
+
+        let font = Font::load(&ufo).unwrap();
+        let names: Vec<_> = font.iter_layers().map(|layer| layer.name().as_str()).collect();
+        assert_eq!(names, ["public.default", "c", "a", "d", "b"]);
+    }
+
+    #[test]
+    fn layers_containing() {
+        let font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        let names =
+            |glyph| font.layers_containing(glyph).into_iter().map(Name::as_str).collect::<Vec<_>>();
+        assert_eq!(names("S.closed"), ["foreground", "background"]);
+        assert_eq!(names("A"), ["foreground"]);
+        assert!(font.layers_containing("missing").is_empty());
+
+        let glyph = font.glyph_in_layer("background", "S.closed").unwrap();
+        assert!(std::ptr::eq(
+            glyph,
+            font.layers.get("background").unwrap().get_glyph("S.closed").unwrap()
+        ));
+        assert!(font.glyph_in_layer("background", "A").is_none());
+        assert!(font.glyph_in_layer("missing", "A").is_none());
+    }
+
+    #[test]
+    fn iter_all_glyphs() {
+        let mut font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        let count = font.iter_layers().map(Layer::len).sum::<usize>();
+        assert_eq!(font.iter_all_glyphs().count(), count);
+        let (layer, glyph) = font.iter_all_glyphs().next().unwrap();
+        assert_eq!((layer.as_str(), glyph.name().as_str()), ("foreground", "A"));
+        let (layer, _) = font.iter_all_glyphs().last().unwrap();
+        assert_eq!(layer.as_str(), "background");
+
+        let copy = font.clone();
+        for (_, mut glyph) in font.iter_all_glyphs_mut() {
+            if *glyph.name() == "A" {
+                glyph.width = 1.;
+            }
+        }
+        for ((_, glyph), (_, original)) in font.iter_all_glyphs().zip(copy.iter_all_glyphs()) {
+            // Glyphs that were only read are still shared.
+            assert_eq!(std::ptr::eq(glyph, original), *glyph.name() != "A");
+        }
+        assert!(font.iter_all_glyphs().all(|(_, glyph)| *glyph.name() != "A" || glyph.width == 1.));
+    }
+
+    #[test]
+    fn insert_and_remove_glyph() {
+        let mut font = Font::new();
+        font.insert_glyph(Glyph::new("A")).unwrap();
+        assert!(font.get_glyph("A").is_some());
+        assert_eq!(font.default_layer().get_path("A"), Some(Path::new("A_.glif")));
+        assert!(matches!(
+            font.insert_glyph(Glyph::new("A")),
+            Err(NamingError::Duplicate(name)) if name == "A"
+        ));
+
+        font.get_glyph_mut("A").unwrap().width = 500.;
+        assert_eq!(font.remove_glyph("A").unwrap().width, 500.);
+        assert_eq!(font.default_layer().get_path("A"), None);
+        assert!(matches!(
+            font.remove_glyph("A"),
+            Err(NamingError::Missing(name)) if name == "A"
+        ));
+    }
+
+    #[test]
+    fn mark_color_palette() {
+        use crate::Color;
+
+        let mut font = Font::new();
+        assert!(font.mark_color_palette().unwrap().is_empty());
+
+        let palette = [
+            PaletteColor { name: "Done".into(), color: Color::new(0., 1., 0., 1.).unwrap() },
+            PaletteColor { name: "Todo".into(), color: Color::new(1., 0.5, 0., 1.).unwrap() },
+        ];
+        font.set_mark_color_palette(&palette);
+        assert_eq!(font.mark_color_palette().unwrap(), palette);
+
+        let bytes = font.lib_to_bytes(&WriteOptions::default()).unwrap();
+        let lib: Plist = plist::from_bytes(&bytes).unwrap();
+        assert_eq!(lib, font.lib);
+
+        let entry = font.lib.get_mut(MARK_COLOR_PALETTE_KEY).unwrap().as_array_mut().unwrap();
+        entry[1].as_dictionary_mut().unwrap().insert("color".into(), "1,0,0".into());
+        assert!(matches!(
+            font.mark_color_palette(),
+            Err(PaletteError::InvalidColor { index: 1, .. })
+        ));
+
+        font.set_mark_color_palette(&[]);
+        assert!(font.lib.is_empty());
+    }
+
+    #[test]
+    fn add_global_guideline() {
+        use crate::{Line, SequentialIdentifiers};
+
+        let mut font = Font::new();
+        let mut ids = SequentialIdentifiers::new("guide-").unwrap();
+        let line = Line::Horizontal(500.);
+        let first = font
+            .add_global_guideline_with(Guideline::new(line.clone(), None, None, None), &mut ids)
+            .unwrap();
+        assert_eq!(font.guidelines()[0].identifier(), Some(&first));
+
+        let duplicate = Guideline::new(line.clone(), None, None, Some(first.clone()));
+        assert!(matches!(
+            font.add_global_guideline_with(duplicate, &mut ids),
+            Err(FontInfoErrorKind::DuplicateGuidelineIdentifiers)
+        ));
+        assert_eq!(font.guidelines().len(), 1);
+
+        let second = font
+            .add_global_guideline_with(Guideline::new(line, None, None, None), &mut ids)
+            .unwrap();
+        assert_ne!(first, second);
+        assert!(font.font_info.validate().is_ok());
+    }
+
+    #[test]
+    fn glyphs_for_codepoint() {
+        use crate::Codepoints;
+
+        let mut font = Font::new();
+        for (name, codepoints) in [("A", "A"), ("A.alt", "A"), ("B", "Bb"), ("space", "")] {
+            let mut glyph = Glyph::new(name);
+            glyph.codepoints = Codepoints::new(codepoints.chars());
+            font.insert_glyph(glyph).unwrap();
+        }
+        let names = |c| -> Vec<_> {
+            font.glyphs_for_codepoint(c).into_iter().map(|g| g.name().as_str()).collect()
+        };
+        assert_eq!(names('A'), ["A", "A.alt"]);
+        assert_eq!(names('b'), ["B"]);
+        assert!(names(' ').is_empty());
+    }
+
+    #[test]
+    fn glyph_categories() {
+        let mut font = Font::new();
+        assert_eq!(font.glyph_category("a"), None);
+
+        font.set_glyph_category("a", Some(GlyphCategory::Base));
+        font.set_glyph_category("acutecomb", Some(GlyphCategory::Mark));
+        assert_eq!(font.glyph_category("a"), Some(GlyphCategory::Base));
+        assert_eq!(font.glyph_category("acutecomb"), Some(GlyphCategory::Mark));
+        assert_eq!(font.glyph_category("b"), None);
+
+        let categories = font.lib.get_mut(PUBLIC_OPENTYPE_CATEGORIES_KEY).unwrap();
+        let categories = categories.as_dictionary_mut().unwrap();
+        categories.insert("f_f".into(), "ligature".into());
+        categories.insert("x".into(), "bogus".into());
+        categories.insert("y".into(), 1.into());
+        assert_eq!(font.glyph_category("f_f"), Some(GlyphCategory::Ligature));
+        assert_eq!(font.glyph_category("x"), Some(GlyphCategory::from("bogus")));
+        assert!(matches!(
+            font.glyph_category("x"),
+            Some(GlyphCategory::Unknown(category)) if category.as_str() == "bogus"
+        ));
+        assert_eq!(GlyphCategory::from("base"), GlyphCategory::Base);
+        assert_eq!(font.glyph_category("y"), None);
+
+        for name in ["a", "acutecomb", "f_f", "x", "y"] {
+            font.set_glyph_category(name, None);
+        }
+        assert!(font.lib.is_empty());
+    }
+
+    #[test]
+    fn ensure_advances() {
+        use crate::Codepoints;
+
+        let mut font = Font::new();
+        for (name, width) in [("b", 0.0), ("a", 0.0), ("c", 300.0), ("acutecomb", 0.0)] {
+            let mut glyph = Glyph::new(name);
+            glyph.width = width;
+            glyph.codepoints = Codepoints::new(name.chars().take(1));
+            font.insert_glyph(glyph).unwrap();
+        }
+        font.set_glyph_category("acutecomb", Some(GlyphCategory::Mark));
+
+        assert_eq!(font.ensure_advances(500.0), vec![Name::new_raw("a"), Name::new_raw("b")]);
+        assert_eq!(font.get_glyph("a").unwrap().width, 500.0);
+        assert_eq!(font.get_glyph("c").unwrap().width, 300.0);
+        assert_eq!(font.get_glyph("acutecomb").unwrap().width, 0.0);
+        assert!(font.ensure_advances(500.0).is_empty());
+    }
+
+    #[test]
+    fn adjust_tracking() {
+        let mut font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        font.insert_glyph(Glyph::new("empty")).unwrap();
+        let original = font.clone();
+
+        font.adjust_tracking(20.0, false);
+        let (e, before) = (font.get_glyph("E").unwrap(), original.get_glyph("E").unwrap());
+        assert_eq!(e.width, before.width + 20.0);
+        assert!(e.outline_eq(before));
+        assert_eq!(font.get_glyph("empty").unwrap().width, 20.0);
+        assert_eq!(
+            font.layers.get("background").unwrap(),
+            original.layers.get("background").unwrap()
+        );
+
+        let mut font = original.clone();
+        font.adjust_tracking(-20.0, true);
+        let layer = font.default_layer();
+        let (e, before) = (layer.get_glyph("E").unwrap(), original.get_glyph("E").unwrap());
+        assert_eq!(e.width, before.width - 20.0);
+        let original_layer = original.default_layer();
+        let lsb = before.left_side_bearing(original_layer).unwrap();
+        let rsb = before.right_side_bearing(original_layer).unwrap();
+        assert_eq!(e.left_side_bearing(layer), Some(lsb - 10.0));
+        assert_eq!(e.right_side_bearing(layer), Some(rsb - 10.0));
+        assert_eq!(e.anchors[0].x, before.anchors[0].x - 10.0);
+
+        let (aacute, before) =
+            (layer.get_glyph("Aacute").unwrap(), original.get_glyph("Aacute").unwrap());
+        assert_eq!(aacute.components, before.components);
+        let lsb = before.left_side_bearing(original_layer).unwrap();
+        let rsb = before.right_side_bearing(original_layer).unwrap();
+        assert_eq!(aacute.left_side_bearing(layer), Some(lsb - 10.0));
+        assert_eq!(aacute.right_side_bearing(layer), Some(rsb - 10.0));
+    }
+
+    #[test]
+    fn remove_empty_layers() {
+        let mut font = Font::new();
+        font.layers.new_layer("public.background").unwrap();
+        font.layers.new_layer("sketches").unwrap().insert_glyph(Glyph::new("a"));
+
+        assert_eq!(font.remove_empty_layers(), vec![Name::new_raw("public.background")]);
+        assert_eq!(
+            font.layers.names().map(Name::as_str).collect::<Vec<_>>(),
+            ["public.default", "sketches"]
+        );
+        assert!(font.remove_empty_layers().is_empty());
+
+        let dir = tempfile::tempdir().unwrap();
+        font.save(&dir).unwrap();
+        assert!(!dir.path().join("glyphs.public.background").exists());
+        let loaded = Font::load(&dir).unwrap();
+        assert_eq!(loaded.layers.len(), 2);
+    }
+
+    #[test]
+    fn sync_advances_from_default() {
+        let mut font = Font::new();
+        for (name, width) in [("a", 500.0), ("b", 600.0), ("c", 700.0)] {
+            let mut glyph = Glyph::new(name);
+            glyph.width = width;
+            font.insert_glyph(glyph).unwrap();
+        }
+        let bold = font.layers.new_layer("bold").unwrap();
+        for (name, width) in [("a", 0.0), ("b", 650.0), ("c", 700.0), ("extra", 0.0)] {
+            let mut glyph = Glyph::new(name);
+            glyph.width = width;
+            bold.insert_glyph(glyph);
+        }
+        let mut font2 = font.clone();
+        let bold = |font: &Font, name| font.glyph_in_layer("bold", name).unwrap().width;
+
+        let name = |s| Name::new_raw(s);
+        assert_eq!(font.sync_advances_from_default(true), vec![(name("bold"), name("a"))]);
+        assert_eq!((bold(&font, "a"), bold(&font, "b")), (500.0, 650.0));
+
+        let changed = font2.sync_advances_from_default(false);
+        assert_eq!(changed, vec![(name("bold"), name("a")), (name("bold"), name("b"))]);
+        assert_eq!((bold(&font2, "a"), bold(&font2, "b")), (500.0, 600.0));
+        assert_eq!(bold(&font2, "extra"), 0.0);
+        assert!(font2.sync_advances_from_default(false).is_empty());
+    }
+
+    #[test]
+    fn load_lenient() {
+        let dir = tempfile::tempdir().unwrap();
+        let original = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        original.save(&dir).unwrap();
+        fs::write(dir.path().join("glyphs/A_.glif"), "<glyph name=").unwrap();
+        fs::write(dir.path().join("glyphs.background/S_.closed.glif"), "").unwrap();
+        assert!(Font::load(&dir).is_err());
+
+        let (font, skipped) = Font::load_lenient(&dir).unwrap();
+        let skipped: Vec<_> =
+            skipped.iter().map(|e| (e.layer.as_str(), e.name.as_str(), e.path.clone())).collect();
+        assert_eq!(
+            skipped,
+            [
+                ("foreground", "A", dir.path().join("glyphs/A_.glif")),
+                ("background", "S.closed", dir.path().join("glyphs.background/S_.closed.glif")),
+            ]
+        );
+        assert!(font.get_glyph("A").is_none());
+        assert_eq!(font.glyph_count(), original.glyph_count() - 1);
+        assert!(font.layers.get("background").unwrap().is_empty());
+        assert_eq!(font.get_glyph("B"), original.get_glyph("B"));
+
+        // The skipped glyphs are gone from the contents, so the font saves.
+        let saved = tempfile::tempdir().unwrap();
+        font.save(&saved).unwrap();
+        assert!(!saved.path().join("glyphs/A_.glif").exists());
+        assert_eq!(Font::load(&saved).unwrap().glyph_count(), font.glyph_count());
+
+        let request = DataRequest::none().layers(true);
+        let (font, skipped) = Font::load_lenient_requested_data(&dir, request).unwrap();
+        assert_eq!(skipped.len(), 2);
+        assert!(font.lib.is_empty());
+        assert_eq!(font.glyph_count(), original.glyph_count() - 1);
+    }
+
+    #[test]
+    fn units_per_em() {
+        let mut font = Font::new();
+        assert_eq!(font.units_per_em(), None);
+        font.set_units_per_em(2048.0).unwrap();
+        assert_eq!(font.units_per_em(), Some(2048.0));
+
+        for invalid in [0.0, -0.0, -1000.0, f64::NAN, f64::INFINITY] {
+            assert!(font.set_units_per_em(invalid).is_err(), "{invalid}");
+        }
+        assert_eq!(font.units_per_em(), Some(2048.0));
+        assert_eq!(
+            Font::load("testdata/MutatorSansLightWide.ufo").unwrap().units_per_em(),
+            Some(1000.0)
+        );
+    }
+
+    #[test]
+    fn ensure_notdef() {
+        let mut font = Font::new();
+        font.font_info.units_per_em = Some(2000u32.into());
+        font.font_info.ascender = Some(1600.0);
+        font.font_info.descender = Some(-400.0);
+        assert!(!font.has_notdef());
+        assert!(font.ensure_notdef());
+        assert!(font.has_notdef());
+
+        let notdef = font.get_glyph(".notdef").unwrap();
+        assert_eq!(notdef.width, 1000.0);
+        assert_eq!(notdef.bounds(font.default_layer()), Some((100.0, -400.0, 900.0, 1600.0)));
+        assert_eq!(notdef.contours.len(), 2);
+        assert!(notdef.has_consistent_directions(font.default_layer()));
+
+        font.get_glyph_mut(".notdef").unwrap().width = 600.0;
+        assert!(!font.ensure_notdef());
+        assert_eq!(font.get_glyph(".notdef").unwrap().width, 600.0);
+
+        let mut font = Font::new();
+        font.ensure_notdef();
+        let notdef = font.get_glyph(".notdef").unwrap();
+        assert_eq!(notdef.bounds(font.default_layer()), Some((50.0, -200.0, 450.0, 800.0)));
+    }
+
+    #[test]
+    fn interpolation_report() {
+        let light = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        assert!(light.interpolation_report(&light).is_compatible());
+        assert_eq!(light.interpolation_report(&light).to_string(), "fonts are compatible\n");
+
+        let mut bold = light.clone();
+        bold.remove_glyph("B").unwrap();
+        bold.insert_glyph(Glyph::new("Z.alt")).unwrap();
+        bold.get_glyph_mut("A").unwrap().contours.pop();
+        let report = light.interpolation_report(&bold);
+        assert!(!report.is_compatible());
+        assert_eq!(report.only_in_first, [Name::new_raw("B")]);
+        assert_eq!(report.only_in_second, [Name::new_raw("Z.alt")]);
+        assert_eq!(report.incompatible.len(), 1);
+        assert_eq!(
+            report.to_string(),
+            "glyph 'B' is missing from the second font\n\
+             glyph 'Z.alt' is missing from the first font\n\
+             glyph 'A': glyphs have 4 and 3 contours\n"
+        );
+    }
+
+    #[test]
+    fn kerning_entries() {
+        let font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        let entries: Vec<_> = font.kerning_entries().collect();
+        assert_eq!(entries.len(), font.kerning.values().map(|pairs| pairs.len()).sum::<usize>());
+        assert!(entries.windows(2).all(|w| (w[0].0, w[0].1) < (w[1].0, w[1].1)));
+    }
+
+    #[test]
+    fn canonicalize_group_names() {
+        let mut font = Font::new();
+        for name in ["A", "V"] {
+            font.default_layer_mut().insert_glyph(Glyph::new(name));
+        }
+        let group = |name: &str| (Name::new_raw(name), vec![Name::new_raw("A")]);
+        font.groups.extend([group("@MMK_L_A"), group("@MMK_R_V"), group("public.kern1.O")]);
+        font.kerning.insert(
+            Name::new_raw("@MMK_L_A"),
+            [("@MMK_R_V", -10.), ("V", -20.), ("public.kern1.O", 5.), ("missing", 1.)]
+                .into_iter()
+                .map(|(name, value)| (Name::new_raw(name), value))
+                .collect(),
+        );
+
+        assert_eq!(font.canonicalize_group_names(), ["missing", "public.kern1.O"]);
+        let names: Vec<_> = font.groups.keys().map(Name::as_str).collect();
+        assert_eq!(
+            names,
+            ["@MMK_L_A", "@MMK_R_V", "public.kern1.A", "public.kern1.O", "public.kern2.V"]
+        );
+        let pairs = &font.kerning["public.kern1.A"];
+        assert_eq!(pairs.get("public.kern2.V"), Some(&-10.));
+        assert_eq!(pairs.get("V"), Some(&-20.));
+
+        let (groups, kerning) = (font.groups.clone(), font.kerning.clone());
+        font.canonicalize_group_names();
+        assert_eq!((font.groups, font.kerning), (groups, kerning));
+    }
+
+    #[test]
+    fn all_lib_keys() {
+        let mut font = Font::new();
+        font.lib.insert("com.example.font".into(), true.into());
+        font.default_layer_mut().lib.insert("com.example.layer".into(), true.into());
+        let mut glyph = Glyph::new("a");
+        glyph.lib.insert("com.example.glyph".into(), true.into());
+        font.default_layer_mut().insert_glyph(glyph);
+        let background = font.layers.new_layer("background").unwrap();
+        let mut glyph = Glyph::new("a");
+        glyph.lib.insert("com.example.font".into(), true.into());
+        background.insert_glyph(glyph);
+
+        assert_eq!(
+            font.all_lib_keys().into_iter().collect::<Vec<_>>(),
+            ["com.example.font", "com.example.glyph", "com.example.layer"]
+        );
+    }
+
+    #[test]
+    fn flatten_for_export() {
+        use crate::glyph::tests::contour;
+        use crate::{AffineTransform, Component, PointType, SequentialIdentifiers};
+
+        let component =
+            |base| Component::new(Name::new_raw(base), AffineTransform::default(), None);
+        let mut font = Font::new();
+        let mut a = Glyph::new("A");
+        a.contours.push(contour(&[(0., 0., PointType::Line), (10., 0., PointType::Line)]));
+        let mut acute = Glyph::new("acute");
+        acute.contours.push(contour(&[(20., 0., PointType::Line)]));
+        let mut acute_alias = Glyph::new("acute.alt");
+        acute_alias.components.push(component("acute"));
+        let mut aacute = Glyph::new("Aacute");
+        aacute.components.extend([component("A"), component("acute.alt")]);
+        for glyph in [a, acute, acute_alias, aacute] {
+            font.default_layer_mut().insert_glyph(glyph);
+        }
+
+        // Drop single-point contours.
+        let mut filter = |glyph: &mut Glyph| {
+            let before = glyph.contours.len();
+            glyph.contours.retain(|contour| contour.points.len() > 1);
+            glyph.contours.len() != before
+        };
+        let mut ids = SequentialIdentifiers::new("id-").unwrap();
+        let changed = font.flatten_for_export_with(&mut filter, &mut ids).unwrap();
+        assert_eq!(changed, ["Aacute", "acute", "acute.alt"]);
+        let aacute = font.get_glyph("Aacute").unwrap();
+        assert!(aacute.components.is_empty());
+        assert_eq!(aacute.contours.len(), 1);
+
+        let mut cycle = Glyph::new("cycle");
+        cycle.components.push(component("cycle"));
+        font.default_layer_mut().insert_glyph(cycle);
+        let mut font_with_cycle = font.clone();
+        let mut filter = |_: &mut Glyph| true;
+        assert!(matches!(
+            font_with_cycle.flatten_for_export_with(&mut filter, &mut ids),
+            Err(ComponentError::Cycle(_))
+        ));
+        assert_eq!(font_with_cycle.default_layer(), font.default_layer());
+    }
+
+    #[test]
+    fn used_identifiers() {
+        use crate::{Anchor, Contour, ContourPoint, Line, PointType};
+
+        let id = |id| Identifier::new(id).unwrap();
+        let mut font = Font::new();
+        font.guidelines_mut().push(Guideline::new(Line::Vertical(0.), None, None, Some(id("g"))));
+        let mut glyph = Glyph::new("a");
+        glyph.anchors.push(Anchor::new(0., 0., None, None, Some(id("anchor"))));
+        let point = ContourPoint::new(0., 0., PointType::Move, false, None, Some(id("point")));
+        glyph.contours.push(Contour::new(vec![point], Some(id("contour"))));
+        font.default_layer_mut().insert_glyph(glyph);
+        let background = font.layers.new_layer("background").unwrap();
+        let mut glyph = Glyph::new("a");
+        glyph.anchors.push(Anchor::new(0., 0., None, None, Some(id("anchor"))));
+        glyph.anchors.push(Anchor::new(0., 0., None, None, None));
+        background.insert_glyph(glyph);
+
+        let mut identifiers: Vec<_> = font.used_identifiers().into_iter().collect();
+        identifiers.sort_unstable();
+        assert_eq!(identifiers, ["anchor", "contour", "g", "point"]);
+    }
+
+    #[test]
+    fn save_plan() {
+        let dir = TempDir::new().unwrap();
+        let ufo = dir.path().join("font.ufo");
+        for source in ["dataimagetest.ufo", "MutatorSansLightWide.ufo"] {
+            let font = Font::load(Path::new("testdata").join(source)).unwrap();
+            let plan = font.save_plan(&ufo).unwrap();
+            assert!(plan.overwritten.is_empty() && plan.unchanged.is_empty());
+            assert!(plan.deleted.is_empty());
+
+            // The plan lists exactly the files saving writes.
+            font.save(&ufo).unwrap();
+            let written: Vec<_> = list_files(&ufo).unwrap().into_iter().collect();
+            assert_eq!(written, plan.created, "{source}");
+            let plan = font.save_plan(&ufo).unwrap();
+            assert!(!plan.has_changes());
+            assert_eq!(plan.unchanged, written);
+            fs::remove_dir_all(&ufo).unwrap();
+        }
+
+        let mut font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        font.save(&ufo).unwrap();
+        font.default_layer_mut().get_glyph_mut("A").unwrap().width += 10.;
+        font.layers.remove("background");
+        fs::write(ufo.join("notes.txt"), "").unwrap();
+        let plan = font.save_plan(&ufo).unwrap();
+        assert!(plan.created.is_empty());
+        assert_eq!(plan.overwritten, [Path::new("glyphs/A_.glif"), Path::new(LAYER_CONTENTS_FILE)]);
+        assert!(plan.deleted.contains(&PathBuf::from("notes.txt")));
+        assert!(plan.deleted.contains(&PathBuf::from("glyphs.background/S_.closed.glif")));
+        assert!(plan.has_changes());
+        assert!(ufo.join("notes.txt").exists());
+    }
+
+    #[test]
+    fn save_only_changes() {
+        use std::time::{Duration, SystemTime};
+
+        let dir = TempDir::new().unwrap();
+        let ufo = dir.path().join("font.ufo");
+        let plain = dir.path().join("plain.ufo");
+        let mut font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        let options = WriteOptions::default().only_write_changes(true);
+        // Without an existing UFO, everything is written.
+        font.save_with_options(&ufo, &options).unwrap();
+        font.save(&plain).unwrap();
+        assert_eq!(list_files(&ufo).unwrap(), list_files(&plain).unwrap());
+
+        let past = SystemTime::UNIX_EPOCH + Duration::from_secs(1_000_000);
+        for file in list_files(&ufo).unwrap() {
+            fs::File::options()
+                .write(true)
+                .open(ufo.join(file))
+                .unwrap()
+                .set_modified(past)
+                .unwrap();
+        }
+        let modified = |file: &str| fs::metadata(ufo.join(file)).unwrap().modified().unwrap();
+
+        font.default_layer_mut().get_glyph_mut("A").unwrap().width += 10.;
+        font.layers.remove("background");
+        fs::write(ufo.join("notes.txt"), "").unwrap();
+        let plan = font.save_plan_with_options(&ufo, &options).unwrap();
+        font.save_with_options(&ufo, &options).unwrap();
+        font.save(&plain).unwrap();
+
+        assert_eq!(Font::load(&ufo).unwrap(), Font::load(&plain).unwrap());
+        assert_eq!(list_files(&ufo).unwrap(), list_files(&plain).unwrap());
+        assert!(!ufo.join("notes.txt").exists());
+        assert!(!ufo.join("glyphs.background").exists());
+        assert_ne!(modified("glyphs/A_.glif"), past);
+        assert_eq!(modified("glyphs/B_.glif"), past);
+        assert_eq!(modified("fontinfo.plist"), past);
+        assert!(!font.save_plan(&ufo).unwrap().has_changes());
+        let rewritten = list_files(&ufo)
+            .unwrap()
+            .into_iter()
+            .filter(|file| fs::metadata(ufo.join(file)).unwrap().modified().unwrap() != past);
+        assert!(rewritten.eq(plan.created.into_iter().chain(plan.overwritten)));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn save_only_changes_replaces_symlinks() {
+        use std::os::unix::fs::symlink;
+
+        let dir = TempDir::new().unwrap();
+        let ufo = dir.path().join("font.ufo");
+        let font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        font.save(&ufo).unwrap();
+
+        let outside = dir.path().join("outside");
+        fs::create_dir(&outside).unwrap();
+        fs::write(outside.join("keep.txt"), "keep").unwrap();
+        let glyph = ufo.join("glyphs/A_.glif");
+        fs::rename(&glyph, outside.join("A_.glif")).unwrap();
+        symlink(outside.join("A_.glif"), &glyph).unwrap();
+        symlink(&outside, ufo.join("linked")).unwrap();
+        assert!(list_files(&ufo).unwrap().contains(Path::new("linked")));
+        let plan = font.save_plan(&ufo).unwrap();
+        assert!(plan.overwritten.contains(&PathBuf::from("glyphs/A_.glif")));
+        assert_eq!(plan.deleted, [Path::new("linked")]);
+
+        let options = WriteOptions::default().only_write_changes(true);
+        font.save_with_options(&ufo, &options).unwrap();
+        assert!(!glyph.is_symlink() && glyph.is_file());
+        assert!(!ufo.join("linked").exists());
+        assert!(outside.join("keep.txt").exists());
+        assert!(outside.join("A_.glif").exists());
+    }
+
+    #[test]
+    fn export_set() {
+        use crate::{AffineTransform, Component};
+
+        let mut font = Font::new();
+        let glyph = |name: &str, codepoint: Option<char>, bases: &[&str]| {
+            let mut glyph = Glyph::new(name);
+            glyph.codepoints.set(codepoint);
+            for base in bases {
+                let transform = AffineTransform::default();
+                glyph.components.push(Component::new(Name::new_raw(base), transform, None));
+            }
+            glyph
+        };
+        let layer = font.default_layer_mut();
+        layer.insert_glyph(glyph("A", Some('A'), &[]));
+        layer.insert_glyph(glyph("Aacute", Some('Á'), &["A", "acute.cap"]));
+        layer.insert_glyph(glyph("acute.cap", None, &["acute.base"]));
+        layer.insert_glyph(glyph("acute.base", None, &[]));
+        layer.insert_glyph(glyph("Adieresis", Some('Ä'), &["A", "dieresis.draft"]));
+        layer.insert_glyph(glyph("dieresis.draft", None, &[]));
+        layer.insert_glyph(glyph("space", None, &[]));
+        layer.insert_glyph(glyph("unused", None, &[]));
+        layer.insert_glyph(glyph("B", Some('B'), &[]));
+
+        let names = |export_set: BTreeSet<Name>| {
+            export_set.iter().map(|name| name.to_string()).collect::<Vec<_>>()
+        };
+        let expected =
+            ["A", "Aacute", "Adieresis", "B", "acute.base", "acute.cap", "dieresis.draft"];
+        assert_eq!(names(font.export_set()), expected);
+
+        let array =
+            |names: &[&str]| plist::Value::Array(names.iter().map(|&name| name.into()).collect());
+        font.lib.insert("public.glyphOrder".into(), array(&["space", "missing"]));
+        font.lib.insert("public.skipExportGlyphs".into(), array(&["B", "dieresis.draft"]));
+        let expected = ["A", "Aacute", "Adieresis", "acute.base", "acute.cap", "space"];
+        assert_eq!(names(font.export_set()), expected);
+    }
+
+    #[test]
+    fn validate() {
+        use crate::glyph::tests::contour;
+        use crate::{AffineTransform, Anchor, Component, Image, PointType};
+
+        let font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        let issues = font.validate();
+        assert!(
+            issues.iter().all(|issue| matches!(issue, ValidationIssue::Layer(_))),
+            "{issues:?}"
+        );
+
+        let mut font = Font::new();
+        font.font_info.open_type_head_created = Some("yesterday".into());
+        font.groups.insert(Name::new_raw("public.kern1.a"), vec![Name::new_raw("a")]);
+        font.groups.insert(Name::new_raw("public.kern1.b"), vec![Name::new_raw("a")]);
+        font.kerning.insert(
+            Name::new_raw("public.kern1.a"),
+            [(Name::new_raw("public.kern2.missing"), 10.)].into(),
+        );
+        let mut glyph = Glyph::new("a");
+        glyph.image = Some(Image::new("a.png".into(), None, AffineTransform::default()).unwrap());
+        let id = Identifier::new_raw("abc");
+        glyph.anchors.push(Anchor::new(0., 0., None, None, Some(id.clone())));
+        glyph.anchors.push(Anchor::new(0., 0., None, None, Some(id.clone())));
+        glyph.anchors.push(Anchor::new(0., 0., None, None, Some(id)));
+        glyph.contours.push(contour(&[(0., 0., PointType::Line)]));
+        glyph.contours.push(contour(&[(0., 0., PointType::Line), (0., 0., PointType::Move)]));
+        glyph.components.push(Component::new(Name::new_raw("b"), AffineTransform::default(), None));
+        font.default_layer_mut().insert_glyph(glyph);
+
+        let issues = font.validate();
+        assert_eq!(issues.len(), 7, "{issues:?}");
+        assert!(matches!(issues[0], ValidationIssue::FontInfo(_)));
+        assert!(matches!(issues[1], ValidationIssue::Groups(_)));
+        assert!(matches!(
+            &issues[2],
+            ValidationIssue::MissingKerningGroup { group, .. } if *group == "public.kern2.missing"
+        ));
+        assert!(matches!(
+            &issues[3],
+            ValidationIssue::Layer(LayerIssue::MissingComponentBase { base, .. }) if *base == "b"
+        ));
+        assert!(matches!(
+            &issues[4],
+            ValidationIssue::MissingImage { glyph, file_name, .. }
+                if *glyph == "a" && file_name == Path::new("a.png")
+        ));
+        assert!(matches!(
+            &issues[5],
+            ValidationIssue::DuplicateIdentifier { identifier, .. } if identifier.as_str() == "abc"
+        ));
+        assert!(matches!(issues[6], ValidationIssue::InvalidContour { contour: 1, .. }));
+
+        // Images are not checked if they were not loaded.
+        font.images_skipped = true;
+        let issues = font.validate();
+        assert_eq!(issues.len(), 6, "{issues:?}");
+        assert!(!issues.iter().any(|issue| matches!(issue, ValidationIssue::MissingImage { .. })));
+    }
+
+    #[test]
+    fn layer_consistency() {
+        use crate::{AffineTransform, Component};
+
+        let mut font = Font::new();
+        let mut a = Glyph::new("A");
+        a.width = 500.;
+        font.default_layer_mut().insert_glyph(a);
+
+        let background = font.layers.new_layer("background").unwrap();
+        let mut a = Glyph::new("A");
+        a.width = 600.;
+        a.components.push(Component::new(Name::new_raw("B"), AffineTransform::default(), None));
+        background.insert_glyph(a);
+        background.insert_glyph(Glyph::new("C"));
+
+        let background = Name::new_raw("background");
+        assert_eq!(
+            font.check_layer_consistency(),
+            vec![
+                LayerIssue::AdvanceWidthMismatch {
+                    layer: background.clone(),
+                    glyph: Name::new_raw("A"),
+                    default_width: 500.,
+                    width: 600.,
+                },
+                LayerIssue::MissingComponentBase {
+                    layer: background.clone(),
+                    glyph: Name::new_raw("A"),
+                    base: Name::new_raw("B"),
+                },
+                LayerIssue::MissingFromDefaultLayer {
+                    layer: background,
+                    glyph: Name::new_raw("C")
+                },
+            ]
+        );
+
+        let font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        assert_eq!(
+            font.check_layer_consistency(),
+            vec![LayerIssue::AdvanceWidthMismatch {
+                layer: Name::new_raw("background"),
+                glyph: Name::new_raw("S.closed"),
+                default_width: 1175.,
+                width: 1185.,
+            }]
+        );
+    }
+
+    #[test]
+    fn load_single_glyph() {
+        let path = "testdata/MutatorSansLightWide.ufo";
+        let font = Font::load(path).unwrap();
+
+        let glyph = load_glyph_from_ufo(path, None, "A").unwrap();
+        assert_eq!(&glyph, font.get_glyph("A").unwrap());
+        let glyph = load_glyph_from_ufo(path, Some("background"), "S.closed").unwrap();
+        assert_eq!(&glyph, font.layers.get("background").unwrap().get_glyph("S.closed").unwrap());
+
+        assert!(matches!(
+            load_glyph_from_ufo(path, Some("nope"), "A"),
+            Err(FontLoadError::MissingLayer(layer)) if layer == "nope"
+        ));
+        assert!(matches!(
+            load_glyph_from_ufo(path, None, "nope"),
+            Err(FontLoadError::MissingGlyph { layer, glyph }) if layer == "foreground" && glyph == "nope"
+        ));
+    }
+
+    #[test]
+    fn serialize_files_to_bytes() {
+        let font = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        let dir = TempDir::new().unwrap();
+        let options = WriteOptions::default();
+        font.save_with_options(&dir, &options).unwrap();
+
+        for (name, bytes) in [
+            (FONTINFO_FILE, font.font_info_to_bytes(&options).unwrap()),
+            (GROUPS_FILE, font.groups_to_bytes(&options).unwrap()),
+            (KERNING_FILE, font.kerning_to_bytes(&options).unwrap()),
+            (LIB_FILE, font.lib_to_bytes(&options).unwrap()),
+        ] {
+            assert_eq!(bytes, fs::read(dir.path().join(name)).unwrap(), "{name} differs");
+        }
+
+        let options = WriteOptions::default().quote_char(crate::QuoteChar::Single);
+        let bytes = font.kerning_to_bytes(&options).unwrap();
+        assert!(bytes.starts_with(b"<?xml version='1.0' encoding='UTF-8'?>\n"));
+    }
+
+    #[test]
+    fn plist_parse_errors_report_location() {
+        let dir = TempDir::new().unwrap();
+        Font::new().save(&dir).unwrap();
+        fs::write(
+            dir.path().join(FONTINFO_FILE),
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<plist version=\"1.0\">\n<dict>\n<key>unitsPerEm</key>\n<integer>1000</string>\n</dict>\n</plist>\n",
+        )
+        .unwrap();
+
+        let err = Font::load(&dir).unwrap_err();
+        assert!(matches!(err, FontLoadError::FontInfo(FontInfoLoadError::ParsePlist(_))));
+        // Each error in the chain adds its own context, the innermost one the
+        // location.
+        let mut messages = vec![err.to_string()];
+        let mut source = std::error::Error::source(&err);
+        while let Some(err) = source {
+            messages.push(err.to_string());
+            source = err.source();
+        }
+        assert_eq!(
+            messages[..2],
+            ["failed to load font info data", "failed to parse fontinfo.plist file"]
+        );
+        assert!(messages[2].contains("offset"), "{messages:?}");
+    }
+
     #[test]
     fn load_save_feature_file_line_endings() {
         let font_obj = Font::load("testdata/lineendings/Tester-LineEndings.ufo").unwrap();
@@ -781,6 +2873,43 @@ mod tests {
         assert!(font_obj.features.is_empty());
     }
 
+    #[test]
+    fn strict_object_libs() {
+        let dir = TempDir::new().unwrap();
+        let font = Font::load("testdata/identifiers.ufo").unwrap();
+        font.save(&dir).unwrap();
+        let strict = || DataRequest::default().strict_object_libs(true);
+        assert!(Font::load_requested_data(&dir, strict()).is_ok());
+
+        // Delete an anchor from the file, leaving its lib behind.
+        let glif_path = dir.path().join("glyphs/test.glif");
+        let glif = fs::read_to_string(&glif_path).unwrap();
+        let anchor =
+            glif.lines().find(|line| line.contains("<anchor") && line.contains("90b7eb80"));
+        let anchor = anchor.unwrap();
+        fs::write(&glif_path, glif.replace(anchor, "")).unwrap();
+        let loaded = Font::load(&dir).unwrap();
+        assert_eq!(loaded.get_glyph("test").unwrap().anchors.len(), 1);
+        let err = Font::load_requested_data(&dir, strict()).unwrap_err();
+        let FontLoadError::Layer { source, .. } = err else { panic!("unexpected error {err:?}") };
+        assert!(matches!(
+            *source,
+            LayerLoadError::Glyph { source: GlifLoadError::OrphanedObjectLib(_), .. }
+        ));
+
+        font.save(&dir).unwrap();
+        let mut lib: Plist = plist::from_file(dir.path().join(LIB_FILE)).unwrap();
+        let object_libs = lib.get_mut(PUBLIC_OBJECT_LIBS_KEY).unwrap().as_dictionary_mut().unwrap();
+        object_libs.insert("deleted".into(), plist::Dictionary::new().into());
+        plist::to_file_xml(dir.path().join(LIB_FILE), &lib).unwrap();
+        assert!(!Font::load(&dir).unwrap().lib.contains_key(PUBLIC_OBJECT_LIBS_KEY));
+        let err = Font::load_requested_data(&dir, strict()).unwrap_err();
+        assert!(matches!(
+            err,
+            FontLoadError::FontInfo(FontInfoLoadError::OrphanedObjectLib(id)) if id == "deleted"
+        ));
+    }
+
     #[test]
     fn upconvert_ufov1_robofab_data() {
         let path = "testdata/fontinfotest_v1.ufo";
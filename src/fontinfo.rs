@@ -3,6 +3,7 @@
 //! [`fontinfo.plist`]: https://unifiedfontobject.org/versions/ufo3/fontinfo.plist/
 
 use std::path::Path;
+use std::sync::OnceLock;
 use std::{collections::HashSet, convert::TryFrom, ops::Deref};
 
 use serde::de::Deserializer;
@@ -334,6 +335,10 @@ pub struct FontInfo {
     pub x_height: Option<IntegerOrFloat>,
     /// Year that the font was created (year).
     pub year: Option<Integer>,
+    /// Keys not defined by the specification, preserved as-is. Not part of
+    /// the serde representation, see [`FontInfo::extra`].
+    #[serde(skip)]
+    extra: Plist,
 }
 
 /// The contents of the [`fontinfo.plist`][] file specified for UFO version 2. Its only purpose is
@@ -503,14 +508,29 @@ impl FontInfo {
         path: P,
         format_version: FormatVersion,
         lib: &mut Plist,
+        strict_object_libs: bool,
     ) -> Result<Self, FontInfoLoadError> {
         let path = path.as_ref();
         match format_version {
             FormatVersion::V3 => {
-                let mut fontinfo: FontInfo =
+                let mut dict: Plist =
                     plist::from_file(path).map_err(FontInfoLoadError::ParsePlist)?;
+                let known_keys = FontInfo::keys();
+                let extra_keys: Vec<String> = dict
+                    .keys()
+                    .filter(|key| !known_keys.contains(&key.as_str()))
+                    .cloned()
+                    .collect();
+                let mut extra = Plist::new();
+                for key in extra_keys {
+                    let value = dict.remove(&key).unwrap();
+                    extra.insert(key, value);
+                }
+                let mut fontinfo: FontInfo = plist::from_value(&plist::Value::Dictionary(dict))
+                    .map_err(FontInfoLoadError::ParsePlist)?;
+                fontinfo.extra = extra;
                 fontinfo.validate().map_err(FontInfoLoadError::InvalidData)?;
-                fontinfo.load_object_libs(lib)?;
+                fontinfo.load_object_libs(lib, strict_object_libs)?;
                 Ok(fontinfo)
             }
             FormatVersion::V2 => {
@@ -792,6 +812,137 @@ impl FontInfo {
         }
     }
 
+    /// Returns the keys of the `fontinfo.plist` file that are not defined by
+    /// the specification.
+    ///
+    /// These are preserved when loading and saving a font, but are not part
+    /// of the [serde] representation of `FontInfo`: serializing it with serde
+    /// drops them, and deserializing rejects them as unknown fields.
+    ///
+    /// [serde]: https://serde.rs
+    pub fn extra(&self) -> &Plist {
+        &self.extra
+    }
+
+    /// Returns a mutable reference to the keys of the `fontinfo.plist` file
+    /// that are not defined by the specification.
+    ///
+    /// Keys added here that clash with a key defined by the specification are
+    /// not written; set the corresponding field instead.
+    pub fn extra_mut(&mut self) -> &mut Plist {
+        &mut self.extra
+    }
+
+    /// Returns the keys of the `fontinfo.plist` file modelled by this struct.
+    fn keys() -> &'static [&'static str] {
+        static KEYS: OnceLock<&'static [&'static str]> = OnceLock::new();
+        KEYS.get_or_init(serde_impls::struct_fields::<FontInfo>)
+    }
+
+    /// Returns the contents of the `fontinfo.plist` file: the known keys in
+    /// their usual order, with the [`extra`][FontInfo::extra] keys merged in
+    /// sorted order. Extra keys defined by the specification are skipped.
+    pub(crate) fn to_plist(&self) -> Result<Plist, plist::Error> {
+        let known = plist::to_value(self)?.into_dictionary().unwrap_or_default();
+        if self.extra.is_empty() {
+            return Ok(known);
+        }
+        let known_keys = FontInfo::keys();
+        let mut extra: Vec<_> =
+            self.extra.iter().filter(|(key, _)| !known_keys.contains(&key.as_str())).collect();
+        extra.sort_by(|a, b| a.0.cmp(b.0));
+        let mut extra = extra.into_iter().peekable();
+        let mut merged = Plist::new();
+        for (key, value) in known {
+            while let Some((extra_key, extra_value)) = extra.next_if(|(k, _)| **k < key) {
+                merged.insert(extra_key.clone(), extra_value.clone());
+            }
+            merged.insert(key, value);
+        }
+        for (key, value) in extra {
+            merged.insert(key.clone(), value.clone());
+        }
+        Ok(merged)
+    }
+
+    /// Sets `postscriptBlueValues`, checking that `values` holds at most 14
+    /// numbers, in pairs.
+    ///
+    /// Returns an error, leaving the field unchanged, if it does not.
+    pub fn set_postscript_blue_values(
+        &mut self,
+        values: Vec<IntegerOrFloat>,
+    ) -> Result<(), FontInfoErrorKind> {
+        PostscriptList::BlueValues.validate(&values)?;
+        self.postscript_blue_values = Some(values);
+        Ok(())
+    }
+
+    /// Sets `postscriptOtherBlues`, checking that `values` holds at most 10
+    /// numbers, in pairs.
+    ///
+    /// Returns an error, leaving the field unchanged, if it does not.
+    pub fn set_postscript_other_blues(
+        &mut self,
+        values: Vec<IntegerOrFloat>,
+    ) -> Result<(), FontInfoErrorKind> {
+        PostscriptList::OtherBlues.validate(&values)?;
+        self.postscript_other_blues = Some(values);
+        Ok(())
+    }
+
+    /// Sets `postscriptFamilyBlues`, checking that `values` holds at most 14
+    /// numbers, in pairs.
+    ///
+    /// Returns an error, leaving the field unchanged, if it does not.
+    pub fn set_postscript_family_blues(
+        &mut self,
+        values: Vec<IntegerOrFloat>,
+    ) -> Result<(), FontInfoErrorKind> {
+        PostscriptList::FamilyBlues.validate(&values)?;
+        self.postscript_family_blues = Some(values);
+        Ok(())
+    }
+
+    /// Sets `postscriptFamilyOtherBlues`, checking that `values` holds at most
+    /// 10 numbers, in pairs.
+    ///
+    /// Returns an error, leaving the field unchanged, if it does not.
+    pub fn set_postscript_family_other_blues(
+        &mut self,
+        values: Vec<IntegerOrFloat>,
+    ) -> Result<(), FontInfoErrorKind> {
+        PostscriptList::FamilyOtherBlues.validate(&values)?;
+        self.postscript_family_other_blues = Some(values);
+        Ok(())
+    }
+
+    /// Sets `postscriptStemSnapH`, checking that `values` holds at most 12
+    /// numbers.
+    ///
+    /// Returns an error, leaving the field unchanged, if it does not.
+    pub fn set_postscript_stem_snap_h(
+        &mut self,
+        values: Vec<IntegerOrFloat>,
+    ) -> Result<(), FontInfoErrorKind> {
+        PostscriptList::StemSnapH.validate(&values)?;
+        self.postscript_stem_snap_h = Some(values);
+        Ok(())
+    }
+
+    /// Sets `postscriptStemSnapV`, checking that `values` holds at most 12
+    /// numbers.
+    ///
+    /// Returns an error, leaving the field unchanged, if it does not.
+    pub fn set_postscript_stem_snap_v(
+        &mut self,
+        values: Vec<IntegerOrFloat>,
+    ) -> Result<(), FontInfoErrorKind> {
+        PostscriptList::StemSnapV.validate(&values)?;
+        self.postscript_stem_snap_v = Some(values);
+        Ok(())
+    }
+
     /// Returns `false` if this [`FontInfo`] has any non-default value, and `true` otherwise.
     pub fn is_empty(&self) -> bool {
         self == &Self::default()
@@ -888,74 +1039,16 @@ impl FontInfo {
         }
 
         // The Postscript blue zone and stem widths lists have a length limitation.
-        if let Some(v) = &self.postscript_blue_values {
-            let name = "postscriptBlueValues";
-            if v.len() > 14 {
-                return Err(FontInfoErrorKind::InvalidPostscriptListLength {
-                    name,
-                    max_len: 14,
-                    len: v.len(),
-                });
-            }
-            if v.len() % 2 != 0 {
-                return Err(FontInfoErrorKind::PostscriptListMustBePairs(name));
-            }
-        }
-        if let Some(v) = &self.postscript_other_blues {
-            let name = "postscriptOtherBlues";
-            if v.len() > 10 {
-                return Err(FontInfoErrorKind::InvalidPostscriptListLength {
-                    name,
-                    max_len: 10,
-                    len: v.len(),
-                });
-            }
-            if v.len() % 2 != 0 {
-                return Err(FontInfoErrorKind::PostscriptListMustBePairs(name));
-            }
-        }
-        if let Some(v) = &self.postscript_family_blues {
-            let name = "postscriptFamilyBlues";
-            if v.len() > 14 {
-                return Err(FontInfoErrorKind::InvalidPostscriptListLength {
-                    name,
-                    max_len: 14,
-                    len: v.len(),
-                });
-            }
-            if v.len() % 2 != 0 {
-                return Err(FontInfoErrorKind::PostscriptListMustBePairs(name));
-            }
-        }
-        if let Some(v) = &self.postscript_family_other_blues {
-            let name = "postscriptFamilyOtherBlues";
-            if v.len() > 10 {
-                return Err(FontInfoErrorKind::InvalidPostscriptListLength {
-                    name,
-                    max_len: 10,
-                    len: v.len(),
-                });
-            }
-            if v.len() % 2 != 0 {
-                return Err(FontInfoErrorKind::PostscriptListMustBePairs(name));
-            }
-        }
-        if let Some(v) = &self.postscript_stem_snap_h {
-            if v.len() > 12 {
-                return Err(FontInfoErrorKind::InvalidPostscriptListLength {
-                    name: "postscriptStemSnapH",
-                    max_len: 12,
-                    len: v.len(),
-                });
-            }
-        }
-        if let Some(v) = &self.postscript_stem_snap_v {
-            if v.len() > 12 {
-                return Err(FontInfoErrorKind::InvalidPostscriptListLength {
-                    name: "postscriptStemSnapV",
-                    max_len: 12,
-                    len: v.len(),
-                });
+        for (list, values) in [
+            (PostscriptList::BlueValues, &self.postscript_blue_values),
+            (PostscriptList::OtherBlues, &self.postscript_other_blues),
+            (PostscriptList::FamilyBlues, &self.postscript_family_blues),
+            (PostscriptList::FamilyOtherBlues, &self.postscript_family_other_blues),
+            (PostscriptList::StemSnapH, &self.postscript_stem_snap_h),
+            (PostscriptList::StemSnapV, &self.postscript_stem_snap_v),
+        ] {
+            if let Some(values) = values {
+                list.validate(values)?;
             }
         }
 
@@ -1005,9 +1098,31 @@ impl FontInfo {
         Ok(())
     }
 
+    /// Returns the global guidelines, which apply to all glyphs.
+    ///
+    /// Their libs are stored in the font lib's `public.objectLibs` key on
+    /// disk, and are moved into the guidelines when loading.
+    pub fn guidelines(&self) -> &[Guideline] {
+        self.guidelines.as_deref().unwrap_or(&[])
+    }
+
+    /// Returns a mutable reference to the global guidelines.
+    ///
+    /// These will be created if they do not already exist.
+    pub fn guidelines_mut(&mut self) -> &mut Vec<Guideline> {
+        self.guidelines.get_or_insert_with(Default::default)
+    }
+
     /// Move libs from the font lib's `public.objectLibs` key into the actual objects.
     /// The key will be removed from the font lib.
-    fn load_object_libs(&mut self, lib: &mut Plist) -> Result<(), FontInfoLoadError> {
+    ///
+    /// Entries matching no guideline are dropped, or are an error if
+    /// `strict_object_libs` is set.
+    fn load_object_libs(
+        &mut self,
+        lib: &mut Plist,
+        strict_object_libs: bool,
+    ) -> Result<(), FontInfoLoadError> {
         let mut object_libs = match lib.remove(PUBLIC_OBJECT_LIBS_KEY) {
             Some(lib) => {
                 lib.into_dictionary().ok_or(FontInfoLoadError::PublicObjectLibsMustBeDictionary)?
@@ -1030,7 +1145,10 @@ impl FontInfo {
             }
         }
 
-        Ok(())
+        match object_libs.keys().next() {
+            Some(id) if strict_object_libs => Err(FontInfoLoadError::OrphanedObjectLib(id.clone())),
+            _ => Ok(()),
+        }
     }
 
     /// Dump guideline libs into a [`Plist`].
@@ -1126,7 +1244,8 @@ impl<'de> Deserialize<'de> for NonNegativeIntegerOrFloat {
 }
 
 mod serde_impls {
-    use serde::{ser::SerializeSeq, Serialize, Serializer};
+    use serde::de::{self, value, Visitor};
+    use serde::{ser::SerializeSeq, Deserialize, Deserializer, Serialize, Serializer};
 
     struct IntegerOrFloat(f64);
 
@@ -1150,6 +1269,42 @@ mod serde_impls {
         value.map(IntegerOrFloat).serialize(serializer)
     }
 
+    /// Returns the serialized names of the fields of the struct `T`.
+    ///
+    /// This relies on derived `Deserialize` impls passing their field names to
+    /// `deserialize_struct`, which the deserializer below records.
+    pub(crate) fn struct_fields<T: for<'de> Deserialize<'de>>() -> &'static [&'static str] {
+        struct FieldNames<'a>(&'a mut &'static [&'static str]);
+
+        impl<'de> Deserializer<'de> for FieldNames<'_> {
+            type Error = value::Error;
+
+            fn deserialize_any<V: Visitor<'de>>(self, _: V) -> Result<V::Value, Self::Error> {
+                Err(de::Error::custom("not a struct"))
+            }
+
+            fn deserialize_struct<V: Visitor<'de>>(
+                self,
+                _name: &'static str,
+                fields: &'static [&'static str],
+                _visitor: V,
+            ) -> Result<V::Value, Self::Error> {
+                *self.0 = fields;
+                Err(de::Error::custom("field names recorded"))
+            }
+
+            serde::forward_to_deserialize_any! {
+                bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string
+                bytes byte_buf option unit unit_struct newtype_struct seq tuple
+                tuple_struct map enum identifier ignored_any
+            }
+        }
+
+        let mut fields: &'static [&'static str] = &[];
+        let _ = T::deserialize(FieldNames(&mut fields));
+        fields
+    }
+
     pub(crate) fn ser_opt_vec_int_or_float<S: Serializer>(
         value: &Option<Vec<f64>>,
         serializer: S,
@@ -1213,9 +1368,64 @@ pub struct NameRecord {
     pub string: String,
 }
 
+/// The Postscript number lists with a length limitation.
+#[derive(Debug, Clone, Copy)]
+enum PostscriptList {
+    BlueValues,
+    OtherBlues,
+    FamilyBlues,
+    FamilyOtherBlues,
+    StemSnapH,
+    StemSnapV,
+}
+
+impl PostscriptList {
+    fn name(self) -> &'static str {
+        match self {
+            PostscriptList::BlueValues => "postscriptBlueValues",
+            PostscriptList::OtherBlues => "postscriptOtherBlues",
+            PostscriptList::FamilyBlues => "postscriptFamilyBlues",
+            PostscriptList::FamilyOtherBlues => "postscriptFamilyOtherBlues",
+            PostscriptList::StemSnapH => "postscriptStemSnapH",
+            PostscriptList::StemSnapV => "postscriptStemSnapV",
+        }
+    }
+
+    fn max_len(self) -> u8 {
+        match self {
+            PostscriptList::BlueValues | PostscriptList::FamilyBlues => 14,
+            PostscriptList::OtherBlues | PostscriptList::FamilyOtherBlues => 10,
+            PostscriptList::StemSnapH | PostscriptList::StemSnapV => 12,
+        }
+    }
+
+    /// Blue zones are given as pairs of bottom and top values.
+    fn is_pairs(self) -> bool {
+        !matches!(self, PostscriptList::StemSnapH | PostscriptList::StemSnapV)
+    }
+
+    fn validate(self, values: &[IntegerOrFloat]) -> Result<(), FontInfoErrorKind> {
+        if values.len() > self.max_len() as usize {
+            return Err(FontInfoErrorKind::InvalidPostscriptListLength {
+                name: self.name(),
+                max_len: self.max_len(),
+                len: values.len(),
+            });
+        }
+        if self.is_pairs() && !values.len().is_multiple_of(2) {
+            return Err(FontInfoErrorKind::PostscriptListMustBePairs(self.name()));
+        }
+        Ok(())
+    }
+}
+
 /// Corresponds to the allowed values for
 /// [openTypeOS2WidthClass](http://unifiedfontobject.org/versions/ufo3/fontinfo.plist/#opentype-os2-table-fields).
-#[derive(Debug, Clone, Copy, Default, Serialize_repr, Deserialize_repr, PartialEq, Eq)]
+///
+/// The class is stored as an integer; use [`Os2WidthClass::value`] and
+/// `Os2WidthClass::try_from(u16)` to convert between the two. Values outside
+/// of the range 1-9 are rejected when loading.
+#[derive(Debug, Clone, Copy, Default, Serialize_repr, PartialEq, Eq)]
 #[repr(u8)]
 pub enum Os2WidthClass {
     /// Ultra-condensed width.
@@ -1239,6 +1449,50 @@ pub enum Os2WidthClass {
     UltraExpanded = 9,
 }
 
+impl Os2WidthClass {
+    /// Returns the integer value of the width class, as stored in the UFO.
+    pub fn value(self) -> u16 {
+        self as u16
+    }
+}
+
+impl TryFrom<u16> for Os2WidthClass {
+    type Error = FontInfoErrorKind;
+
+    /// Returns the width class for a stored value, or an error if the value is
+    /// not in the range 1-9.
+    fn try_from(value: u16) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Os2WidthClass::UltraCondensed),
+            2 => Ok(Os2WidthClass::ExtraCondensed),
+            3 => Ok(Os2WidthClass::Condensed),
+            4 => Ok(Os2WidthClass::SemiCondensed),
+            5 => Ok(Os2WidthClass::Normal),
+            6 => Ok(Os2WidthClass::SemiExpanded),
+            7 => Ok(Os2WidthClass::Expanded),
+            8 => Ok(Os2WidthClass::ExtraExpanded),
+            9 => Ok(Os2WidthClass::UltraExpanded),
+            other => Err(FontInfoErrorKind::UnknownWidthClass(other.to_string())),
+        }
+    }
+}
+
+impl From<Os2WidthClass> for u16 {
+    fn from(class: Os2WidthClass) -> Self {
+        class.value()
+    }
+}
+
+impl<'de> Deserialize<'de> for Os2WidthClass {
+    fn deserialize<D>(deserializer: D) -> Result<Os2WidthClass, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let value = u16::deserialize(deserializer)?;
+        Os2WidthClass::try_from(value).map_err(serde::de::Error::custom)
+    }
+}
+
 /// Corresponds to [openTypeOS2FamilyClass](http://unifiedfontobject.org/versions/ufo3/fontinfo.plist/#opentype-os2-table-fields).
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct Os2FamilyClass {
@@ -1689,7 +1943,7 @@ impl<'de> Deserialize<'de> for StyleMapStyle {
 mod tests {
     use super::*;
     use crate::{Color, Identifier, Line, Name};
-    use serde_test::{assert_tokens, Token};
+    use serde_test::{assert_de_tokens, assert_de_tokens_error, assert_tokens, Token};
 
     #[test]
     fn fontinfo() {
@@ -1762,6 +2016,74 @@ mod tests {
         );
     }
 
+    #[test]
+    fn unknown_keys_round_trip() {
+        let dir = tempfile::TempDir::new().unwrap();
+        let path = dir.path().join("fontinfo.plist");
+        std::fs::write(
+            &path,
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>ascender</key>
+	<integer>750</integer>
+	<key>com.example.buildNumber</key>
+	<integer>42</integer>
+	<key>familyName</key>
+	<string>Test</string>
+	<key>zzz.custom</key>
+	<dict>
+		<key>nested</key>
+		<true/>
+	</dict>
+</dict>
+</plist>"#,
+        )
+        .unwrap();
+
+        let mut lib = Plist::new();
+        let mut font_info = FontInfo::from_file(&path, FormatVersion::V3, &mut lib, false).unwrap();
+        assert_eq!(font_info.family_name.as_deref(), Some("Test"));
+        assert_eq!(font_info.extra().len(), 2);
+        assert_eq!(
+            font_info.extra().get("com.example.buildNumber"),
+            Some(&plist::Value::Integer(42.into()))
+        );
+
+        let keys: Vec<_> = font_info.to_plist().unwrap().keys().cloned().collect();
+        assert_eq!(keys, ["ascender", "com.example.buildNumber", "familyName", "zzz.custom"]);
+
+        font_info.extra_mut().remove("zzz.custom");
+        assert!(!font_info.to_plist().unwrap().contains_key("zzz.custom"));
+        font_info.extra_mut().insert("familyName".into(), "Clash".into());
+        font_info.extra_mut().insert("capHeight".into(), 700.into());
+        let plist = font_info.to_plist().unwrap();
+        assert_eq!(plist.get("familyName").and_then(|v| v.as_string()), Some("Test"));
+        assert!(!plist.contains_key("capHeight"));
+        font_info.extra_mut().clear();
+        font_info.family_name = None;
+        font_info.ascender = None;
+        assert!(font_info.is_empty());
+    }
+
+    #[test]
+    fn test_serde_os2_width_class() {
+        assert_tokens(&Os2WidthClass::Condensed, &[Token::U8(3)]);
+        assert_de_tokens(&Os2WidthClass::Normal, &[Token::U16(5)]);
+        assert_de_tokens_error::<Os2WidthClass>(
+            &[Token::U16(12)],
+            "unrecognized OS/2 width class '12'",
+        );
+        assert_eq!(Os2WidthClass::try_from(9).unwrap(), Os2WidthClass::UltraExpanded);
+        assert!(matches!(
+            Os2WidthClass::try_from(0),
+            Err(FontInfoErrorKind::UnknownWidthClass(value)) if value == "0"
+        ));
+        assert!(Os2WidthClass::try_from(10).is_err());
+        assert_eq!(u16::from(Os2WidthClass::Normal), 5);
+    }
+
     #[test]
     fn test_serde_os2_family_class() {
         let c1 = Os2FamilyClass { class_id: 14, subclass_id: 15 };
@@ -2000,4 +2322,24 @@ mod tests {
             Err(FontInfoErrorKind::InvalidPostscriptListLength { name: _, max_len: _, len: _ })
         ));
     }
+
+    #[test]
+    fn postscript_list_setters_validate() {
+        let mut fi = FontInfo::default();
+        fi.set_postscript_blue_values(vec![-10., 0., 500., 510.]).unwrap();
+        assert!(matches!(
+            fi.set_postscript_blue_values([0.0; 16].into()),
+            Err(FontInfoErrorKind::InvalidPostscriptListLength { max_len: 14, len: 16, .. })
+        ));
+        assert!(matches!(
+            fi.set_postscript_other_blues(vec![0.]),
+            Err(FontInfoErrorKind::PostscriptListMustBePairs("postscriptOtherBlues"))
+        ));
+        assert_eq!(fi.postscript_blue_values, Some(vec![-10., 0., 500., 510.]));
+        assert_eq!(fi.postscript_other_blues, None);
+
+        fi.set_postscript_stem_snap_v(vec![80.]).unwrap();
+        assert!(fi.set_postscript_stem_snap_h([0.0; 13].into()).is_err());
+        assert!(fi.validate().is_ok());
+    }
 }
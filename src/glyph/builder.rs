@@ -1,4 +1,4 @@
-//! A builder for outlines.
+//! Builders for outlines.
 //!
 //! An [`OutlineBuilder`] is a point-oriented builder for a glyph's graphical outline,
 //! not unlike a [fontTools point pen], but different, because it does not draw _into_ a
@@ -7,6 +7,9 @@
 //! To be used internally by [`super::parse::GlifParser`]. Does not keep track of identifier
 //! uniqueness (`GlifParser` has to).
 //!
+//! A [`ContourBuilder`] is the public, fluent counterpart for building a single contour,
+//! validated the same way.
+//!
 //! [fontTools point pen]: https://fonttools.readthedocs.io/en/latest/pens/basePen.html
 
 use crate::{
@@ -14,6 +17,102 @@ use crate::{
     PointType,
 };
 
+/// A fluent builder for a single [`Contour`].
+///
+/// Points are collected as given and checked against the rules of the
+/// [specification] when the contour is [built](ContourBuilder::build), just
+/// like when a glif file is parsed.
+///
+/// # Examples
+///
+/// ```
+/// # use norad::{ContourBuilder, PointType};
+/// let triangle = ContourBuilder::new()
+///     .point(0., 0., PointType::Line)
+///     .point(100., 0., PointType::Line)
+///     .point(50., 100., PointType::Line)
+///     .build()
+///     .unwrap();
+/// assert_eq!(triangle.points.len(), 3);
+///
+/// // A curve point may follow at most two off-curve points.
+/// let invalid = ContourBuilder::new()
+///     .point(0., 0., PointType::Line)
+///     .point(0., 10., PointType::OffCurve)
+///     .point(0., 20., PointType::OffCurve)
+///     .point(0., 30., PointType::OffCurve)
+///     .point(0., 40., PointType::Curve)
+///     .build();
+/// assert!(invalid.is_err());
+/// ```
+///
+/// [specification]: https://unifiedfontobject.org/versions/ufo3/glyphs/glif/#point-types
+#[derive(Debug, Clone, Default)]
+pub struct ContourBuilder {
+    identifier: Option<Identifier>,
+    points: Vec<ContourPoint>,
+}
+
+impl ContourBuilder {
+    /// Returns a new builder for an empty contour.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Sets the identifier of the contour.
+    pub fn identifier(mut self, identifier: Identifier) -> Self {
+        self.identifier = Some(identifier);
+        self
+    }
+
+    /// Adds a point that is not smooth.
+    pub fn point(self, x: f64, y: f64, typ: PointType) -> Self {
+        self.point_with(x, y, typ, false, None, None)
+    }
+
+    /// Adds a smooth point.
+    pub fn smooth_point(self, x: f64, y: f64, typ: PointType) -> Self {
+        self.point_with(x, y, typ, true, None, None)
+    }
+
+    /// Adds a point with all attributes given.
+    pub fn point_with(
+        mut self,
+        x: f64,
+        y: f64,
+        typ: PointType,
+        smooth: bool,
+        name: Option<Name>,
+        identifier: Option<Identifier>,
+    ) -> Self {
+        self.points.push(ContourPoint::new(x, y, typ, smooth, name, identifier));
+        self
+    }
+
+    /// Validates the points and returns the contour.
+    ///
+    /// Returns an error if a move point is not the first point, an off-curve
+    /// point is smooth, a line point follows an off-curve point, a curve point
+    /// follows more than two off-curve points, or an open contour ends with
+    /// off-curve points.
+    pub fn build(self) -> Result<Contour, ErrorKind> {
+        validate_points(&self.points)?;
+        Ok(Contour::new(self.points, self.identifier))
+    }
+}
+
+/// Checks that `points` form a valid contour, by replaying them through an
+/// [`OutlineBuilder`].
+pub(crate) fn validate_points(points: &[ContourPoint]) -> Result<(), ErrorKind> {
+    let mut builder = OutlineBuilder::new();
+    builder.begin_path(None)?;
+    for point in points {
+        builder.add_point((point.x, point.y), point.typ.clone(), point.smooth, None, None)?;
+    }
+    builder.end_path()?;
+    Ok(())
+}
+
 #[derive(Debug, Default)]
 pub(crate) struct OutlineBuilder {
     components: Vec<Component>,
@@ -195,6 +294,7 @@ impl OutlineBuilder {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::glyph::tests::point;
 
     #[test]
     fn builder_basic() -> Result<(), ErrorKind> {
@@ -223,9 +323,9 @@ mod tests {
             contours,
             vec![Contour::new(
                 vec![
-                    ContourPoint::new(173.0, 536.0, PointType::Line, false, None, None),
-                    ContourPoint::new(85.0, 536.0, PointType::Line, false, None, None),
-                    ContourPoint::new(85.0, 0.0, PointType::Line, false, None, None),
+                    point(173.0, 536.0, PointType::Line),
+                    point(85.0, 536.0, PointType::Line),
+                    point(85.0, 0.0, PointType::Line),
                     ContourPoint::new(
                         173.0,
                         0.0,
@@ -258,6 +358,60 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn contour_builder() -> Result<(), ErrorKind> {
+        let triangle = ContourBuilder::new()
+            .identifier(Identifier::new_raw("triangle"))
+            .point(0., 0., PointType::Line)
+            .point(100., 0., PointType::Line)
+            .point(50., 100., PointType::Line)
+            .build()?;
+        assert_eq!(triangle.identifier().map(Identifier::as_str), Some("triangle"));
+        assert_eq!(triangle.points.len(), 3);
+
+        let cubic = ContourBuilder::new()
+            .smooth_point(0., 0., PointType::Curve)
+            .point(55., 0., PointType::OffCurve)
+            .point(100., 45., PointType::OffCurve)
+            .smooth_point(100., 100., PointType::Curve)
+            .point(45., 100., PointType::OffCurve)
+            .point(0., 55., PointType::OffCurve)
+            .build()?;
+        assert!(cubic.is_closed());
+        assert!(cubic.points[0].smooth);
+        assert_eq!(cubic.points[4].typ, PointType::OffCurve);
+
+        let open = ContourBuilder::new()
+            .point(0., 0., PointType::Move)
+            .point(10., 0., PointType::Line)
+            .build()?;
+        assert!(open.is_open());
+
+        assert!(ContourBuilder::new().build()?.points.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn contour_builder_validates() {
+        let misplaced_move =
+            ContourBuilder::new().point(0., 0., PointType::Line).point(1., 1., PointType::Move);
+        assert!(matches!(misplaced_move.build(), Err(ErrorKind::UnexpectedMove)));
+
+        let smooth_off_curve = ContourBuilder::new().smooth_point(0., 0., PointType::OffCurve);
+        assert!(matches!(smooth_off_curve.build(), Err(ErrorKind::UnexpectedSmooth)));
+
+        let trailing =
+            ContourBuilder::new().point(0., 0., PointType::Move).point(1., 1., PointType::OffCurve);
+        assert!(matches!(trailing.build(), Err(ErrorKind::TrailingOffCurves)));
+
+        let line_after_off_curve =
+            ContourBuilder::new().point(0., 0., PointType::OffCurve).point(1., 1., PointType::Line);
+        assert!(matches!(
+            line_after_off_curve.build(),
+            Err(ErrorKind::UnexpectedPointAfterOffCurve)
+        ));
+    }
+
     #[test]
     #[should_panic(expected = "UnfinishedDrawing")]
     fn outline_builder_unfinished_drawing() {
@@ -0,0 +1,112 @@
+//! A normalized serialization of glyphs, for fingerprinting.
+
+use super::Glyph;
+use crate::error::GlifWriteError;
+use crate::identifier::{IdentifierSource, SequentialIdentifiers};
+use crate::{Identifier, Line, Plist, WriteOptions};
+
+impl Glyph {
+    /// Serializes the glyph to a normalized form, suitable for hashing.
+    ///
+    /// Glyphs that differ only cosmetically produce the same bytes. Compared
+    /// to [`Glyph::encode_xml`], the following is normalized:
+    ///
+    /// - the output is always indented with tabs and uses double quotes;
+    /// - anchors and guidelines are sorted, as with
+    ///   [`WriteOptions::sort_anchors`];
+    /// - identifiers are removed. Objects with a lib instead get a sequential
+    ///   identifier, in the order in which they are written, so that their lib
+    ///   is still part of the output;
+    /// - the keys of the glyph lib and of object libs, including those of
+    ///   nested dictionaries, are sorted;
+    /// - negative zero coordinates are written as zero.
+    ///
+    /// Numbers are otherwise written with full precision, and the order of
+    /// codepoints, contours, points and components is kept, as it is
+    /// significant.
+    ///
+    /// The output is not meant to be loaded again: use [`Glyph::encode_xml`]
+    /// to preserve the glyph as is.
+    pub fn canonical_bytes(&self) -> Result<Vec<u8>, GlifWriteError> {
+        let mut glyph = self.clone();
+        glyph.normalize();
+        glyph.encode_xml_with_options(&WriteOptions::default().sort_anchors(true))
+    }
+
+    fn normalize(&mut self) {
+        fn zero(v: &mut f64) {
+            // Adding positive zero turns negative zero into positive zero.
+            *v += 0.0;
+        }
+
+        fn normalize_object(
+            identifier: &mut Option<Identifier>,
+            lib: &mut Option<Plist>,
+            ids: &mut SequentialIdentifiers,
+        ) {
+            *identifier = lib.as_mut().map(|lib| {
+                sort_keys(lib);
+                ids.next_identifier()
+            });
+        }
+
+        // Sort anchors and guidelines up front so that the sequential
+        // identifiers follow the output order.
+        self.anchors.sort_by(super::serialize::compare_anchors);
+        self.guidelines.sort_by(super::serialize::compare_guidelines);
+
+        let mut ids = SequentialIdentifiers::new("").unwrap();
+        zero(&mut self.width);
+        zero(&mut self.height);
+        for anchor in &mut self.anchors {
+            zero(&mut anchor.x);
+            zero(&mut anchor.y);
+            normalize_object(&mut anchor.identifier, &mut anchor.lib, &mut ids);
+        }
+        for guideline in &mut self.guidelines {
+            match &mut guideline.line {
+                Line::Vertical(x) => zero(x),
+                Line::Horizontal(y) => zero(y),
+                Line::Angle { x, y, degrees } => [x, y, degrees].into_iter().for_each(zero),
+            }
+            normalize_object(&mut guideline.identifier, &mut guideline.lib, &mut ids);
+        }
+        for contour in &mut self.contours {
+            normalize_object(&mut contour.identifier, &mut contour.lib, &mut ids);
+            for point in &mut contour.points {
+                zero(&mut point.x);
+                zero(&mut point.y);
+                normalize_object(&mut point.identifier, &mut point.lib, &mut ids);
+            }
+        }
+        for component in &mut self.components {
+            let t = &mut component.transform;
+            for v in [
+                &mut t.x_scale,
+                &mut t.xy_scale,
+                &mut t.yx_scale,
+                &mut t.y_scale,
+                &mut t.x_offset,
+                &mut t.y_offset,
+            ] {
+                zero(v);
+            }
+            normalize_object(&mut component.identifier, &mut component.lib, &mut ids);
+        }
+        sort_keys(&mut self.lib);
+    }
+}
+
+/// Sorts the keys of `dict` and of all nested dictionaries.
+fn sort_keys(dict: &mut Plist) {
+    fn sort_value(value: &mut plist::Value) {
+        match value {
+            plist::Value::Dictionary(dict) => sort_keys(dict),
+            plist::Value::Array(array) => array.iter_mut().for_each(sort_value),
+            _ => (),
+        }
+    }
+
+    dict.sort_keys();
+    dict.values_mut().for_each(sort_value);
+}
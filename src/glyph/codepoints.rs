@@ -6,6 +6,9 @@
 use indexmap::IndexSet;
 use serde::{Deserialize, Serialize};
 
+use super::Glyph;
+use crate::error::ErrorKind;
+
 /// A set of Unicode codepoints
 #[derive(Clone, Debug, Default, Serialize, Deserialize, PartialEq, Eq)]
 pub struct Codepoints(IndexSet<char>);
@@ -89,3 +92,51 @@ impl<'a> IntoIterator for &'a Codepoints {
         self.0.iter()
     }
 }
+
+impl Glyph {
+    /// Returns the glyph's codepoints as uppercase hexadecimal strings of at
+    /// least four digits, such as `"0041"`, as they are written in `.glif`
+    /// files.
+    pub fn unicodes_hex(&self) -> Vec<String> {
+        self.codepoints.iter().map(to_hex).collect()
+    }
+
+    /// Replaces the glyph's codepoints with the ones given as hexadecimal
+    /// strings, in either case and without a prefix.
+    ///
+    /// Returns [`ErrorKind::BadHexValue`] if a value is not hexadecimal or not
+    /// a Unicode scalar value, such as a surrogate, leaving the codepoints
+    /// unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::Glyph;
+    /// let mut glyph = Glyph::new("A");
+    /// glyph.set_unicodes_hex(["41", "1d400"]).unwrap();
+    /// assert_eq!(glyph.codepoints.iter().collect::<Vec<_>>(), ['A', '𝐀']);
+    /// assert_eq!(glyph.unicodes_hex(), ["0041", "1D400"]);
+    /// assert!(glyph.set_unicodes_hex(["D800"]).is_err());
+    /// ```
+    pub fn set_unicodes_hex(
+        &mut self,
+        values: impl IntoIterator<Item = impl AsRef<str>>,
+    ) -> Result<(), ErrorKind> {
+        let codepoints = values
+            .into_iter()
+            .map(|value| from_hex(value.as_ref()))
+            .collect::<Result<Codepoints, _>>()?;
+        self.codepoints = codepoints;
+        Ok(())
+    }
+}
+
+/// Formats a codepoint the way `.glif` files do.
+pub(crate) fn to_hex(codepoint: char) -> String {
+    format!("{:04X}", codepoint as u32)
+}
+
+/// Parses a hexadecimal codepoint, as found in `.glif` files.
+pub(crate) fn from_hex(value: &str) -> Result<char, ErrorKind> {
+    u32::from_str_radix(value, 16).ok().and_then(char::from_u32).ok_or(ErrorKind::BadHexValue)
+}
@@ -0,0 +1,106 @@
+//! Resolving component references.
+
+use std::collections::HashSet;
+
+use super::{Component, Glyph};
+use crate::error::ComponentError;
+use crate::identifier::IdentifierSource;
+#[cfg(feature = "object-libs")]
+use crate::identifier::UuidV4Identifiers;
+use crate::{Layer, Name};
+
+impl Glyph {
+    /// Replaces each component with the contours and components of its base
+    /// glyph in `layer`, transformed by the component's transform.
+    ///
+    /// Only one level is resolved: components of the base glyphs are copied
+    /// over as components, with their transforms combined with that of the
+    /// replaced component.
+    ///
+    /// Pulled-in objects that carry an identifier get a new UUID v4 identifier
+    /// so identifiers stay unique within the glyph; see
+    /// [`Glyph::flatten_components_once_with`] to control how they are
+    /// generated.
+    ///
+    /// Returns an error, leaving the glyph unchanged, if a base glyph is
+    /// missing from `layer` or if the components reachable from this glyph
+    /// form a cycle.
+    #[cfg(feature = "object-libs")]
+    pub fn flatten_components_once(&mut self, layer: &Layer) -> Result<(), ComponentError> {
+        self.flatten_components_once_with(layer, &mut UuidV4Identifiers)
+    }
+
+    /// Like [`Glyph::flatten_components_once`], but takes new identifiers from
+    /// `ids`.
+    pub fn flatten_components_once_with(
+        &mut self,
+        layer: &Layer,
+        ids: &mut impl IdentifierSource,
+    ) -> Result<(), ComponentError> {
+        check_component_cycles(self, layer)?;
+
+        let mut contours = Vec::new();
+        let mut components = Vec::new();
+        for component in &self.components {
+            // Checked for existence above.
+            let base = layer.get_glyph(&component.base).unwrap();
+            for contour in &base.contours {
+                let mut contour = contour.clone();
+                for point in &mut contour.points {
+                    point.transform(component.transform);
+                    if point.identifier.is_some() {
+                        point.identifier = Some(ids.next_identifier());
+                    }
+                }
+                if contour.identifier.is_some() {
+                    contour.identifier = Some(ids.next_identifier());
+                }
+                contours.push(contour);
+            }
+            for inner in &base.components {
+                let identifier = inner.identifier.as_ref().map(|_| ids.next_identifier());
+                components.push(Component {
+                    base: inner.base.clone(),
+                    transform: component.transform.concat(inner.transform),
+                    identifier,
+                    lib: inner.lib.clone(),
+                });
+            }
+        }
+
+        self.contours.append(&mut contours);
+        self.components = components;
+        Ok(())
+    }
+}
+
+/// Returns an error if a component base reachable from `glyph` is missing from
+/// `layer` or if the components form a cycle.
+fn check_component_cycles(glyph: &Glyph, layer: &Layer) -> Result<(), ComponentError> {
+    fn visit<'a>(
+        name: &'a Name,
+        components: &'a [Component],
+        layer: &'a Layer,
+        path: &mut Vec<&'a Name>,
+        done: &mut HashSet<&'a Name>,
+    ) -> Result<(), ComponentError> {
+        if path.contains(&name) {
+            return Err(ComponentError::Cycle(name.clone()));
+        }
+        path.push(name);
+        for component in components {
+            if done.contains(&component.base) {
+                continue;
+            }
+            let base = layer
+                .get_glyph(&component.base)
+                .ok_or_else(|| ComponentError::MissingBase(component.base.clone()))?;
+            visit(base.name(), &base.components, layer, path, done)?;
+        }
+        path.pop();
+        done.insert(name);
+        Ok(())
+    }
+
+    visit(glyph.name(), &glyph.components, layer, &mut Vec::new(), &mut HashSet::new())
+}
@@ -0,0 +1,21 @@
+//! Transformations of glyph outlines.
+
+use super::Glyph;
+
+/// A transformation applied to the outline of a [`Glyph`], such as removing
+/// overlaps, see [`Font::flatten_for_export`](crate::Font::flatten_for_export).
+///
+/// Norad does not provide any geometric filters itself; this trait lets
+/// other crates plug theirs in. It is implemented for closures taking a
+/// `&mut Glyph` and returning a `bool`.
+pub trait OutlineFilter {
+    /// Transforms the outline of `glyph` in place, returning whether it was
+    /// changed.
+    fn filter(&mut self, glyph: &mut Glyph) -> bool;
+}
+
+impl<F: FnMut(&mut Glyph) -> bool> OutlineFilter for F {
+    fn filter(&mut self, glyph: &mut Glyph) -> bool {
+        self(glyph)
+    }
+}
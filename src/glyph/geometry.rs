@@ -0,0 +1,501 @@
+//! Geometric operations on contours.
+
+use super::segments::distance_to_segment;
+use super::{Contour, ContourPoint, Glyph, PointType};
+use crate::Line;
+
+/// The indices of the extreme on-curve points of a contour, as returned by
+/// [`Contour::extrema`].
+///
+/// When several points share an extreme coordinate, the first one is used.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Extrema {
+    /// The index of the on-curve point with the smallest x coordinate.
+    pub left: usize,
+    /// The index of the on-curve point with the largest x coordinate.
+    pub right: usize,
+    /// The index of the on-curve point with the smallest y coordinate.
+    pub bottom: usize,
+    /// The index of the on-curve point with the largest y coordinate.
+    pub top: usize,
+}
+
+impl Contour {
+    /// Removes redundant on-curve points from straight runs of the contour,
+    /// returning the number of points removed.
+    ///
+    /// This is a Ramer–Douglas–Peucker simplification applied to each run of
+    /// consecutive straight line segments: a point is dropped if it lies within
+    /// `tolerance` units of the line that would replace it. Curve segments
+    /// are left intact, as are the on-curve points they start or end at, so the
+    /// contour keeps its shape (within `tolerance`) and its open or closed
+    /// nature.
+    ///
+    /// Points that carry a name, an identifier or a lib are never removed; see
+    /// [`Contour::simplify_all`] to allow that.
+    pub fn simplify(&mut self, tolerance: f64) -> usize {
+        self.simplify_impl(tolerance, false)
+    }
+
+    /// Like [`Contour::simplify`], but also removes points that carry a name,
+    /// an identifier or a lib.
+    pub fn simplify_all(&mut self, tolerance: f64) -> usize {
+        self.simplify_impl(tolerance, true)
+    }
+
+    /// Returns the indices of the on-curve points before and after the point
+    /// at `index`.
+    ///
+    /// For an off-curve point these are the on-curve points governing its
+    /// segment; for an on-curve point, the adjacent on-curve points. Closed
+    /// contours wrap around, so every point has both neighbors unless the
+    /// contour has no other on-curve point. Open contours do not wrap,
+    /// so their first and last points lack a previous and next neighbor,
+    /// respectively.
+    ///
+    /// Returns `(None, None)` if `index` is out of bounds.
+    pub fn on_curve_neighbors(&self, index: usize) -> (Option<usize>, Option<usize>) {
+        let len = self.points.len();
+        if index >= len {
+            return (None, None);
+        }
+        let is_on_curve = |idx: &usize| self.points[*idx].typ != PointType::OffCurve;
+        if self.is_closed() {
+            let previous = (1..len).map(|step| (index + len - step) % len).find(is_on_curve);
+            let next = (1..len).map(|step| (index + step) % len).find(is_on_curve);
+            (previous, next)
+        } else {
+            ((0..index).rev().find(is_on_curve), (index + 1..len).find(is_on_curve))
+        }
+    }
+
+    /// Reverses the direction of the contour, leaving its shape unchanged.
+    ///
+    /// As a point's type describes the segment ending at it, the on-curve
+    /// points trade types so that each segment keeps its kind. A closed
+    /// contour keeps its first point; an open contour starts at its former
+    /// last point, which becomes the [`PointType::Move`] point. Names,
+    /// identifiers, libs and smooth flags stay with their points.
+    pub fn reverse(&mut self) {
+        let closed = self.is_closed();
+        let types: Vec<PointType> = (0..self.points.len())
+            .map(|idx| match (&self.points[idx].typ, self.on_curve_neighbors(idx).1) {
+                (PointType::OffCurve, _) => PointType::OffCurve,
+                (_, Some(next)) => self.points[next].typ.clone(),
+                // Only the last on-curve point of an open contour, or the sole
+                // one of a closed contour, has none.
+                (typ, None) if closed => typ.clone(),
+                (_, None) => PointType::Move,
+            })
+            .collect();
+        for (point, typ) in self.points.iter_mut().zip(types) {
+            point.typ = typ;
+        }
+        if !closed {
+            self.points.reverse();
+        } else if let Some(rest) = self.points.get_mut(1..) {
+            rest.reverse();
+        }
+    }
+
+    /// Returns the indices of the leftmost, rightmost, bottommost and topmost
+    /// on-curve points, or `None` if the contour has no on-curve points.
+    ///
+    /// Off-curve points are not considered, and curves may bulge beyond these
+    /// points: a well-drawn contour has on-curve points at all its extrema,
+    /// but that is not guaranteed. See [`Contour::curve_extrema`] for the
+    /// points where the curves themselves reach an extremum.
+    pub fn extrema(&self) -> Option<Extrema> {
+        let mut on_curve =
+            self.points.iter().enumerate().filter(|(_, point)| point.typ != PointType::OffCurve);
+        let (first, _) = on_curve.next()?;
+        let mut extrema = Extrema { left: first, right: first, bottom: first, top: first };
+        for (idx, point) in on_curve {
+            let points = &self.points;
+            if point.x < points[extrema.left].x {
+                extrema.left = idx;
+            }
+            if point.x > points[extrema.right].x {
+                extrema.right = idx;
+            }
+            if point.y < points[extrema.bottom].y {
+                extrema.bottom = idx;
+            }
+            if point.y > points[extrema.top].y {
+                extrema.top = idx;
+            }
+        }
+        Some(extrema)
+    }
+
+    /// Returns the points strictly inside curve segments where the curve is
+    /// horizontal or vertical, i.e. reaches a local extremum, in contour
+    /// order.
+    ///
+    /// These are computed by solving the bezier derivative, so unlike
+    /// [`Contour::extrema`] they are exact even where the contour lacks
+    /// on-curve points at its extrema. Straight segments and the segments'
+    /// end points are not included.
+    pub fn curve_extrema(&self) -> Vec<(f64, f64)> {
+        self.segments()
+            .iter()
+            .flat_map(|segment| segment.extrema_params().into_iter().map(move |t| segment.eval(t)))
+            .collect()
+    }
+
+    /// Inserts on-curve points at the horizontal and vertical extrema of the
+    /// contour's curves, returning the number of points inserted.
+    ///
+    /// Each curve is split at the parameters where it is horizontal or
+    /// vertical, without changing its shape. The inserted points are smooth
+    /// and have the type of the curve's end point. Extrema that lie within
+    /// 0.001 units of a segment's existing start or end point are skipped.
+    ///
+    /// Only segments that are a single cubic or quadratic curve are split:
+    /// cubic curves with more than two off-curve points and runs of several
+    /// quadratic off-curve points are left as they are. The off-curve points
+    /// of a split curve are replaced, dropping any name, identifier or lib
+    /// they had.
+    #[cfg(feature = "kurbo")]
+    pub fn add_extrema(&mut self) -> usize {
+        let Some(first_on) = self.points.iter().position(|p| p.typ != PointType::OffCurve) else {
+            return 0;
+        };
+        let len = self.points.len();
+        let closed = self.is_closed();
+        let steps = if closed { len } else { len - 1 };
+
+        let mut points = Vec::with_capacity(len);
+        let mut inserted = 0;
+        // Where the points following the last on-curve point before the
+        // closing segment begin.
+        let mut tail_start = 0;
+        let mut start = &self.points[first_on];
+        let mut offs = Vec::new();
+        for step in 1..=steps {
+            let point = &self.points[(first_on + step) % len];
+            if point.typ == PointType::OffCurve {
+                offs.push(point);
+                continue;
+            }
+            let split = match (&point.typ, &offs[..]) {
+                (PointType::Curve | PointType::QCurve, [p1]) => split_at_extrema(
+                    kurbo::QuadBez::new(start.to_kurbo(), p1.to_kurbo(), point.to_kurbo()),
+                    |quad| vec![quad.p1],
+                ),
+                (PointType::Curve, [p1, p2]) => split_at_extrema(
+                    kurbo::CubicBez::new(
+                        start.to_kurbo(),
+                        p1.to_kurbo(),
+                        p2.to_kurbo(),
+                        point.to_kurbo(),
+                    ),
+                    |cubic| vec![cubic.p1, cubic.p2],
+                ),
+                _ => Vec::new(),
+            };
+            if split.is_empty() {
+                points.extend(offs.drain(..).cloned());
+            } else {
+                for (idx, (controls, end)) in split.iter().enumerate() {
+                    points.extend(controls.iter().map(|p| {
+                        ContourPoint::new(p.x, p.y, PointType::OffCurve, false, None, None)
+                    }));
+                    if idx + 1 < split.len() {
+                        points.push(ContourPoint::new(
+                            end.x,
+                            end.y,
+                            point.typ.clone(),
+                            true,
+                            None,
+                            None,
+                        ));
+                        inserted += 1;
+                    }
+                }
+                offs.clear();
+            }
+            points.push(point.clone());
+            if step < steps {
+                tail_start = points.len();
+            }
+            start = point;
+        }
+
+        if inserted > 0 {
+            if closed && first_on > 0 {
+                // The contour started with the off-curve points of its closing
+                // segment; start with the (now split) closing segment again.
+                points.rotate_left(tail_start);
+            } else if closed {
+                // The first point was emitted last, closing the contour.
+                points.rotate_right(1);
+            } else {
+                points.insert(0, self.points[0].clone());
+            }
+            self.points = points;
+        }
+        inserted
+    }
+
+    /// Returns the points where the contour crosses itself, in the order of
+    /// the first of the two crossing edges, or of the first pass through a
+    /// point crossed twice.
+    ///
+    /// Curves are first flattened to straight lines within `tolerance` units,
+    /// see [`Contour::flatten`], so the points are approximate for curves and
+    /// exact for lines. Only proper crossings are found: edges crossing each
+    /// other, or the contour passing twice through the same point from one
+    /// side to the other. Places where the contour merely touches itself,
+    /// passes through one of its own points in the middle of an edge, or runs
+    /// along itself are not reported.
+    pub fn self_intersections(&self, tolerance: f64) -> Vec<(f64, f64)> {
+        let polyline = self.flatten(tolerance);
+        let len = polyline.len();
+        let closed = self.is_closed() && len > 2;
+        let edge_count = match closed {
+            true => len,
+            false => len.saturating_sub(1),
+        };
+        let edge = |idx: usize| (polyline[idx], polyline[(idx + 1) % len]);
+        // The points before and after the point at `idx`, if it has both.
+        let neighbors = |idx: usize| match closed {
+            true => Some((polyline[(idx + len - 1) % len], polyline[(idx + 1) % len])),
+            false if idx > 0 && idx + 1 < len => Some((polyline[idx - 1], polyline[idx + 1])),
+            false => None,
+        };
+
+        let mut intersections = Vec::new();
+        for i in 0..edge_count {
+            if let Some(around_i) = neighbors(i) {
+                for j in i + 1..len {
+                    let Some(around_j) = neighbors(j) else { continue };
+                    if polyline[i] == polyline[j]
+                        && crosses_at_point(polyline[i], around_i, around_j)
+                    {
+                        intersections.push(polyline[i]);
+                    }
+                }
+            }
+            // Edges sharing an end point touch but cannot cross.
+            let last = if i == 0 && edge_count == len { edge_count - 1 } else { edge_count };
+            for j in i + 2..last {
+                if let Some(point) = crossing(edge(i), edge(j)) {
+                    intersections.push(point);
+                }
+            }
+        }
+        intersections
+    }
+
+    /// Whether the contour crosses itself, see
+    /// [`Contour::self_intersections`].
+    ///
+    /// Self-intersecting contours render incorrectly with some fill rules
+    /// and are commonly flagged by font QA tools.
+    pub fn has_self_intersections(&self, tolerance: f64) -> bool {
+        !self.self_intersections(tolerance).is_empty()
+    }
+
+    fn simplify_impl(&mut self, tolerance: f64, remove_tagged: bool) -> usize {
+        let len = self.points.len();
+        let closed = self.is_closed();
+        if len < 3 {
+            return 0;
+        }
+
+        // A point may go if both the segment ending at it and the one starting
+        // from it are straight lines.
+        let removable: Vec<bool> = (0..len)
+            .map(|idx| {
+                let point = &self.points[idx];
+                let next = match (idx + 1 < len, closed) {
+                    (true, _) => &self.points[idx + 1],
+                    (false, true) => &self.points[0],
+                    (false, false) => return false,
+                };
+                point.typ == PointType::Line
+                    && next.typ == PointType::Line
+                    && (remove_tagged || !point.is_tagged())
+            })
+            .collect();
+
+        let mut keep = vec![true; len];
+        let Some(first_fixed) = removable.iter().position(|r| !r) else {
+            // A closed polygon made only of lines: anchor the run at the first
+            // point and let it wrap all the way around.
+            let run: Vec<usize> = (0..=len).map(|i| i % len).collect();
+            mark_rdp(&self.points, &run, tolerance, &mut keep);
+            return self.remove_unkept(&keep);
+        };
+
+        // Walk runs of removable points between fixed points.
+        let mut start = first_fixed;
+        let steps = if closed { len } else { len - 1 - first_fixed };
+        let mut run = vec![start];
+        for step in 1..=steps {
+            let idx = (first_fixed + step) % len;
+            run.push(idx);
+            if !removable[idx] {
+                if run.len() > 2 {
+                    mark_rdp(&self.points, &run, tolerance, &mut keep);
+                }
+                start = idx;
+                run.clear();
+                run.push(start);
+            }
+        }
+        self.remove_unkept(&keep)
+    }
+
+    fn remove_unkept(&mut self, keep: &[bool]) -> usize {
+        let before = self.points.len();
+        let mut keep = keep.iter();
+        self.points.retain(|_| *keep.next().unwrap());
+        before - self.points.len()
+    }
+}
+
+/// Splits `curve` at its extrema, returning the control points and end point
+/// of each piece, or nothing if there is no extremum to split at.
+#[cfg(feature = "kurbo")]
+fn split_at_extrema<C: kurbo::ParamCurveExtrema>(
+    curve: C,
+    controls: impl Fn(&C) -> Vec<kurbo::Point>,
+) -> Vec<(Vec<kurbo::Point>, kurbo::Point)> {
+    const MIN_DISTANCE: f64 = 0.001;
+    let (start, end) = (curve.start(), curve.end());
+    let params: Vec<f64> = curve
+        .extrema()
+        .into_iter()
+        .filter(|t| {
+            let point = curve.eval(*t);
+            point.distance(start) > MIN_DISTANCE && point.distance(end) > MIN_DISTANCE
+        })
+        .collect();
+    if params.is_empty() {
+        return Vec::new();
+    }
+    let mut t0 = 0.0;
+    params
+        .into_iter()
+        .chain(std::iter::once(1.0))
+        .map(|t1| {
+            let piece = curve.subsegment(t0..t1);
+            t0 = t1;
+            (controls(&piece), piece.end())
+        })
+        .collect()
+}
+
+impl Glyph {
+    /// Snaps all coordinates to the nearest multiple of `grid`, returning the
+    /// largest distance any single coordinate moved.
+    ///
+    /// The points of the contours, the offsets of the components, the
+    /// anchors, the positions of the guidelines and the advance width and
+    /// height are snapped. Component scale factors and guideline angles are
+    /// left alone. A `grid` of 1 rounds to integers; a `grid` that is not
+    /// positive leaves the glyph unchanged.
+    pub fn quantize(&mut self, grid: f64) -> f64 {
+        if grid.is_nan() || grid <= 0.0 {
+            return 0.0;
+        }
+        let mut max_displacement = 0f64;
+        let mut snap = |value: &mut f64| {
+            let snapped = (*value / grid).round() * grid;
+            max_displacement = max_displacement.max((snapped - *value).abs());
+            *value = snapped;
+        };
+        for point in self.contours.iter_mut().flat_map(|contour| &mut contour.points) {
+            snap(&mut point.x);
+            snap(&mut point.y);
+        }
+        for component in &mut self.components {
+            snap(&mut component.transform.x_offset);
+            snap(&mut component.transform.y_offset);
+        }
+        for anchor in &mut self.anchors {
+            snap(&mut anchor.x);
+            snap(&mut anchor.y);
+        }
+        for guideline in &mut self.guidelines {
+            match &mut guideline.line {
+                Line::Vertical(x) => snap(x),
+                Line::Horizontal(y) => snap(y),
+                Line::Angle { x, y, .. } => {
+                    snap(x);
+                    snap(y);
+                }
+            }
+        }
+        snap(&mut self.width);
+        snap(&mut self.height);
+        max_displacement
+    }
+}
+
+impl ContourPoint {
+    /// Whether the point carries data beyond its position and type.
+    fn is_tagged(&self) -> bool {
+        self.name.is_some() || self.identifier.is_some() || self.lib.is_some()
+    }
+}
+
+/// Marks the interior points of `run` (indices into `points`) that the
+/// Ramer–Douglas–Peucker algorithm discards as not to be kept.
+///
+/// The first and last entries of `run` are always kept.
+fn mark_rdp(points: &[ContourPoint], run: &[usize], tolerance: f64, keep: &mut [bool]) {
+    if run.len() < 3 {
+        return;
+    }
+    let xy = |idx: usize| (points[idx].x, points[idx].y);
+    let (first, last) = (xy(run[0]), xy(run[run.len() - 1]));
+    let (split, max_dist) = run[1..run.len() - 1]
+        .iter()
+        .enumerate()
+        .map(|(i, &idx)| (i + 1, distance_to_segment(xy(idx), first, last)))
+        .fold((0, f64::NEG_INFINITY), |acc, cur| if cur.1 > acc.1 { cur } else { acc });
+
+    if max_dist > tolerance {
+        mark_rdp(points, &run[..=split], tolerance, keep);
+        mark_rdp(points, &run[split..], tolerance, keep);
+    } else {
+        for &idx in &run[1..run.len() - 1] {
+            keep[idx] = false;
+        }
+    }
+}
+
+/// Returns whether the paths from `a.0` through `p` to `a.1` and from `b.0`
+/// through `p` to `b.1` cross at `p` rather than touch: `b.0` and `b.1` lie
+/// strictly on either side of the angle the first path makes at `p`.
+fn crosses_at_point(
+    p: (f64, f64),
+    a: ((f64, f64), (f64, f64)),
+    b: ((f64, f64), (f64, f64)),
+) -> bool {
+    let angle = |q: (f64, f64)| (q.1 - p.1).atan2(q.0 - p.0);
+    let start = angle(a.0);
+    let turn = |q| (angle(q) - start).rem_euclid(std::f64::consts::TAU);
+    let (end, b0, b1) = (turn(a.1), turn(b.0), turn(b.1));
+    let inside = |t: f64| t > 0.0 && t < end;
+    (inside(b0) && b1 > end) || (b0 > end && inside(b1))
+}
+
+/// Returns the point where the edges `a` and `b` cross, if they do so
+/// properly: each one's end points lying strictly on either side of the
+/// other.
+fn crossing(a: ((f64, f64), (f64, f64)), b: ((f64, f64), (f64, f64))) -> Option<(f64, f64)> {
+    let cross = |o: (f64, f64), p: (f64, f64), q: (f64, f64)| {
+        (p.0 - o.0) * (q.1 - o.1) - (p.1 - o.1) * (q.0 - o.0)
+    };
+    let (a0, a1) = (cross(b.0, b.1, a.0), cross(b.0, b.1, a.1));
+    let (b0, b1) = (cross(a.0, a.1, b.0), cross(a.0, a.1, b.1));
+    if a0 * a1 >= 0.0 || b0 * b1 >= 0.0 {
+        return None;
+    }
+    let t = a0 / (a0 - a1);
+    Some((a.0 .0 + t * (a.1 .0 - a.0 .0), a.0 .1 + t * (a.1 .1 - a.0 .1)))
+}
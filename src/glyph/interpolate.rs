@@ -0,0 +1,141 @@
+//! Checking glyphs for interpolation compatibility and interpolating them.
+
+use super::{AffineTransform, Contour, Glyph, PointType};
+use crate::error::IncompatibilityReason;
+use crate::Line;
+
+impl Contour {
+    /// Returns the types of the contour's points, in order.
+    ///
+    /// This is the structure that must match between masters for the contour
+    /// to be interpolated, see [`Glyph::is_compatible`]: comparing signatures
+    /// is a quick way to spot incompatible contours.
+    pub fn node_signature(&self) -> Vec<PointType> {
+        self.points.iter().map(|point| point.typ.clone()).collect()
+    }
+}
+
+impl Glyph {
+    /// Checks whether this glyph and `other` can be interpolated.
+    ///
+    /// Two glyphs are compatible if they have the same number of contours,
+    /// each contour has the same number of points with the same
+    /// [`PointType`](super::PointType)s in the same order, and they have the
+    /// same number of components referencing the same base glyphs in the same
+    /// order.
+    ///
+    /// Returns the first mismatch found, checking contours before components.
+    pub fn is_compatible(&self, other: &Glyph) -> Result<(), IncompatibilityReason> {
+        if self.contours.len() != other.contours.len() {
+            return Err(IncompatibilityReason::ContourCount {
+                expected: self.contours.len(),
+                found: other.contours.len(),
+            });
+        }
+        for (contour, (a, b)) in self.contours.iter().zip(&other.contours).enumerate() {
+            if a.points.len() != b.points.len() {
+                return Err(IncompatibilityReason::PointCount {
+                    contour,
+                    expected: a.points.len(),
+                    found: b.points.len(),
+                });
+            }
+            for (point, (a, b)) in a.points.iter().zip(&b.points).enumerate() {
+                if a.typ != b.typ {
+                    return Err(IncompatibilityReason::PointType {
+                        contour,
+                        point,
+                        expected: a.typ.clone(),
+                        found: b.typ.clone(),
+                    });
+                }
+            }
+        }
+
+        if self.components.len() != other.components.len() {
+            return Err(IncompatibilityReason::ComponentCount {
+                expected: self.components.len(),
+                found: other.components.len(),
+            });
+        }
+        for (component, (a, b)) in self.components.iter().zip(&other.components).enumerate() {
+            if a.base != b.base {
+                return Err(IncompatibilityReason::ComponentBase {
+                    component,
+                    expected: a.base.clone(),
+                    found: b.base.clone(),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Linearly interpolates between the compatible glyphs `a` and `b`.
+    ///
+    /// A `t` of `0.0` yields the geometry of `a` and a `t` of `1.0` that of
+    /// `b`; other values blend between, or extrapolate beyond, the two.
+    ///
+    /// The advance width and height, point coordinates, anchor positions,
+    /// component transforms and guideline positions and angles are
+    /// interpolated. Anchors and guidelines are matched up by index.
+    /// Everything else, such as the name, codepoints, point types, identifiers
+    /// and libs, is taken from `a`.
+    ///
+    /// Returns an error if the glyphs are not compatible according to
+    /// [`Glyph::is_compatible`], or if they have a different number of anchors
+    /// or guidelines, or a guideline has a different orientation in each.
+    pub fn interpolate(a: &Glyph, b: &Glyph, t: f64) -> Result<Glyph, IncompatibilityReason> {
+        a.is_compatible(b)?;
+        if a.anchors.len() != b.anchors.len() {
+            return Err(IncompatibilityReason::AnchorCount {
+                expected: a.anchors.len(),
+                found: b.anchors.len(),
+            });
+        }
+        if a.guidelines.len() != b.guidelines.len() {
+            return Err(IncompatibilityReason::GuidelineCount {
+                expected: a.guidelines.len(),
+                found: b.guidelines.len(),
+            });
+        }
+
+        let lerp = |a: f64, b: f64| a + (b - a) * t;
+        let mut glyph = a.clone();
+        glyph.width = lerp(a.width, b.width);
+        glyph.height = lerp(a.height, b.height);
+        for (contour, other) in glyph.contours.iter_mut().zip(&b.contours) {
+            for (point, other) in contour.points.iter_mut().zip(&other.points) {
+                point.x = lerp(point.x, other.x);
+                point.y = lerp(point.y, other.y);
+            }
+        }
+        for (anchor, other) in glyph.anchors.iter_mut().zip(&b.anchors) {
+            anchor.x = lerp(anchor.x, other.x);
+            anchor.y = lerp(anchor.y, other.y);
+        }
+        for (component, other) in glyph.components.iter_mut().zip(&b.components) {
+            let (m, n) = (component.transform, other.transform);
+            component.transform = AffineTransform {
+                x_scale: lerp(m.x_scale, n.x_scale),
+                xy_scale: lerp(m.xy_scale, n.xy_scale),
+                yx_scale: lerp(m.yx_scale, n.yx_scale),
+                y_scale: lerp(m.y_scale, n.y_scale),
+                x_offset: lerp(m.x_offset, n.x_offset),
+                y_offset: lerp(m.y_offset, n.y_offset),
+            };
+        }
+        for (idx, (guideline, other)) in glyph.guidelines.iter_mut().zip(&b.guidelines).enumerate()
+        {
+            guideline.line = match (&guideline.line, &other.line) {
+                (&Line::Vertical(x1), &Line::Vertical(x2)) => Line::Vertical(lerp(x1, x2)),
+                (&Line::Horizontal(y1), &Line::Horizontal(y2)) => Line::Horizontal(lerp(y1, y2)),
+                (
+                    &Line::Angle { x: x1, y: y1, degrees: d1 },
+                    &Line::Angle { x: x2, y: y2, degrees: d2 },
+                ) => Line::Angle { x: lerp(x1, x2), y: lerp(y1, y2), degrees: lerp(d1, d2) },
+                _ => return Err(IncompatibilityReason::GuidelineKind(idx)),
+            };
+        }
+        Ok(glyph)
+    }
+}
@@ -0,0 +1,512 @@
+//! Outline bounds and the spacing metrics derived from them.
+
+use std::collections::HashMap;
+
+use super::segments::Segment;
+use super::{AffineTransform, Contour, Glyph, PointType};
+use crate::shared_types::PUBLIC_VERTICAL_ORIGIN_KEY;
+use crate::{Layer, Line, Name};
+
+/// Extends `bounds`, given as `(x_min, y_min, x_max, y_max)`, to include the
+/// point `(x, y)`.
+pub(crate) fn extend_bounds(
+    bounds: Option<(f64, f64, f64, f64)>,
+    (x, y): (f64, f64),
+) -> Option<(f64, f64, f64, f64)> {
+    Some(match bounds {
+        Some((x_min, y_min, x_max, y_max)) => {
+            (x_min.min(x), y_min.min(y), x_max.max(x), y_max.max(y))
+        }
+        None => (x, y, x, y),
+    })
+}
+
+/// Returns whether `c` is a combining diacritical mark or a default-ignorable
+/// code point, which are displayed without an advance width.
+fn is_zero_width_codepoint(c: char) -> bool {
+    matches!(
+        c,
+        // Combining diacritical mark blocks.
+        '\u{0300}'..='\u{036F}'
+            | '\u{1AB0}'..='\u{1AFF}'
+            | '\u{1DC0}'..='\u{1DFF}'
+            | '\u{20D0}'..='\u{20FF}'
+            | '\u{FE20}'..='\u{FE2F}'
+            // Default_Ignorable_Code_Point, besides U+034F above.
+            | '\u{00AD}'
+            | '\u{061C}'
+            | '\u{115F}'..='\u{1160}'
+            | '\u{17B4}'..='\u{17B5}'
+            | '\u{180B}'..='\u{180F}'
+            | '\u{200B}'..='\u{200F}'
+            | '\u{202A}'..='\u{202E}'
+            | '\u{2060}'..='\u{206F}'
+            | '\u{3164}'
+            | '\u{FE00}'..='\u{FE0F}'
+            | '\u{FEFF}'
+            | '\u{FFA0}'
+            | '\u{FFF0}'..='\u{FFF8}'
+            | '\u{1BCA0}'..='\u{1BCA3}'
+            | '\u{1D173}'..='\u{1D17A}'
+            | '\u{E0000}'..='\u{E0FFF}'
+    )
+}
+
+/// Extends `bounds` to include the segment, including its curve extrema.
+fn extend_bounds_by_segment(
+    bounds: Option<(f64, f64, f64, f64)>,
+    segment: &Segment,
+) -> Option<(f64, f64, f64, f64)> {
+    let bounds = extend_bounds(extend_bounds(bounds, segment.start()), segment.end());
+    segment
+        .extrema_params()
+        .into_iter()
+        .fold(bounds, |bounds, t| extend_bounds(bounds, segment.eval(t)))
+}
+
+/// Computes glyph [bounds](Glyph::bounds) like [`Glyph::bounds_with`],
+/// caching the bounds of the base glyphs of components.
+///
+/// The bounds of a base glyph are computed once and then translated and
+/// scaled for each component using it. Components that rotate or skew their
+/// base glyph cannot reuse cached bounds, so their base glyph's geometry is
+/// transformed as [`Glyph::bounds`] does.
+///
+/// The resolver borrows its layer, so the base glyphs cannot change while it
+/// is alive and the cache never goes stale. Create a new resolver after
+/// editing the layer.
+///
+/// # Examples
+///
+/// ```
+/// # use norad::{AffineTransform, Component, ComponentResolver, Contour, ContourPoint, Glyph, Layer, Name, PointType};
+/// let mut layer = Layer::default();
+/// let mut base = Glyph::new("dot");
+/// let point = |x, y| ContourPoint::new(x, y, PointType::Line, false, None, None);
+/// base.contours.push(Contour::new(vec![point(0., 0.), point(10., 0.), point(10., 10.)], None));
+/// layer.insert_glyph(base);
+///
+/// let mut glyph = Glyph::new("colon");
+/// let offset = AffineTransform { y_offset: 100., ..Default::default() };
+/// glyph.components.push(Component::new(Name::new("dot").unwrap(), AffineTransform::default(), None));
+/// glyph.components.push(Component::new(Name::new("dot").unwrap(), offset, None));
+///
+/// let mut resolver = ComponentResolver::new(&layer);
+/// assert_eq!(glyph.bounds_with(&mut resolver), Some((0., 0., 10., 110.)));
+/// ```
+#[derive(Debug)]
+pub struct ComponentResolver<'a> {
+    layer: &'a Layer,
+    cache: HashMap<Name, Option<(f64, f64, f64, f64)>>,
+}
+
+impl<'a> ComponentResolver<'a> {
+    /// Returns a resolver for components whose base glyphs are in `layer`.
+    pub fn new(layer: &'a Layer) -> Self {
+        ComponentResolver { layer, cache: HashMap::new() }
+    }
+
+    /// Returns the bounds of the glyph named `name` in the layer, computing
+    /// them if they are not cached yet, and whether a cycle was cut off.
+    fn base_bounds(
+        &mut self,
+        name: &Name,
+        stack: &mut Vec<Name>,
+    ) -> (Option<(f64, f64, f64, f64)>, bool) {
+        if let Some(bounds) = self.cache.get(name) {
+            return (*bounds, false);
+        }
+        let Some(glyph) = self.layer.get_glyph(name) else { return (None, false) };
+        stack.push(name.clone());
+        let (bounds, cyclic) = self.extend(glyph, AffineTransform::default(), stack, None);
+        stack.pop();
+        // Where a cycle is cut off depends on the glyph it was entered from.
+        if !cyclic {
+            self.cache.insert(name.clone(), bounds);
+        }
+        (bounds, cyclic)
+    }
+
+    /// Extends `bounds` by `glyph` with `transform` applied, returning whether
+    /// a cycle was cut off.
+    fn extend(
+        &mut self,
+        glyph: &Glyph,
+        transform: AffineTransform,
+        stack: &mut Vec<Name>,
+        bounds: Option<(f64, f64, f64, f64)>,
+    ) -> (Option<(f64, f64, f64, f64)>, bool) {
+        let layer = self.layer;
+        let mut bounds = glyph
+            .contours
+            .iter()
+            .fold(bounds, |bounds, contour| contour.transformed_bounds(transform, bounds));
+        let mut cyclic = false;
+        for component in &glyph.components {
+            let Some(base) = layer.get_glyph(&component.base) else { continue };
+            if stack.contains(&component.base) {
+                cyclic = true;
+                continue;
+            }
+            let transform = transform.concat(component.transform);
+            let base_cyclic;
+            if transform.xy_scale == 0.0 && transform.yx_scale == 0.0 {
+                let base_bounds;
+                (base_bounds, base_cyclic) = self.base_bounds(&component.base, stack);
+                if let Some((x_min, y_min, x_max, y_max)) = base_bounds {
+                    bounds = extend_bounds(bounds, transform.transform_point((x_min, y_min)));
+                    bounds = extend_bounds(bounds, transform.transform_point((x_max, y_max)));
+                }
+            } else {
+                stack.push(component.base.clone());
+                (bounds, base_cyclic) = self.extend(base, transform, stack, bounds);
+                stack.pop();
+            }
+            cyclic |= base_cyclic;
+        }
+        (bounds, cyclic)
+    }
+}
+
+impl Contour {
+    /// Returns the tight bounding box of the contour as
+    /// `(x_min, y_min, x_max, y_max)`, or `None` if it has no points.
+    ///
+    /// Unlike [`ControlBounds`](super::ControlBounds), this accounts for the
+    /// actual extent of curves rather than that of their off-curve points.
+    pub fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.transformed_bounds(AffineTransform::default(), None)
+    }
+
+    /// Returns the signed area enclosed by the contour, positive if it runs
+    /// counter-clockwise and negative if it runs clockwise.
+    ///
+    /// Curves are accounted for exactly. An open contour is measured as if
+    /// closed by a straight line back to its first point.
+    pub fn signed_area(&self) -> f64 {
+        let segments = self.segments();
+        let closing = match (segments.first(), segments.last()) {
+            (Some(first), Some(last)) if self.is_open() => {
+                Segment::Line(last.end(), first.start()).area()
+            }
+            _ => 0.0,
+        };
+        segments.iter().map(Segment::area).sum::<f64>() + closing
+    }
+
+    fn transformed_bounds(
+        &self,
+        transform: AffineTransform,
+        bounds: Option<(f64, f64, f64, f64)>,
+    ) -> Option<(f64, f64, f64, f64)> {
+        // A lone move point has no segments but still counts.
+        let bounds = self.points.iter().filter(|point| point.typ != PointType::OffCurve).fold(
+            bounds,
+            |bounds, point| {
+                let mut point = point.clone();
+                point.transform(transform);
+                extend_bounds(bounds, (point.x, point.y))
+            },
+        );
+        self.segments().into_iter().fold(bounds, |bounds, segment| {
+            extend_bounds_by_segment(bounds, &segment.transform(transform))
+        })
+    }
+}
+
+impl Glyph {
+    /// Returns the tight bounding box of the glyph's outline as
+    /// `(x_min, y_min, x_max, y_max)`, or `None` if the outline is empty.
+    ///
+    /// Components are resolved against `layer`, transforming the base glyphs'
+    /// actual geometry, so rotated or skewed components are bounded
+    /// precisely. Components whose base glyph is missing from `layer`, or that
+    /// would form a cycle, are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::{AffineTransform, Component, Contour, ContourPoint, Glyph, Layer, Name, PointType};
+    /// let mut layer = Layer::default();
+    /// let mut base = Glyph::new("dot");
+    /// let point = |x, y| ContourPoint::new(x, y, PointType::Line, false, None, None);
+    /// base.contours.push(Contour::new(vec![point(0., 0.), point(10., 0.), point(10., 10.)], None));
+    /// layer.insert_glyph(base);
+    ///
+    /// let mut glyph = Glyph::new("colon");
+    /// let offset = AffineTransform { y_offset: 100., ..Default::default() };
+    /// glyph.components.push(Component::new(Name::new("dot").unwrap(), AffineTransform::default(), None));
+    /// glyph.components.push(Component::new(Name::new("dot").unwrap(), offset, None));
+    /// assert_eq!(glyph.bounds(&layer), Some((0., 0., 10., 110.)));
+    /// ```
+    pub fn bounds(&self, layer: &Layer) -> Option<(f64, f64, f64, f64)> {
+        let mut bounds = None;
+        self.visit_resolved_contours(layer, |contour, transform| {
+            bounds = contour.transformed_bounds(transform, bounds);
+        });
+        bounds
+    }
+
+    /// Returns the tight bounding box of the glyph's outline like
+    /// [`Glyph::bounds`], reusing the base glyph bounds cached by `resolver`.
+    ///
+    /// This is faster when computing the bounds of many composite glyphs
+    /// sharing base glyphs. The results are the same as those of
+    /// [`Glyph::bounds`], up to floating point rounding, except for glyphs
+    /// whose components form a cycle, where the cycle may be cut off at a
+    /// different component.
+    pub fn bounds_with(&self, resolver: &mut ComponentResolver) -> Option<(f64, f64, f64, f64)> {
+        let mut stack = vec![self.name.clone()];
+        resolver.extend(self, AffineTransform::default(), &mut stack, None).0
+    }
+
+    /// Calls `f` with each contour of the glyph and, recursively, of its
+    /// components' base glyphs in `layer`, along with the transform to apply
+    /// to it.
+    ///
+    /// Components whose base glyph is missing from `layer`, or that would form
+    /// a cycle, are skipped.
+    pub(crate) fn visit_resolved_contours(
+        &self,
+        layer: &Layer,
+        mut f: impl FnMut(&Contour, AffineTransform),
+    ) {
+        fn visit<'a>(
+            glyph: &'a Glyph,
+            transform: AffineTransform,
+            layer: &'a Layer,
+            stack: &mut Vec<&'a Name>,
+            f: &mut impl FnMut(&Contour, AffineTransform),
+        ) {
+            for contour in &glyph.contours {
+                f(contour, transform);
+            }
+            for component in &glyph.components {
+                let Some(base) = layer.get_glyph(&component.base) else { continue };
+                if stack.contains(&&component.base) {
+                    continue;
+                }
+                stack.push(&component.base);
+                visit(base, transform.concat(component.transform), layer, stack, f);
+                stack.pop();
+            }
+        }
+
+        visit(self, AffineTransform::default(), layer, &mut vec![&self.name], &mut f);
+    }
+
+    /// Returns the indices of the contours whose [area](Contour::signed_area),
+    /// regardless of direction, is below `area_threshold`.
+    ///
+    /// Such contours are usually stray artifacts, such as leftover single
+    /// points or slivers from an accidental double-click.
+    pub fn tiny_contours(&self, area_threshold: f64) -> Vec<usize> {
+        self.contours
+            .iter()
+            .enumerate()
+            .filter(|(_, contour)| contour.signed_area().abs() < area_threshold)
+            .map(|(idx, _)| idx)
+            .collect()
+    }
+
+    /// Returns the left side bearing: the distance from the origin to the left
+    /// edge of the outline's [bounds](Glyph::bounds), resolving components
+    /// against `layer`.
+    ///
+    /// Together with the bounds' width and the
+    /// [right side bearing](Glyph::right_side_bearing), it adds up to the
+    /// advance width.
+    ///
+    /// Returns `None` if the outline is empty.
+    pub fn left_side_bearing(&self, layer: &Layer) -> Option<f64> {
+        self.bounds(layer).map(|(x_min, ..)| x_min)
+    }
+
+    /// Returns the right side bearing: the distance from the right edge of the
+    /// outline's [bounds](Glyph::bounds) to the advance width, resolving
+    /// components against `layer`.
+    ///
+    /// Returns `None` if the outline is empty.
+    pub fn right_side_bearing(&self, layer: &Layer) -> Option<f64> {
+        self.bounds(layer).map(|(_, _, x_max, _)| self.width - x_max)
+    }
+
+    /// Sets the left side bearing to `value` by moving the glyph horizontally,
+    /// adjusting the advance width so the right side bearing stays the same.
+    ///
+    /// The contours, components, anchors and guidelines are all moved, while
+    /// the [bounds](Glyph::bounds) are computed with components resolved
+    /// against `layer`. Does nothing if the outline is empty.
+    pub fn set_left_side_bearing(&mut self, value: f64, layer: &Layer) {
+        let Some(current) = self.left_side_bearing(layer) else { return };
+        let delta = value - current;
+        self.move_horizontally(delta);
+        self.width += delta;
+    }
+
+    /// Sets the right side bearing to `value` by changing the advance width,
+    /// leaving the outline in place.
+    ///
+    /// The [bounds](Glyph::bounds) are computed with components resolved
+    /// against `layer`. Does nothing if the outline is empty.
+    pub fn set_right_side_bearing(&mut self, value: f64, layer: &Layer) {
+        if let Some((_, _, x_max, _)) = self.bounds(layer) {
+            self.width = x_max + value;
+        }
+    }
+
+    /// Sets any two of the left side bearing `lsb`, the right side bearing
+    /// `rsb` and the advance `width`, deriving the third, by moving the glyph
+    /// horizontally and setting its width.
+    ///
+    /// The glyph is moved as by [`Glyph::set_left_side_bearing`], with the
+    /// [bounds](Glyph::bounds) computed resolving components against `layer`.
+    /// If all three are given, `lsb` and `width` take precedence and `rsb` is
+    /// ignored. If only one is given, it is set as by the single-value
+    /// setters: the other side bearing stays the same for `lsb` and `rsb`,
+    /// while `width` leaves the outline in place. If the outline is empty,
+    /// only `width` is applied.
+    pub fn set_metrics(
+        &mut self,
+        lsb: Option<f64>,
+        rsb: Option<f64>,
+        width: Option<f64>,
+        layer: &Layer,
+    ) {
+        let Some((x_min, _, x_max, _)) = self.bounds(layer) else {
+            if let Some(width) = width {
+                self.width = width;
+            }
+            return;
+        };
+        let ink = x_max - x_min;
+        let (lsb, width) = match (lsb, rsb, width) {
+            (Some(lsb), _, Some(width)) => (Some(lsb), width),
+            (Some(lsb), Some(rsb), None) => (Some(lsb), lsb + ink + rsb),
+            (None, Some(rsb), Some(width)) => (Some(width - rsb - ink), width),
+            (Some(lsb), None, None) => (Some(lsb), self.width + lsb - x_min),
+            (None, Some(rsb), None) => (None, x_max + rsb),
+            (None, None, Some(width)) => (None, width),
+            (None, None, None) => return,
+        };
+        if let Some(lsb) = lsb {
+            self.move_horizontally(lsb - x_min);
+        }
+        self.width = width;
+    }
+
+    /// Sets the advance width to `default_width` if the glyph has none,
+    /// returning whether it was changed.
+    ///
+    /// The UFO specification treats a missing `advance` element or `width`
+    /// attribute as a width of zero, so a width of zero counts as none, except
+    /// for glyphs that are meant to have a zero width:
+    ///
+    /// - glyphs with a combining mark or a default-ignorable codepoint, such
+    ///   as U+0301 COMBINING ACUTE ACCENT or U+200D ZERO WIDTH JOINER;
+    /// - glyphs without codepoints and without an outline, such as `.null`.
+    ///
+    /// Combining marks are only recognized in the combining diacritical mark
+    /// blocks; use [`Font::glyph_category`](crate::Font::glyph_category) to
+    /// tell other marks apart.
+    pub fn ensure_advance(&mut self, default_width: f64) -> bool {
+        let missing = self.is_missing_advance();
+        if missing {
+            self.width = default_width;
+        }
+        missing
+    }
+
+    /// Returns whether [`Glyph::ensure_advance`] would change the glyph.
+    pub(crate) fn is_missing_advance(&self) -> bool {
+        let zero_width = self.codepoints.iter().any(is_zero_width_codepoint)
+            || (self.codepoints.is_empty()
+                && self.contours.is_empty()
+                && self.components.is_empty());
+        self.width == 0.0 && !zero_width
+    }
+
+    /// Adds `delta` to the advance width, for tracking: loosening or
+    /// tightening the spacing of all glyphs alike.
+    ///
+    /// A glyph without an advance width, i.e. with a width of zero, ends up
+    /// with a width of `delta`. If `center` is true, the contours,
+    /// components, anchors and guidelines are also moved right by half of
+    /// `delta`, so the extra space is split evenly between both sides;
+    /// otherwise the outline stays in place and only the right side bearing
+    /// changes.
+    pub fn adjust_tracking(&mut self, delta: f64, center: bool) {
+        self.width += delta;
+        if center {
+            self.move_horizontally(delta / 2.0);
+        }
+    }
+
+    /// Returns the y coordinate of the glyph's origin for vertical layout,
+    /// stored under the `public.verticalOrigin` lib key.
+    ///
+    /// Returns `None` if the key is missing or is not a number.
+    pub fn vertical_origin(&self) -> Option<f64> {
+        let value = self.lib.get(PUBLIC_VERTICAL_ORIGIN_KEY)?;
+        value.as_real().or_else(|| value.as_signed_integer().map(|v| v as f64))
+    }
+
+    /// Sets or, if `origin` is `None`, removes the glyph's vertical origin.
+    pub fn set_vertical_origin(&mut self, origin: Option<f64>) {
+        match origin {
+            Some(origin) => self.lib.insert(PUBLIC_VERTICAL_ORIGIN_KEY.into(), origin.into()),
+            None => self.lib.remove(PUBLIC_VERTICAL_ORIGIN_KEY),
+        };
+    }
+
+    /// Returns the top side bearing: the distance from the top edge of the
+    /// outline's [bounds](Glyph::bounds) up to the
+    /// [vertical origin](Glyph::vertical_origin), resolving components against
+    /// `layer`.
+    ///
+    /// This mirrors [`Glyph::left_side_bearing`] for vertical layout, where
+    /// the advance height runs downwards from the vertical origin.
+    ///
+    /// Returns `None` if the outline is empty or the glyph has no vertical
+    /// origin. Compilers usually fall back to the font's ascender for the
+    /// latter, which is not known at the glyph level.
+    pub fn top_side_bearing(&self, layer: &Layer) -> Option<f64> {
+        let origin = self.vertical_origin()?;
+        self.bounds(layer).map(|(_, _, _, y_max)| origin - y_max)
+    }
+
+    /// Returns the bottom side bearing: the distance from the bottom of the
+    /// advance height, measured down from the
+    /// [vertical origin](Glyph::vertical_origin), up to the bottom edge of the
+    /// outline's [bounds](Glyph::bounds), resolving components against
+    /// `layer`.
+    ///
+    /// Together with the bounds' height and the
+    /// [top side bearing](Glyph::top_side_bearing), it adds up to the advance
+    /// height.
+    ///
+    /// Returns `None` if the outline is empty or the glyph has no vertical
+    /// origin.
+    pub fn bottom_side_bearing(&self, layer: &Layer) -> Option<f64> {
+        let origin = self.vertical_origin()?;
+        self.bounds(layer).map(|(_, y_min, ..)| y_min - (origin - self.height))
+    }
+
+    fn move_horizontally(&mut self, delta: f64) {
+        for point in self.contours.iter_mut().flat_map(|contour| &mut contour.points) {
+            point.x += delta;
+        }
+        for component in &mut self.components {
+            component.transform.x_offset += delta;
+        }
+        for anchor in &mut self.anchors {
+            anchor.x += delta;
+        }
+        for guideline in &mut self.guidelines {
+            match &mut guideline.line {
+                Line::Vertical(x) | Line::Angle { x, .. } => *x += delta,
+                Line::Horizontal(_) => (),
+            }
+        }
+    }
+}
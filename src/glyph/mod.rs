@@ -1,31 +1,69 @@
 //! Data related to individual glyphs.
 
 pub mod builder;
+mod canonical;
 mod codepoints;
+mod components;
+mod filter;
+mod geometry;
+mod interpolate;
+mod metrics;
 mod parse;
+mod pen;
+mod segments;
+mod selection;
+mod serde_impls;
 mod serialize;
+mod svg;
 #[cfg(test)]
-mod tests;
+pub(crate) mod tests;
+mod truetype;
+mod visitor;
+mod winding;
 
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::io::Read;
 use std::path::{Path, PathBuf};
 
+use serde::{Deserialize, Serialize};
+
 #[cfg(feature = "kurbo")]
 use crate::error::ConvertContourError;
 
-use crate::error::{ErrorKind, GlifLoadError, GlifWriteError, StoreError};
+use crate::error::{
+    DuplicateAnchorsError, ErrorKind, GlifError, GlifLoadError, GlifWriteError, StoreError,
+};
+use crate::identifier::IdentifierSource;
+#[cfg(feature = "object-libs")]
+use crate::identifier::UuidV4Identifiers;
 use crate::name::Name;
 use crate::names::NameList;
-use crate::shared_types::PUBLIC_OBJECT_LIBS_KEY;
+use crate::shared_types::{PUBLIC_MARK_COLOR_KEY, PUBLIC_OBJECT_LIBS_KEY};
 use crate::{Color, Guideline, Identifier, Line, Plist, WriteOptions};
 
+pub use builder::ContourBuilder;
 pub use codepoints::Codepoints;
+pub use filter::OutlineFilter;
+pub use geometry::Extrema;
+pub use metrics::ComponentResolver;
+pub use pen::PointTuple;
+pub use selection::{ObjectRef, ObjectRefMut, PointSelection};
+pub use truetype::TrueTypeInstructions;
+pub use visitor::{ControlBounds, OutlineVisitor};
 
 /// A glyph, loaded from a [`.glif` file][glif].
 ///
 /// Norad can load glif version 1.0 and 2.0, and can save 2.0 only.
 ///
+/// Glyphs also implement [`Serialize`] and [`Deserialize`], for exporting them
+/// to formats such as JSON. All data is kept, including object libs, while
+/// empty and missing values are left out. Lib values map to their natural
+/// counterparts, except that formats without native support for them will
+/// store data as an array of bytes and dates as strings, which are not read
+/// back as data and dates.
+///
 /// [glif]: http://unifiedfontobject.org/versions/ufo3/glyphs/glif/
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Glyph {
     /// The name of the glyph.
     pub(crate) name: Name,
@@ -36,31 +74,103 @@ pub struct Glyph {
     /// A collection of glyph Unicode code points.
     ///
     /// The first entry defines the primary Unicode value for this glyph.
+    #[serde(default, skip_serializing_if = "Codepoints::is_empty")]
     pub codepoints: Codepoints,
     /// Arbitrary glyph note.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub note: Option<String>,
     /// A collection of glyph guidelines.
+    #[serde(default, skip_serializing_if = "Vec::is_empty", with = "serde_impls::guidelines")]
     pub guidelines: Vec<Guideline>,
     /// A collection of glyph anchors.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub anchors: Vec<Anchor>,
     /// A collection of glyph components.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub components: Vec<Component>,
     /// A collection of glyph contours.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub contours: Vec<Contour>,
     /// Glyph image data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub image: Option<Image>,
     /// Glyph library data.
+    #[serde(default, skip_serializing_if = "Plist::is_empty")]
     pub lib: Plist,
 }
 
+/// A major version of the [`.glif` format][glif].
+///
+/// Versions are ordered, so features can be gated on a minimum version:
+///
+/// ```
+/// # use norad::GlifVersion;
+/// assert!(GlifVersion::V2 >= GlifVersion::V1);
+/// assert_eq!(GlifVersion::V2.as_u8(), 2);
+/// ```
+///
+/// [glif]: http://unifiedfontobject.org/versions/ufo3/glyphs/glif/
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(u8)]
+pub enum GlifVersion {
+    /// Glif format version 1. Only reading is supported.
+    V1 = 1,
+    /// Glif format version 2.
+    V2 = 2,
+}
+
+impl GlifVersion {
+    /// Returns the version number, as written in the `format` attribute.
+    pub fn as_u8(self) -> u8 {
+        self as u8
+    }
+
+    /// Returns the version with the given number, if it is known.
+    pub(crate) fn from_u8(version: u8) -> Option<Self> {
+        match version {
+            1 => Some(GlifVersion::V1),
+            2 => Some(GlifVersion::V2),
+            _ => None,
+        }
+    }
+}
+
 impl Glyph {
     /// Attempt to parse a `Glyph` from a [`.glif`] at the provided path.
     ///
+    /// The file must be encoded in UTF-8, optionally starting with a byte order
+    /// mark; an XML declaration naming any other encoding is an error.
+    ///
     /// [`.glif`]: http://unifiedfontobject.org/versions/ufo3/glyphs/glif/
     pub fn load(path: impl AsRef<Path>) -> Result<Self, GlifLoadError> {
         let path = path.as_ref();
         let names = NameList::default();
-        Glyph::load_with_names(path, &names)
+        Glyph::load_with_names(path, &names, false)
+    }
+
+    /// Parses a `Glyph` from the [`.glif`] data read from `reader`.
+    ///
+    /// The whole input must be a single glyph: anything but comments and
+    /// whitespace after the closing `glyph` tag is an error.
+    ///
+    /// [`.glif`]: http://unifiedfontobject.org/versions/ufo3/glyphs/glif/
+    pub fn from_reader(mut reader: impl Read) -> Result<Self, GlifLoadError> {
+        let mut data = Vec::new();
+        reader.read_to_end(&mut data)?;
+        let names = NameList::default();
+        parse::GlifParser::from_xml(&data, Some(&names), false)
+    }
+
+    /// Parses a `Glyph` from [`.glif`] data, like [`Glyph::from_reader`].
+    ///
+    /// On failure, the returned [`GlifError`] also records where in `data` the
+    /// problem was found, and can [show the surrounding lines](GlifError::context).
+    ///
+    /// [`.glif`]: http://unifiedfontobject.org/versions/ufo3/glyphs/glif/
+    pub fn from_bytes(data: &[u8]) -> Result<Self, GlifError> {
+        let names = NameList::default();
+        parse::GlifParser::from_xml_with_position(data, Some(&names), false)
+            .map_err(|(source, position)| GlifError { source, position })
     }
 
     /// THIS IS NOT STABLE API!
@@ -69,17 +179,21 @@ impl Glyph {
     #[doc(hidden)]
     pub fn parse_raw(xml: &[u8]) -> Result<Self, GlifLoadError> {
         let names = NameList::default();
-        parse::GlifParser::from_xml(xml, Some(&names))
+        parse::GlifParser::from_xml(xml, Some(&names), false)
     }
 
     /// Attempt to load the glyph at `path`, reusing names from the `NameList`.
     ///
     /// This uses string interning to reuse allocations when a glyph name
     /// occurs multiple times (such as in components or in different layers).
-    pub(crate) fn load_with_names(path: &Path, names: &NameList) -> Result<Self, GlifLoadError> {
+    pub(crate) fn load_with_names(
+        path: &Path,
+        names: &NameList,
+        strict_object_libs: bool,
+    ) -> Result<Self, GlifLoadError> {
         std::fs::read(path)
             .map_err(GlifLoadError::Io)
-            .and_then(|data| parse::GlifParser::from_xml(&data, Some(names)))
+            .and_then(|data| parse::GlifParser::from_xml(&data, Some(names), strict_object_libs))
     }
 
     #[doc(hidden)]
@@ -94,16 +208,23 @@ impl Glyph {
         path: &Path,
         opts: &WriteOptions,
     ) -> Result<(), GlifWriteError> {
-        if self.lib.contains_key(PUBLIC_OBJECT_LIBS_KEY) {
-            return Err(GlifWriteError::PreexistingPublicObjectLibsKey);
-        }
-
-        let data = self.encode_xml_with_options(opts)?;
+        let data = self.encode_for_writing(opts)?;
         close_already::fs::write(path, data).map_err(GlifWriteError::Io)?;
 
         Ok(())
     }
 
+    /// Serializes the glyph as it is written to disk when saving.
+    pub(crate) fn encode_for_writing(
+        &self,
+        opts: &WriteOptions,
+    ) -> Result<Vec<u8>, GlifWriteError> {
+        if self.lib.contains_key(PUBLIC_OBJECT_LIBS_KEY) {
+            return Err(GlifWriteError::PreexistingPublicObjectLibsKey);
+        }
+        self.encode_xml_with_options(opts)
+    }
+
     /// Returns a new, "empty" [`Glyph`] with the given `name`.
     ///
     /// # Panics
@@ -138,11 +259,44 @@ impl Glyph {
         &self.name
     }
 
+    /// Returns the glyph's primary codepoint, its first one, as a number.
+    pub fn unicode_value(&self) -> Option<u32> {
+        self.codepoints.iter().next().map(u32::from)
+    }
+
+    /// Sets the glyph's codepoints to the characters of `s`, in order.
+    ///
+    /// Repeated characters are only added once. As `s` is a string, all of
+    /// its characters are valid Unicode scalar values; in particular, it
+    /// cannot contain surrogates.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::Glyph;
+    /// let mut glyph = Glyph::new("f_i");
+    /// glyph.set_codepoints_from_str("ﬁ");
+    /// assert_eq!(glyph.unicode_value(), Some(0xFB01));
+    /// ```
+    pub fn set_codepoints_from_str(&mut self, s: &str) {
+        self.codepoints.set(s.chars());
+    }
+
     /// Returns true if [`Glyph`] contains one or more [`Component`]s.
     pub fn has_component(&self) -> bool {
         !self.components.is_empty()
     }
 
+    /// Returns true if the Glyph consists of [`Component`]s only, with no contours.
+    pub fn is_composite(&self) -> bool {
+        !self.components.is_empty() && self.contours.is_empty()
+    }
+
+    /// Returns true if the Glyph has both [`Component`]s and contours.
+    pub fn is_mixed(&self) -> bool {
+        !self.components.is_empty() && !self.contours.is_empty()
+    }
+
     /// Returns the number of [`Component`]s in the Glyph.
     pub fn component_count(&self) -> usize {
         self.components.len()
@@ -162,9 +316,223 @@ impl Glyph {
         self.components.iter().filter(move |x| *x.base == *basename)
     }
 
+    /// Removes all [`Component`]s with base glyph name `basename`, returning
+    /// how many were removed.
+    pub fn remove_components_with_base(&mut self, basename: &str) -> usize {
+        let len = self.components.len();
+        self.components.retain(|x| *x.base != *basename);
+        len - self.components.len()
+    }
+
+    /// Removes anchors whose name is already used by another anchor, returning
+    /// the duplicated names in the order they first appear.
+    ///
+    /// Mark positioning looks anchors up by name, so duplicates make it
+    /// ambiguous. `policy` decides which anchor of each name is kept; with
+    /// [`DuplicateAnchors::Error`], nothing is removed and the duplicated
+    /// names are returned as an error instead. Unnamed anchors are left
+    /// alone, as are the glyph's contours and components.
+    pub fn dedup_anchors(
+        &mut self,
+        policy: DuplicateAnchors,
+    ) -> Result<Vec<Name>, DuplicateAnchorsError> {
+        let mut counts: HashMap<&Name, usize> = HashMap::new();
+        let mut names = Vec::new();
+        for name in self.anchors.iter().filter_map(|anchor| anchor.name.as_ref()) {
+            let count = counts.entry(name).or_default();
+            *count += 1;
+            if *count == 2 {
+                names.push(name.clone());
+            }
+        }
+        if names.is_empty() {
+            return Ok(names);
+        }
+
+        let mut remaining: HashMap<Name, usize> =
+            counts.into_iter().map(|(name, count)| (name.clone(), count)).collect();
+        match policy {
+            DuplicateAnchors::KeepFirst => self.anchors.retain(|anchor| {
+                let Some(name) = &anchor.name else { return true };
+                let seen = remaining.get_mut(name).expect("all names are counted");
+                let keep = *seen != 0;
+                *seen = 0;
+                keep
+            }),
+            DuplicateAnchors::KeepLast => self.anchors.retain(|anchor| {
+                let Some(name) = &anchor.name else { return true };
+                let left = remaining.get_mut(name).expect("all names are counted");
+                *left -= 1;
+                *left == 0
+            }),
+            DuplicateAnchors::Error => return Err(DuplicateAnchorsError { names }),
+        }
+        Ok(names)
+    }
+
+    /// Returns `true` if `self` and `other` would draw the same, ignoring
+    /// metadata such as identifiers and libs.
+    ///
+    /// The following are compared:
+    ///
+    /// - the advance width and height;
+    /// - the codepoints, in order;
+    /// - the contours, in order: each point's coordinates, [`PointType`] and
+    ///   smooth flag;
+    /// - the components, in order: each component's base glyph and transform;
+    /// - the anchors, in any order: each anchor's name and coordinates.
+    ///
+    /// Everything else is ignored: the glyph name, note, guidelines, image and
+    /// lib, as well as the identifiers, libs, point names and anchor colors of
+    /// the compared objects.
+    ///
+    /// See [`Glyph::outline_eq`] to compare the outlines alone.
+    pub fn visually_eq(&self, other: &Glyph) -> bool {
+        fn sorted_anchors(glyph: &Glyph) -> Vec<&Anchor> {
+            let mut anchors: Vec<_> = glyph.anchors.iter().collect();
+            anchors.sort_by(|a, b| serialize::compare_anchors(a, b));
+            anchors
+        }
+
+        self.width == other.width
+            && self.height == other.height
+            && self.codepoints == other.codepoints
+            && self.outline_eq(other)
+            && self.anchors.len() == other.anchors.len()
+            && sorted_anchors(self)
+                .into_iter()
+                .zip(sorted_anchors(other))
+                .all(|(a, b)| a.name == b.name && a.x == b.x && a.y == b.y)
+    }
+
+    /// Returns `true` if `self` and `other` have the same outline, ignoring
+    /// spacing and metadata.
+    ///
+    /// The following are compared:
+    ///
+    /// - the contours, in order: each point's coordinates, [`PointType`] and
+    ///   smooth flag;
+    /// - the components, in order: each component's base glyph and transform.
+    ///
+    /// Everything else is ignored: the glyph name, advance width and height,
+    /// codepoints, note, anchors, guidelines, image and lib, as well as the
+    /// identifiers, libs and point names of the compared objects. This tells
+    /// drawing changes apart from spacing changes, which
+    /// [`Glyph::visually_eq`] does not.
+    pub fn outline_eq(&self, other: &Glyph) -> bool {
+        fn points_eq(a: &ContourPoint, b: &ContourPoint) -> bool {
+            a.x == b.x && a.y == b.y && a.typ == b.typ && a.smooth == b.smooth
+        }
+
+        self.contours.len() == other.contours.len()
+            && self.contours.iter().zip(&other.contours).all(|(a, b)| {
+                a.points.len() == b.points.len()
+                    && a.points.iter().zip(&b.points).all(|(a, b)| points_eq(a, b))
+            })
+            && self.components.len() == other.components.len()
+            && self
+                .components
+                .iter()
+                .zip(&other.components)
+                .all(|(a, b)| a.base == b.base && a.transform == b.transform)
+    }
+
+    /// Returns the keys used in the glyph lib and in the libs of the glyph's
+    /// anchors, guidelines, contours, points and components.
+    pub fn lib_keys(&self) -> BTreeSet<&str> {
+        self.libs().flat_map(|lib| lib.keys().map(String::as_str)).collect()
+    }
+
+    /// Returns the glyph lib followed by the libs of the glyph's anchors,
+    /// guidelines, contours, points and components.
+    pub(crate) fn libs(&self) -> impl Iterator<Item = &Plist> {
+        let object_libs = self
+            .anchors
+            .iter()
+            .map(Anchor::lib)
+            .chain(self.guidelines.iter().map(Guideline::lib))
+            .chain(self.contours.iter().map(Contour::lib))
+            .chain(self.contours.iter().flat_map(|c| c.points.iter().map(ContourPoint::lib)))
+            .chain(self.components.iter().map(Component::lib))
+            .flatten();
+        std::iter::once(&self.lib).chain(object_libs)
+    }
+
+    /// Returns the identifiers of the glyph's anchors, guidelines, contours,
+    /// points and components.
+    pub fn identifiers(&self) -> HashSet<&str> {
+        self.iter_identifiers().map(Identifier::as_str).collect()
+    }
+
+    /// Returns the identifiers used by more than one object of the glyph,
+    /// which the specification forbids, in the order they are first repeated.
+    pub(crate) fn duplicate_identifiers(&self) -> Vec<&Identifier> {
+        let mut seen = HashSet::new();
+        let mut duplicates = Vec::new();
+        for identifier in self.iter_identifiers() {
+            if !seen.insert(identifier) && !duplicates.contains(&identifier) {
+                duplicates.push(identifier);
+            }
+        }
+        duplicates
+    }
+
+    fn iter_identifiers(&self) -> impl Iterator<Item = &Identifier> {
+        self.anchors
+            .iter()
+            .map(Anchor::identifier)
+            .chain(self.guidelines.iter().map(Guideline::identifier))
+            .chain(self.contours.iter().map(Contour::identifier))
+            .chain(self.contours.iter().flat_map(|c| c.points.iter().map(ContourPoint::identifier)))
+            .chain(self.components.iter().map(Component::identifier))
+            .flatten()
+    }
+
+    /// Returns a one-line summary of the glyph, for logging.
+    ///
+    /// This is the same as the glyph's [`Display`](std::fmt::Display) output.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::{Anchor, Glyph, Name};
+    /// let mut glyph = Glyph::new("A");
+    /// glyph.width = 540.;
+    /// glyph.codepoints.insert('A');
+    /// glyph.anchors.push(Anchor::new(270., 700., Some(Name::new("top").unwrap()), None, None));
+    /// assert_eq!(
+    ///     glyph.summary(),
+    ///     "A: 0 contours, 0 points, 0 components, advance 540, U+0041, anchors: top"
+    /// );
+    /// ```
+    pub fn summary(&self) -> String {
+        self.to_string()
+    }
+
+    /// Returns the glyph's mark color, stored under the `public.markColor`
+    /// lib key.
+    ///
+    /// Returns `None` if the key is missing or is not a valid color string.
+    pub fn mark_color(&self) -> Option<Color> {
+        self.lib.get(PUBLIC_MARK_COLOR_KEY)?.as_string()?.parse().ok()
+    }
+
+    /// Sets or, if `color` is `None`, removes the glyph's mark color.
+    pub fn set_mark_color(&mut self, color: Option<Color>) {
+        match color {
+            Some(color) => {
+                self.lib.insert(PUBLIC_MARK_COLOR_KEY.into(), color.to_rgba_string().into())
+            }
+            None => self.lib.remove(PUBLIC_MARK_COLOR_KEY),
+        };
+    }
+
     /// Move libs from the lib's `public.objectLibs` into the actual objects.
     /// The key will be removed from the glyph lib.
-    fn load_object_libs(&mut self) -> Result<(), GlifLoadError> {
+    ///
+    /// Entries matching no object are dropped, or are an error if
+    /// `strict_object_libs` is set.
+    fn load_object_libs(&mut self, strict_object_libs: bool) -> Result<(), GlifLoadError> {
         // Use a macro to reduce boilerplate, to avoid having to mess with the typing system.
         macro_rules! transfer_lib {
             ($object:expr, $object_libs:expr) => {
@@ -202,75 +570,128 @@ impl Glyph {
             transfer_lib!(component, object_libs);
         }
 
-        Ok(())
+        match object_libs.keys().next() {
+            Some(id) if strict_object_libs => Err(GlifLoadError::OrphanedObjectLib(id.clone())),
+            _ => Ok(()),
+        }
     }
 
-    /// Dump guideline libs into a Plist.
-    fn dump_object_libs(&self) -> Plist {
+    /// Dump object libs into a Plist.
+    ///
+    /// Errors if an object has a lib but no identifier to file it under, which
+    /// the lib setters and deserialization both rule out.
+    fn dump_object_libs(&self) -> Result<Plist, GlifWriteError> {
         let mut object_libs = Plist::default();
 
-        let mut dump_lib = |id: Option<&Identifier>, lib: &Plist| {
-            let id = id.map(|id| id.as_str().to_string());
-            object_libs.insert(id.unwrap(), plist::Value::Dictionary(lib.clone()));
+        let mut dump_lib = |id: Option<&Identifier>, lib: &Plist| -> Result<(), GlifWriteError> {
+            let id = id.ok_or(GlifWriteError::MissingObjectLibIdentifier)?;
+            object_libs.insert(id.as_str().to_string(), plist::Value::Dictionary(lib.clone()));
+            Ok(())
         };
 
         for anchor in &self.anchors {
             if let Some(lib) = anchor.lib() {
-                dump_lib(anchor.identifier(), lib);
+                dump_lib(anchor.identifier(), lib)?;
             }
         }
 
         for guideline in &self.guidelines {
             if let Some(lib) = guideline.lib() {
-                dump_lib(guideline.identifier(), lib);
+                dump_lib(guideline.identifier(), lib)?;
             }
         }
 
         for contour in &self.contours {
             if let Some(lib) = contour.lib() {
-                dump_lib(contour.identifier(), lib);
+                dump_lib(contour.identifier(), lib)?;
             }
             for point in &contour.points {
                 if let Some(lib) = point.lib() {
-                    dump_lib(point.identifier(), lib);
+                    dump_lib(point.identifier(), lib)?;
                 }
             }
         }
         for component in &self.components {
             if let Some(lib) = component.lib() {
-                dump_lib(component.identifier(), lib);
+                dump_lib(component.identifier(), lib)?;
             }
         }
 
-        object_libs
+        Ok(object_libs)
+    }
+}
+
+/// Writes a one-line summary of the glyph, see [`Glyph::summary`].
+impl std::fmt::Display for Glyph {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        fn plural(n: usize, noun: &str) -> String {
+            format!("{n} {noun}{}", if n == 1 { "" } else { "s" })
+        }
+
+        let points = self.contours.iter().map(|c| c.points.len()).sum();
+        write!(
+            f,
+            "{}: {}, {}, {}, advance {}",
+            self.name,
+            plural(self.contours.len(), "contour"),
+            plural(points, "point"),
+            plural(self.components.len(), "component"),
+            self.width
+        )?;
+        for (i, codepoint) in self.codepoints.iter().enumerate() {
+            let sep = if i == 0 { ", " } else { " " };
+            write!(f, "{sep}U+{:04X}", codepoint as u32)?;
+        }
+        for (i, anchor) in self.anchors.iter().enumerate() {
+            let sep = if i == 0 { ", anchors: " } else { ", " };
+            write!(f, "{sep}{}", anchor.name.as_deref().unwrap_or("<unnamed>"))?;
+        }
+        Ok(())
     }
 }
 
+/// Which anchor to keep of those sharing a name, see [`Glyph::dedup_anchors`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DuplicateAnchors {
+    /// Keep the first anchor with each name.
+    KeepFirst,
+    /// Keep the last anchor with each name.
+    KeepLast,
+    /// Keep all anchors and return an error listing the duplicated names.
+    Error,
+}
+
 /// A reference position in a glyph, such as for attaching accents.
 ///
 /// See the [Anchor section] of the UFO spec for more information.
 ///
 /// [Anchor section]: https://unifiedfontobject.org/versions/ufo3/glyphs/glif/#anchor
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "serde_impls::RawAnchor")]
 pub struct Anchor {
     /// Anchor x coordinate value.
     pub x: f64,
     /// Anchor y coordinate value.
     pub y: f64,
     /// Optional arbitrary name for the anchor.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<Name>,
     /// Optional anchor color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<Color>,
     /// Optional unique identifier for the anchor within the glyph.
     ///
     /// This attribute is only required when a lib is present and should otherwise only be added as needed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     identifier: Option<Identifier>,
     /// Optional anchor lib for arbitrary data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     lib: Option<Plist>,
 }
 
 /// A reference to another glyph, to be included in this glyph's outline.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "serde_impls::RawComponent")]
 pub struct Component {
     /// The name of the base glyph used in the component.
     pub base: Name,
@@ -280,13 +701,16 @@ pub struct Component {
     ///
     /// This attribute is only required when a lib is present and should otherwise only
     /// be added as needed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     identifier: Option<Identifier>,
     ///  Optional lib for arbitrary component data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     lib: Option<Plist>,
 }
 
 /// A single open or closed bezier path segment.
-#[derive(Debug, Clone, Default, PartialEq)]
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "serde_impls::RawContour")]
 pub struct Contour {
     /// A collection of contour points.
     pub points: Vec<ContourPoint>,
@@ -294,8 +718,10 @@ pub struct Contour {
     ///
     /// This attribute is only required when a lib is present and should otherwise only
     /// be added as needed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     identifier: Option<Identifier>,
     /// Optional lib for arbitrary contour data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     lib: Option<Plist>,
 }
 
@@ -305,12 +731,22 @@ impl Contour {
         self.points.first().is_none_or(|v| v.typ != PointType::Move)
     }
 
+    /// Whether the contour is open, i.e. it starts with a [`PointType::Move`] point.
+    pub fn is_open(&self) -> bool {
+        !self.is_closed()
+    }
+
     /// Converts the `Contour` to a [`kurbo::BezPath`].
+    ///
+    /// Closed contours end with a [`kurbo::PathEl::ClosePath`]; a final straight
+    /// segment back to the start point is implied by it and not emitted. Open
+    /// contours (see [`Contour::is_open`]) are left open.
     #[cfg(feature = "kurbo")]
     pub fn to_kurbo(&self) -> Result<kurbo::BezPath, ConvertContourError> {
         let mut path = kurbo::BezPath::new();
         let mut offs = std::collections::VecDeque::new();
-        let mut points = if self.is_closed() {
+        let closed = self.is_closed();
+        let points = if closed {
             // Add end-of-contour offcurves to queue
             let rotate = self
                 .points
@@ -323,13 +759,17 @@ impl Contour {
             #[allow(clippy::iter_skip_zero)]
             self.points.iter().cycle().skip(0).take(self.points.len())
         };
+        let mut points = points.peekable();
         if let Some(start) = points.next() {
             path.move_to(start.to_kurbo());
         }
-        for pt in points {
+        while let Some(pt) = points.next() {
             let kurbo_point = pt.to_kurbo();
+            let is_closing_point = closed && points.peek().is_none();
             match pt.typ {
                 PointType::Move => path.move_to(kurbo_point),
+                // The closing line back to the start is implied by `close_path`.
+                PointType::Line if is_closing_point => (),
                 PointType::Line => path.line_to(kurbo_point),
                 PointType::OffCurve => offs.push_back(kurbo_point),
                 PointType::Curve => {
@@ -354,33 +794,43 @@ impl Contour {
                 }
             }
         }
+        if closed && !self.points.is_empty() {
+            path.close_path();
+        }
         Ok(path)
     }
 }
 
 /// A single point in a [`Contour`].
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "serde_impls::RawContourPoint")]
 pub struct ContourPoint {
     /// Contour point x coordinate value.
     pub x: f64,
     /// Contour point y coordinate value.
     pub y: f64,
     /// Contour point type.
+    #[serde(rename = "type")]
     pub typ: PointType,
     /// Whether a smooth curvature should be maintained at this point. Must not be set for off-curve points.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
     pub smooth: bool,
     /// Optional contour point name.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub name: Option<Name>,
     /// Optional unique identifier for the point within the glyph.
     ///
     /// This attribute is only required when a lib is present and should otherwise only be added as needed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     identifier: Option<Identifier>,
     /// Optional lib for arbitrary contour point data.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     lib: Option<Plist>,
 }
 
 /// Possible types of points that can exist in a [`Contour`].
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
 pub enum PointType {
     /// A point of this type must be the first in a contour. The reverse is not true:
     /// a contour does not necessarily start with a move point. When a contour
@@ -439,7 +889,12 @@ impl std::fmt::Display for PointType {
 }
 
 /// A 2D affine transformation.
-#[derive(Debug, Clone, Copy, PartialEq)]
+///
+/// With serde, a transform is a struct with the `xScale`, `xyScale`,
+/// `yxScale`, `yScale`, `xOffset` and `yOffset` fields, named after the
+/// attributes of the glif format. All six are required.
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
 pub struct AffineTransform {
     /// x-scale value.
     pub x_scale: f64,
@@ -481,8 +936,19 @@ impl Anchor {
     /// lib if present. Sets a new UUID v4 identifier if none is set already.
     #[cfg(feature = "object-libs")]
     pub fn replace_lib(&mut self, lib: Plist) -> Option<Plist> {
+        self.replace_lib_with(lib, &mut UuidV4Identifiers)
+    }
+
+    /// Replaces the actual lib by the lib given in parameter, returning the old
+    /// lib if present. Sets a new identifier taken from `ids` if none is set
+    /// already.
+    pub fn replace_lib_with(
+        &mut self,
+        lib: Plist,
+        ids: &mut impl IdentifierSource,
+    ) -> Option<Plist> {
         if self.identifier.is_none() {
-            self.identifier.replace(Identifier::from_uuidv4());
+            self.identifier.replace(ids.next_identifier());
         }
         self.lib.replace(lib)
     }
@@ -510,6 +976,46 @@ impl Contour {
         Self { points, identifier, lib: None }
     }
 
+    /// Inserts `point` at `index`, shifting the following points, if the
+    /// contour stays valid.
+    ///
+    /// Returns an error, leaving the contour unchanged, if the resulting
+    /// points break the rules of the [specification], for example by placing
+    /// a move point anywhere but first or a line point after an off-curve
+    /// point. See [`ContourBuilder::build`] for the full list.
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is greater than the number of points.
+    ///
+    /// [specification]: https://unifiedfontobject.org/versions/ufo3/glyphs/glif/#point-types
+    pub fn insert_point(&mut self, index: usize, point: ContourPoint) -> Result<(), ErrorKind> {
+        self.points.insert(index, point);
+        builder::validate_points(&self.points).inspect_err(|_| {
+            self.points.remove(index);
+        })
+    }
+
+    /// Removes and returns the point at `index`, if the contour stays valid.
+    ///
+    /// Returns an error, leaving the contour unchanged, if the remaining
+    /// points break the rules of the specification, as with
+    /// [`Contour::insert_point`].
+    ///
+    /// # Panics
+    ///
+    /// Panics if `index` is out of bounds.
+    pub fn remove_point(&mut self, index: usize) -> Result<ContourPoint, ErrorKind> {
+        let point = self.points.remove(index);
+        match builder::validate_points(&self.points) {
+            Ok(()) => Ok(point),
+            Err(e) => {
+                self.points.insert(index, point);
+                Err(e)
+            }
+        }
+    }
+
     /// Returns a reference to the contour's lib.
     pub fn lib(&self) -> Option<&Plist> {
         self.lib.as_ref()
@@ -524,8 +1030,19 @@ impl Contour {
     /// lib if present. Sets a new UUID v4 identifier if none is set already.
     #[cfg(feature = "object-libs")]
     pub fn replace_lib(&mut self, lib: Plist) -> Option<Plist> {
+        self.replace_lib_with(lib, &mut UuidV4Identifiers)
+    }
+
+    /// Replaces the actual lib by the lib given in parameter, returning the old
+    /// lib if present. Sets a new identifier taken from `ids` if none is set
+    /// already.
+    pub fn replace_lib_with(
+        &mut self,
+        lib: Plist,
+        ids: &mut impl IdentifierSource,
+    ) -> Option<Plist> {
         if self.identifier.is_none() {
-            self.identifier.replace(Identifier::from_uuidv4());
+            self.identifier.replace(ids.next_identifier());
         }
         self.lib.replace(lib)
     }
@@ -575,8 +1092,19 @@ impl ContourPoint {
     /// lib if present. Sets a new UUID v4 identifier if none is set already.
     #[cfg(feature = "object-libs")]
     pub fn replace_lib(&mut self, lib: Plist) -> Option<Plist> {
+        self.replace_lib_with(lib, &mut UuidV4Identifiers)
+    }
+
+    /// Replaces the actual lib by the lib given in parameter, returning the old
+    /// lib if present. Sets a new identifier taken from `ids` if none is set
+    /// already.
+    pub fn replace_lib_with(
+        &mut self,
+        lib: Plist,
+        ids: &mut impl IdentifierSource,
+    ) -> Option<Plist> {
         if self.identifier.is_none() {
-            self.identifier.replace(Identifier::from_uuidv4());
+            self.identifier.replace(ids.next_identifier());
         }
         self.lib.replace(lib)
     }
@@ -605,10 +1133,7 @@ impl ContourPoint {
 
     /// Applies a transformation matrix to the point's coordinates
     pub fn transform(&mut self, transform: AffineTransform) {
-        let new_x = transform.x_scale * self.x + transform.yx_scale * self.y + transform.x_offset;
-        let new_y = transform.xy_scale * self.x + transform.y_scale * self.y + transform.y_offset;
-        self.x = new_x;
-        self.y = new_y;
+        (self.x, self.y) = transform.transform_point((self.x, self.y));
     }
 }
 
@@ -634,8 +1159,19 @@ impl Component {
     /// lib if present. Sets a new UUID v4 identifier if none is set already.
     #[cfg(feature = "object-libs")]
     pub fn replace_lib(&mut self, lib: Plist) -> Option<Plist> {
+        self.replace_lib_with(lib, &mut UuidV4Identifiers)
+    }
+
+    /// Replaces the actual lib by the lib given in parameter, returning the old
+    /// lib if present. Sets a new identifier taken from `ids` if none is set
+    /// already.
+    pub fn replace_lib_with(
+        &mut self,
+        lib: Plist,
+        ids: &mut impl IdentifierSource,
+    ) -> Option<Plist> {
         if self.identifier.is_none() {
-            self.identifier.replace(Identifier::from_uuidv4());
+            self.identifier.replace(ids.next_identifier());
         }
         self.lib.replace(lib)
     }
@@ -669,6 +1205,30 @@ impl AffineTransform {
             y_offset: 0.,
         }
     }
+
+    /// Returns the point `(x, y)` with the transformation applied.
+    pub fn transform_point(&self, (x, y): (f64, f64)) -> (f64, f64) {
+        (
+            self.x_scale * x + self.yx_scale * y + self.x_offset,
+            self.xy_scale * x + self.y_scale * y + self.y_offset,
+        )
+    }
+
+    /// Returns the transformation that applies `inner` first, then `self`.
+    pub(crate) fn concat(self, inner: AffineTransform) -> Self {
+        AffineTransform {
+            x_scale: self.x_scale * inner.x_scale + self.yx_scale * inner.xy_scale,
+            xy_scale: self.xy_scale * inner.x_scale + self.y_scale * inner.xy_scale,
+            yx_scale: self.x_scale * inner.yx_scale + self.yx_scale * inner.y_scale,
+            y_scale: self.xy_scale * inner.yx_scale + self.y_scale * inner.y_scale,
+            x_offset: self.x_scale * inner.x_offset
+                + self.yx_scale * inner.y_offset
+                + self.x_offset,
+            y_offset: self.xy_scale * inner.x_offset
+                + self.y_scale * inner.y_offset
+                + self.y_offset,
+        }
+    }
 }
 
 impl std::default::Default for AffineTransform {
@@ -678,11 +1238,14 @@ impl std::default::Default for AffineTransform {
 }
 
 /// An image included in a glyph.
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(try_from = "serde_impls::RawImage")]
 pub struct Image {
     /// The name of the image file. Must be a base file name, no subdirectories involved.
+    #[serde(rename = "fileName")]
     file_name: PathBuf,
     /// Optional image color.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub color: Option<Color>,
     /// Affine transformation.
     pub transform: AffineTransform,
@@ -712,6 +1275,38 @@ impl Image {
     pub fn file_name(&self) -> &Path {
         self.file_name.as_path()
     }
+
+    /// Returns where the corners of the image land in the glyph, given its
+    /// size in pixels.
+    ///
+    /// Untransformed, the image covers one font unit per pixel with its
+    /// bottom left corner at the origin. The corners are returned in the
+    /// order bottom left, bottom right, top right and top left, before
+    /// applying the image's transform.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::{AffineTransform, Image};
+    /// let transform = AffineTransform { x_scale: 2., y_scale: 2., x_offset: 10., ..Default::default() };
+    /// let image = Image::new("sketch.png".into(), None, transform).unwrap();
+    /// assert_eq!(image.corners(100., 50.), [(10., 0.), (210., 0.), (210., 100.), (10., 100.)]);
+    /// assert_eq!(image.bounding_box(100., 50.), (10., 0., 210., 100.));
+    /// ```
+    pub fn corners(&self, width: f64, height: f64) -> [(f64, f64); 4] {
+        [(0., 0.), (width, 0.), (width, height), (0., height)]
+            .map(|corner| self.transform.transform_point(corner))
+    }
+
+    /// Returns the bounding box of the image in the glyph as
+    /// `(x_min, y_min, x_max, y_max)`, given its size in pixels.
+    ///
+    /// This is the bounding box of the [corners](Image::corners), so it is
+    /// larger than the image if it is rotated or skewed.
+    pub fn bounding_box(&self, width: f64, height: f64) -> (f64, f64, f64, f64) {
+        let corners = self.corners(width, height);
+        corners.into_iter().fold(None, metrics::extend_bounds).expect("an image has corners")
+    }
 }
 
 #[cfg(feature = "kurbo")]
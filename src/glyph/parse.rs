@@ -14,41 +14,64 @@ use quick_xml::{
 
 #[cfg(test)]
 pub(crate) fn parse_glyph(xml: &[u8]) -> Result<Glyph, GlifLoadError> {
-    GlifParser::from_xml(xml, None)
+    GlifParser::from_xml(xml, None, false)
 }
 
-// major, minor
-type Version = (u32, u32);
-
-const VERSION_1: Version = (1, 0);
-const VERSION_2: Version = (2, 0);
-
 // https://en.wikipedia.org/wiki/Byte_order_mark
 const UTF8_BOM: &[u8] = &[0xEF, 0xBB, 0xBF];
 
 pub(crate) struct GlifParser<'names> {
     glyph: Glyph,
-    version: Version,
+    version: GlifVersion,
     seen_identifiers: HashSet<Identifier>,
     /// Optional set of glyph names to be reused between glyphs.
     names: Option<&'names NameList>,
+    /// Whether object libs matching no object are an error.
+    strict_object_libs: bool,
 }
 
 impl<'names> GlifParser<'names> {
     pub(crate) fn from_xml(
         xml: &[u8],
         names: Option<&'names NameList>,
+        strict_object_libs: bool,
     ) -> Result<Glyph, GlifLoadError> {
+        Self::from_xml_with_position(xml, names, strict_object_libs).map_err(|(err, _)| err)
+    }
+
+    /// Like [`GlifParser::from_xml`], but errors come with the byte offset in
+    /// `xml` at which parsing stopped.
+    pub(crate) fn from_xml_with_position(
+        xml: &[u8],
+        names: Option<&'names NameList>,
+        strict_object_libs: bool,
+    ) -> Result<Glyph, (GlifLoadError, usize)> {
         // optional but allowed for utf-8.
-        let xml = xml.strip_prefix(UTF8_BOM).unwrap_or(xml);
+        let bom_len = if xml.starts_with(UTF8_BOM) { UTF8_BOM.len() } else { 0 };
+        let xml = &xml[bom_len..];
         let mut reader = Reader::from_reader(xml);
         let mut buf = Vec::new();
         reader.config_mut().trim_text(true);
 
-        let (name, version) = start(&mut reader, &mut buf, names)?;
-        let glyph = Glyph::new_impl(name);
-        let parser = GlifParser { glyph, seen_identifiers: Default::default(), names, version };
-        parser.parse_body(&mut reader, xml, &mut buf)
+        let result = start(&mut reader, &mut buf, names).and_then(|(name, version)| {
+            let glyph = Glyph::new_impl(name);
+            let parser = GlifParser {
+                glyph,
+                seen_identifiers: Default::default(),
+                names,
+                version,
+                strict_object_libs,
+            };
+            parser.parse_body(&mut reader, xml, &mut buf)
+        });
+        result.map_err(|err| {
+            // XML syntax errors know best where they start.
+            let position = match err {
+                GlifLoadError::Xml(_) => reader.error_position(),
+                _ => reader.buffer_position(),
+            };
+            (err, bom_len + position as usize)
+        })
     }
 
     fn parse_body(
@@ -79,7 +102,7 @@ impl<'names> GlifParser<'names> {
                         seen_lib = true;
                         self.parse_lib(reader, raw_xml, buf)?;
                     }
-                    b"note" if self.version == VERSION_1 => {
+                    b"note" if self.version < GlifVersion::V2 => {
                         return Err(ErrorKind::UnexpectedV1Element("note").into());
                     }
                     b"note" if self.glyph.note.is_some() => {
@@ -104,15 +127,15 @@ impl<'names> GlifParser<'names> {
                         self.parse_advance(start)?;
                     }
                     b"unicode" => self.parse_unicode(start)?,
-                    b"anchor" if self.version == VERSION_1 => {
+                    b"anchor" if self.version < GlifVersion::V2 => {
                         return Err(ErrorKind::UnexpectedV1Element("anchor").into());
                     }
                     b"anchor" => self.parse_anchor(start)?,
-                    b"guideline" if self.version == VERSION_1 => {
+                    b"guideline" if self.version < GlifVersion::V2 => {
                         return Err(ErrorKind::UnexpectedV1Element("guideline").into());
                     }
                     b"guideline" => self.parse_guideline(start)?,
-                    b"image" if self.version == VERSION_1 => {
+                    b"image" if self.version < GlifVersion::V2 => {
                         return Err(ErrorKind::UnexpectedV1Element("image").into());
                     }
                     b"image" if self.glyph.image.is_some() => {
@@ -127,7 +150,19 @@ impl<'names> GlifParser<'names> {
             buf.clear();
         }
 
-        self.glyph.load_object_libs()?;
+        // Only comments, processing instructions and whitespace may follow, as
+        // in any XML document; in particular, a second glyph element must not
+        // be silently ignored.
+        loop {
+            buf.clear();
+            match reader.read_event_into(buf)? {
+                Event::Eof => break,
+                Event::Comment(_) | Event::PI(_) => (),
+                _other => return Err(ErrorKind::TrailingContent.into()),
+            }
+        }
+
+        self.glyph.load_object_libs(self.strict_object_libs)?;
         Ok(self.glyph)
     }
 
@@ -170,7 +205,7 @@ impl<'names> GlifParser<'names> {
         let (mut contours, components) = outline_builder.finish()?;
 
         // Upgrade implicit anchors to explicit ones.
-        if self.version == VERSION_1 {
+        if self.version < GlifVersion::V2 {
             for c in &mut contours {
                 if c.points.len() == 1
                     && c.points[0].typ == PointType::Move
@@ -194,7 +229,7 @@ impl<'names> GlifParser<'names> {
     }
 
     fn parse_identifier(&mut self, value: &str) -> Result<Identifier, GlifLoadError> {
-        if self.version == VERSION_1 {
+        if self.version < GlifVersion::V2 {
             return Err(ErrorKind::UnexpectedV1Attribute("identifier").into());
         }
 
@@ -215,7 +250,7 @@ impl<'names> GlifParser<'names> {
     ) -> Result<(), GlifLoadError> {
         let mut identifier = None;
         for attr in data.attributes() {
-            if self.version == VERSION_1 {
+            if self.version < GlifVersion::V2 {
                 return Err(ErrorKind::UnexpectedAttribute.into());
             }
             let attr = attr?;
@@ -409,11 +444,7 @@ impl<'names> GlifParser<'names> {
             match attr.key.as_ref() {
                 b"hex" => {
                     let value = attr.unescape_value()?;
-                    let chr = u32::from_str_radix(&value, 16)
-                        .map_err(|_| value.to_string())
-                        .and_then(|n| char::try_from(n).map_err(|_| value.to_string()))
-                        .map_err(|_| ErrorKind::BadHexValue)?;
-                    self.glyph.codepoints.insert(chr);
+                    self.glyph.codepoints.insert(codepoints::from_hex(&value)?);
                 }
                 _other => return Err(ErrorKind::UnexpectedAttribute.into()),
             }
@@ -543,14 +574,24 @@ fn start(
     reader: &mut Reader<&[u8]>,
     buf: &mut Vec<u8>,
     names: Option<&NameList>,
-) -> Result<(Name, Version), GlifLoadError> {
+) -> Result<(Name, GlifVersion), GlifLoadError> {
     loop {
         match reader.read_event_into(buf)? {
             Event::Comment(_) => (),
-            Event::Decl(_decl) => (),
+            Event::Decl(decl) => {
+                // Only UTF-8 is supported; other encodings would be misread.
+                if let Some(encoding) = decl.encoding() {
+                    let encoding = encoding?;
+                    if !encoding.eq_ignore_ascii_case(b"utf-8")
+                        && !encoding.eq_ignore_ascii_case(b"utf8")
+                    {
+                        return Err(ErrorKind::UnsupportedEncoding.into());
+                    }
+                }
+            }
             Event::Start(ref start) if start.name().as_ref() == b"glyph" => {
                 let mut name: Option<Name> = None;
-                let mut format_major = 0;
+                let mut format_major: u32 = 0;
                 let mut format_minor = 0;
                 for attr in start.attributes() {
                     let attr = attr?;
@@ -571,12 +612,12 @@ fn start(
                 }
 
                 let name = name.ok_or(ErrorKind::WrongFirstElement)?;
-                let version = (format_major, format_minor);
-                if version != VERSION_1 && version != VERSION_2 {
-                    return Err(ErrorKind::UnsupportedGlifVersion.into());
-                } else {
-                    return Ok((name, version));
-                }
+                // glif 2.x minor versions are not supported yet.
+                let version = u8::try_from(format_major).ok().and_then(GlifVersion::from_u8);
+                return match version {
+                    Some(version) if format_minor == 0 => Ok((name, version)),
+                    _ => Err(ErrorKind::UnsupportedGlifVersion.into()),
+                };
             }
             _other => return Err(ErrorKind::WrongFirstElement.into()),
         }
@@ -0,0 +1,73 @@
+//! Points in the shape used by fontTools point pens.
+
+use super::builder::validate_points;
+use super::{Contour, ContourPoint, PointType};
+use crate::error::ErrorKind;
+use crate::{Identifier, Name};
+
+/// A point as passed to the `addPoint` method of a fontTools point pen:
+/// `(x, y, segment_type, smooth, name, identifier)`.
+///
+/// The segment type is `"move"`, `"line"`, `"curve"` or `"qcurve"` for
+/// on-curve points, and `None` for off-curve points, as in fontTools. A
+/// contour whose first point is a `"move"` is open; all others are closed.
+/// `smooth` and the optional `name` and `identifier` are those of the
+/// [`ContourPoint`]. Point libs have no place in the tuple.
+pub type PointTuple<'a> = (f64, f64, Option<&'a str>, bool, Option<&'a str>, Option<&'a str>);
+
+impl ContourPoint {
+    /// Returns the point as a fontTools point pen tuple, see [`PointTuple`].
+    ///
+    /// The point's lib is not included.
+    pub fn to_tuple(&self) -> PointTuple<'_> {
+        let segment_type = match self.typ {
+            PointType::Move => Some("move"),
+            PointType::Line => Some("line"),
+            PointType::OffCurve => None,
+            PointType::Curve => Some("curve"),
+            PointType::QCurve => Some("qcurve"),
+        };
+        (
+            self.x,
+            self.y,
+            segment_type,
+            self.smooth,
+            self.name.as_ref().map(Name::as_str),
+            self.identifier.as_ref().map(Identifier::as_str),
+        )
+    }
+
+    /// Returns a point from a fontTools point pen tuple, see [`PointTuple`].
+    ///
+    /// Returns an error if the segment type is unknown, or if the name or
+    /// identifier is invalid.
+    pub fn from_tuple(tuple: PointTuple) -> Result<Self, ErrorKind> {
+        let (x, y, segment_type, smooth, name, identifier) = tuple;
+        let typ = segment_type.map_or(Ok(PointType::OffCurve), str::parse)?;
+        let name = name.map(Name::new).transpose().map_err(|_| ErrorKind::InvalidName)?;
+        let identifier = identifier.map(Identifier::new).transpose()?;
+        Ok(ContourPoint::new(x, y, typ, smooth, name, identifier))
+    }
+}
+
+impl Contour {
+    /// Returns the points of the contour as fontTools point pen tuples, see
+    /// [`PointTuple`].
+    pub fn to_tuples(&self) -> Vec<PointTuple<'_>> {
+        self.points.iter().map(ContourPoint::to_tuple).collect()
+    }
+
+    /// Returns a contour made of fontTools point pen tuples, see
+    /// [`PointTuple`].
+    ///
+    /// Returns an error if a tuple is not a valid point, or if the points do
+    /// not form a valid contour, such as a `"move"` point that is not first.
+    pub fn from_tuples<'a>(
+        tuples: impl IntoIterator<Item = PointTuple<'a>>,
+    ) -> Result<Self, ErrorKind> {
+        let points =
+            tuples.into_iter().map(ContourPoint::from_tuple).collect::<Result<Vec<_>, _>>()?;
+        validate_points(&points)?;
+        Ok(Contour::new(points, None))
+    }
+}
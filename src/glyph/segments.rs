@@ -0,0 +1,341 @@
+//! Decomposing contours into bezier segments.
+
+use super::{AffineTransform, Contour, ContourPoint, PointType};
+
+/// A point, as an `(x, y)` pair.
+pub(crate) type Point = (f64, f64);
+
+/// A single segment of a contour, from its start point to its end point.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum Segment {
+    Line(Point, Point),
+    Quad(Point, Point, Point),
+    Cubic(Point, Point, Point, Point),
+}
+
+impl Segment {
+    pub(crate) fn start(&self) -> Point {
+        match *self {
+            Segment::Line(p0, _) | Segment::Quad(p0, ..) | Segment::Cubic(p0, ..) => p0,
+        }
+    }
+
+    pub(crate) fn end(&self) -> Point {
+        match *self {
+            Segment::Line(_, p1) | Segment::Quad(.., p1) | Segment::Cubic(.., p1) => p1,
+        }
+    }
+
+    /// Returns the point at parameter `t`, from `0.0` at the start to `1.0`
+    /// at the end.
+    pub(crate) fn eval(&self, t: f64) -> Point {
+        let mt = 1.0 - t;
+        match *self {
+            Segment::Line(p0, p1) => blend(&[mt, t], &[p0, p1]),
+            Segment::Quad(p0, p1, p2) => blend(&[mt * mt, 2.0 * mt * t, t * t], &[p0, p1, p2]),
+            Segment::Cubic(p0, p1, p2, p3) => blend(
+                &[mt * mt * mt, 3.0 * mt * mt * t, 3.0 * mt * t * t, t * t * t],
+                &[p0, p1, p2, p3],
+            ),
+        }
+    }
+
+    /// Returns the derivative of the segment at parameter `t`.
+    pub(crate) fn derivative(&self, t: f64) -> Point {
+        let mt = 1.0 - t;
+        let diff = |a: Point, b: Point| (b.0 - a.0, b.1 - a.1);
+        match *self {
+            Segment::Line(p0, p1) => diff(p0, p1),
+            Segment::Quad(p0, p1, p2) => blend(&[2.0 * mt, 2.0 * t], &[diff(p0, p1), diff(p1, p2)]),
+            Segment::Cubic(p0, p1, p2, p3) => blend(
+                &[3.0 * mt * mt, 6.0 * mt * t, 3.0 * t * t],
+                &[diff(p0, p1), diff(p1, p2), diff(p2, p3)],
+            ),
+        }
+    }
+
+    /// Returns the segment's contribution to the signed area of a closed
+    /// path, by Green's theorem: half the integral of `x dy - y dx`.
+    pub(crate) fn area(&self) -> f64 {
+        // The integrand is a polynomial of degree at most five, which
+        // three-point Gauss-Legendre quadrature integrates exactly.
+        const NODES: [(f64, f64); 3] = [
+            (0.5, 4.0 / 9.0),
+            (0.5 - 0.387_298_334_620_741_7, 5.0 / 18.0),
+            (0.5 + 0.387_298_334_620_741_7, 5.0 / 18.0),
+        ];
+        NODES
+            .iter()
+            .map(|&(t, weight)| {
+                let (x, y) = self.eval(t);
+                let (dx, dy) = self.derivative(t);
+                weight * (x * dy - y * dx)
+            })
+            .sum::<f64>()
+            * 0.5
+    }
+
+    /// Returns the parameters strictly between `0.0` and `1.0` at which the
+    /// segment is horizontal or vertical, i.e. its local extrema.
+    pub(crate) fn extrema_params(&self) -> Vec<f64> {
+        let mut params = Vec::new();
+        match *self {
+            Segment::Line(..) => (),
+            Segment::Quad(p0, p1, p2) => {
+                for (a, b, c) in [(p0.0, p1.0, p2.0), (p0.1, p1.1, p2.1)] {
+                    let denominator = a - 2.0 * b + c;
+                    if denominator != 0.0 {
+                        params.push((a - b) / denominator);
+                    }
+                }
+            }
+            Segment::Cubic(p0, p1, p2, p3) => {
+                for (a, b, c, d) in [(p0.0, p1.0, p2.0, p3.0), (p0.1, p1.1, p2.1, p3.1)] {
+                    // The derivative, divided by three.
+                    let qa = -a + 3.0 * b - 3.0 * c + d;
+                    let qb = 2.0 * (a - 2.0 * b + c);
+                    let qc = b - a;
+                    if qa.abs() < 1e-12 {
+                        if qb != 0.0 {
+                            params.push(-qc / qb);
+                        }
+                        continue;
+                    }
+                    let discriminant = qb * qb - 4.0 * qa * qc;
+                    if discriminant >= 0.0 {
+                        let root = discriminant.sqrt();
+                        params.push((-qb + root) / (2.0 * qa));
+                        params.push((-qb - root) / (2.0 * qa));
+                    }
+                }
+            }
+        }
+        params.retain(|t| *t > 0.0 && *t < 1.0);
+        params.sort_by(f64::total_cmp);
+        params.dedup();
+        params
+    }
+
+    /// Splits the segment at parameter `t` with de Casteljau's algorithm.
+    pub(crate) fn split(&self, t: f64) -> (Segment, Segment) {
+        match *self {
+            Segment::Line(p0, p1) => {
+                let mid = lerp(p0, p1, t);
+                (Segment::Line(p0, mid), Segment::Line(mid, p1))
+            }
+            Segment::Quad(p0, p1, p2) => {
+                let (a, b) = (lerp(p0, p1, t), lerp(p1, p2, t));
+                let mid = lerp(a, b, t);
+                (Segment::Quad(p0, a, mid), Segment::Quad(mid, b, p2))
+            }
+            Segment::Cubic(p0, p1, p2, p3) => {
+                let (a, b, c) = (lerp(p0, p1, t), lerp(p1, p2, t), lerp(p2, p3, t));
+                let (d, e) = (lerp(a, b, t), lerp(b, c, t));
+                let mid = lerp(d, e, t);
+                (Segment::Cubic(p0, a, d, mid), Segment::Cubic(mid, e, c, p3))
+            }
+        }
+    }
+
+    /// Pushes the end points of the lines approximating the segment within
+    /// `tolerance`, excluding its start point.
+    fn flatten_into(&self, tolerance: f64, depth: usize, points: &mut Vec<Point>) {
+        let controls: &[Point] = match self {
+            Segment::Line(..) => &[],
+            Segment::Quad(_, p1, _) => &[*p1],
+            Segment::Cubic(_, p1, p2, _) => &[*p1, *p2],
+        };
+        // The curve lies within the hull of its points, so it is flat enough
+        // once its control points are close enough to its chord. Measuring
+        // the distance to the chord's line instead would miss curves that
+        // overshoot their end points.
+        let (start, end) = (self.start(), self.end());
+        if depth == MAX_FLATTEN_DEPTH
+            || controls.iter().all(|&p| distance_to_segment(p, start, end) <= tolerance)
+        {
+            points.push(end);
+            return;
+        }
+        let (first, second) = self.split(0.5);
+        first.flatten_into(tolerance, depth + 1, points);
+        second.flatten_into(tolerance, depth + 1, points);
+    }
+
+    /// Returns the segment with `transform` applied to all its points.
+    pub(crate) fn transform(self, transform: AffineTransform) -> Self {
+        let apply = |point| transform.transform_point(point);
+        match self {
+            Segment::Line(p0, p1) => Segment::Line(apply(p0), apply(p1)),
+            Segment::Quad(p0, p1, p2) => Segment::Quad(apply(p0), apply(p1), apply(p2)),
+            Segment::Cubic(p0, p1, p2, p3) => {
+                Segment::Cubic(apply(p0), apply(p1), apply(p2), apply(p3))
+            }
+        }
+    }
+}
+
+impl Contour {
+    /// Approximates the contour with straight lines, returning the points of
+    /// the resulting polyline as `(x, y)` pairs.
+    ///
+    /// Curves are split with de Casteljau's algorithm until they deviate from
+    /// their lines by at most `tolerance`, in font units. On-curve points are
+    /// always included. The polyline of a closed contour does not repeat its
+    /// first point at the end, while that of an open contour runs from its
+    /// first to its last point.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::{Contour, ContourPoint, PointType};
+    /// let point = |x, y, typ| ContourPoint::new(x, y, typ, false, None, None);
+    /// let arch = Contour::new(
+    ///     vec![
+    ///         point(0., 0., PointType::Move),
+    ///         point(0., 100., PointType::OffCurve),
+    ///         point(100., 100., PointType::OffCurve),
+    ///         point(100., 0., PointType::Curve),
+    ///     ],
+    ///     None,
+    /// );
+    /// let polyline = arch.flatten(1.0);
+    /// assert_eq!(polyline.first(), Some(&(0., 0.)));
+    /// assert_eq!(polyline.last(), Some(&(100., 0.)));
+    /// assert!(polyline.contains(&(50., 75.)));
+    /// ```
+    pub fn flatten(&self, tolerance: f64) -> Vec<(f64, f64)> {
+        let segments = self.segments();
+        let Some(first) = segments.first() else {
+            // A lone move point.
+            return self.points.iter().map(point).collect();
+        };
+        let mut points = vec![first.start()];
+        for segment in &segments {
+            segment.flatten_into(tolerance, 0, &mut points);
+        }
+        if self.is_closed() && points.len() > 1 {
+            points.pop();
+        }
+        points
+    }
+
+    /// Decomposes the contour into its segments, in order.
+    ///
+    /// Closed contours include the segment back to their first on-curve point.
+    /// Runs of quadratic off-curve points are split at their implied on-curve
+    /// points, and cubic curves with more than two off-curve points are split
+    /// the way fontTools does. A closed contour made only of off-curve points
+    /// is a quadratic curve starting between its last and first point.
+    pub(crate) fn segments(&self) -> Vec<Segment> {
+        let points = &self.points;
+        let mut segments = Vec::new();
+        let Some(first_on) = points.iter().position(|p| p.typ != PointType::OffCurve) else {
+            if let Some(last) = points.last() {
+                let start = midpoint(point(last), point(&points[0]));
+                let offs: Vec<Point> = points.iter().map(point).collect();
+                push_qcurve(&mut segments, start, &offs, start);
+            }
+            return segments;
+        };
+
+        let len = points.len();
+        // Open contours start with their move point, so `first_on` is 0.
+        let steps = if self.is_closed() { len } else { len - 1 };
+        let mut current = point(&points[first_on]);
+        let mut offs = Vec::new();
+        for step in 1..=steps {
+            let next = &points[(first_on + step) % len];
+            let end = point(next);
+            match next.typ {
+                PointType::OffCurve => {
+                    offs.push(end);
+                    continue;
+                }
+                PointType::Move | PointType::Line => segments.push(Segment::Line(current, end)),
+                PointType::Curve => match offs[..] {
+                    [] => segments.push(Segment::Line(current, end)),
+                    [p1] => segments.push(Segment::Quad(current, p1, end)),
+                    [p1, p2] => segments.push(Segment::Cubic(current, p1, p2, end)),
+                    _ => push_super_bezier(&mut segments, current, &offs, end),
+                },
+                PointType::QCurve => push_qcurve(&mut segments, current, &offs, end),
+            }
+            current = end;
+            offs.clear();
+        }
+        segments
+    }
+}
+
+/// The maximum number of times a segment is split in half when flattening,
+/// which bounds the work done for tiny or invalid tolerances.
+const MAX_FLATTEN_DEPTH: usize = 16;
+
+/// Returns the distance of `p` to the line segment from `a` to `b`.
+pub(super) fn distance_to_segment(p: Point, a: Point, b: Point) -> f64 {
+    let (dx, dy) = (b.0 - a.0, b.1 - a.1);
+    let len_sq = dx * dx + dy * dy;
+    let t = if len_sq == 0. {
+        0.
+    } else {
+        (((p.0 - a.0) * dx + (p.1 - a.1) * dy) / len_sq).clamp(0., 1.)
+    };
+    (p.0 - (a.0 + t * dx)).hypot(p.1 - (a.1 + t * dy))
+}
+
+fn point(p: &ContourPoint) -> Point {
+    (p.x, p.y)
+}
+
+/// Returns the sum of `points` weighted by `weights`.
+fn blend(weights: &[f64], points: &[Point]) -> Point {
+    weights.iter().zip(points).fold((0.0, 0.0), |(x, y), (w, p)| (x + w * p.0, y + w * p.1))
+}
+
+fn midpoint(a: Point, b: Point) -> Point {
+    ((a.0 + b.0) * 0.5, (a.1 + b.1) * 0.5)
+}
+
+fn lerp(a: Point, b: Point, t: f64) -> Point {
+    (a.0 + (b.0 - a.0) * t, a.1 + (b.1 - a.1) * t)
+}
+
+/// Pushes the quadratic segments of a run of off-curve points, split at their
+/// implied on-curve points.
+fn push_qcurve(segments: &mut Vec<Segment>, start: Point, offs: &[Point], end: Point) {
+    if offs.is_empty() {
+        segments.push(Segment::Line(start, end));
+        return;
+    }
+    let mut current = start;
+    for (idx, &control) in offs.iter().enumerate() {
+        let next = offs.get(idx + 1).map_or(end, |&next| midpoint(control, next));
+        segments.push(Segment::Quad(current, control, next));
+        current = next;
+    }
+}
+
+/// Pushes the cubic segments of a curve with more than two off-curve points,
+/// following fontTools' `decomposeSuperBezierSegment`.
+fn push_super_bezier(segments: &mut Vec<Segment>, start: Point, offs: &[Point], end: Point) {
+    let n = offs.len();
+    let mut current = start;
+    let mut c1 = offs[0];
+    let mut c2 = None;
+    for i in 2..=n {
+        let divisions = i.min(3).min(n + 2 - i);
+        for j in 1..divisions {
+            let temp = lerp(offs[i - 2], offs[i - 1], j as f64 / divisions as f64);
+            match c2.take() {
+                None => c2 = Some(temp),
+                Some(c2) => {
+                    let on_curve = midpoint(c2, temp);
+                    segments.push(Segment::Cubic(current, c1, c2, on_curve));
+                    current = on_curve;
+                    c1 = temp;
+                }
+            }
+        }
+    }
+    segments.push(Segment::Cubic(current, c1, offs[n - 1], end));
+}
@@ -0,0 +1,316 @@
+//! Addressing and editing objects within a glyph.
+
+use std::collections::HashSet;
+
+use super::{AffineTransform, Anchor, Component, Contour, ContourPoint, Glyph, PointType};
+use crate::error::SelectionError;
+use crate::Guideline;
+use crate::Identifier;
+
+/// A reference to an object in a [`Glyph`], as returned by
+/// [`Glyph::find_by_identifier`].
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ObjectRef<'a> {
+    /// An anchor.
+    Anchor(&'a Anchor),
+    /// A guideline.
+    Guideline(&'a Guideline),
+    /// A contour.
+    Contour(&'a Contour),
+    /// A point of a contour.
+    Point(&'a ContourPoint),
+    /// A component.
+    Component(&'a Component),
+}
+
+/// A mutable reference to an object in a [`Glyph`], as returned by
+/// [`Glyph::find_by_identifier_mut`].
+#[derive(Debug, PartialEq)]
+pub enum ObjectRefMut<'a> {
+    /// An anchor.
+    Anchor(&'a mut Anchor),
+    /// A guideline.
+    Guideline(&'a mut Guideline),
+    /// A contour.
+    Contour(&'a mut Contour),
+    /// A point of a contour.
+    Point(&'a mut ContourPoint),
+    /// A component.
+    Component(&'a mut Component),
+}
+
+/// A set of contour points in a [`Glyph`], addressed by index or by identifier.
+///
+/// # Examples
+///
+/// ```
+/// # use norad::{Identifier, PointSelection};
+/// let selection = PointSelection::new()
+///     .with_index(0, 2)
+///     .with_identifier(Identifier::new("node-1").unwrap());
+/// assert!(!selection.is_empty());
+/// ```
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct PointSelection {
+    indices: Vec<(usize, usize)>,
+    identifiers: Vec<Identifier>,
+}
+
+impl PointSelection {
+    /// Returns a new, empty selection.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Builder-style method to select the point at `point` in the contour at
+    /// `contour`.
+    pub fn with_index(mut self, contour: usize, point: usize) -> Self {
+        self.insert_index(contour, point);
+        self
+    }
+
+    /// Builder-style method to select the point with the given identifier.
+    pub fn with_identifier(mut self, identifier: Identifier) -> Self {
+        self.insert_identifier(identifier);
+        self
+    }
+
+    /// Selects the point at `point` in the contour at `contour`.
+    pub fn insert_index(&mut self, contour: usize, point: usize) {
+        self.indices.push((contour, point));
+    }
+
+    /// Selects the point with the given identifier.
+    pub fn insert_identifier(&mut self, identifier: Identifier) {
+        self.identifiers.push(identifier);
+    }
+
+    /// Returns `true` if nothing is selected.
+    pub fn is_empty(&self) -> bool {
+        self.indices.is_empty() && self.identifiers.is_empty()
+    }
+
+    /// Resolves the selection against `glyph`, returning one flag per point
+    /// for every contour.
+    fn resolve(&self, glyph: &Glyph) -> Result<Vec<Vec<bool>>, SelectionError> {
+        let mut selected: Vec<Vec<bool>> =
+            glyph.contours.iter().map(|c| vec![false; c.points.len()]).collect();
+        for &(contour, point) in &self.indices {
+            let flags =
+                selected.get_mut(contour).ok_or(SelectionError::ContourIndexOutOfRange(contour))?;
+            *flags
+                .get_mut(point)
+                .ok_or(SelectionError::PointIndexOutOfRange { contour, point })? = true;
+        }
+
+        let mut remaining: HashSet<&Identifier> = self.identifiers.iter().collect();
+        for (contour, flags) in glyph.contours.iter().zip(&mut selected) {
+            for (point, flag) in contour.points.iter().zip(flags) {
+                if let Some(id) = point.identifier() {
+                    if remaining.remove(id) {
+                        *flag = true;
+                    }
+                }
+            }
+        }
+        match remaining.into_iter().next() {
+            Some(id) => Err(SelectionError::UnknownIdentifier(id.clone())),
+            None => Ok(selected),
+        }
+    }
+}
+
+impl Glyph {
+    /// Returns the anchor, guideline, contour, point or component with the
+    /// given identifier, if any.
+    pub fn find_by_identifier(&self, id: &Identifier) -> Option<ObjectRef<'_>> {
+        let id = Some(id);
+        if let Some(anchor) = self.anchors.iter().find(|a| a.identifier() == id) {
+            return Some(ObjectRef::Anchor(anchor));
+        }
+        if let Some(guideline) = self.guidelines.iter().find(|g| g.identifier() == id) {
+            return Some(ObjectRef::Guideline(guideline));
+        }
+        for contour in &self.contours {
+            if contour.identifier() == id {
+                return Some(ObjectRef::Contour(contour));
+            }
+            if let Some(point) = contour.points.iter().find(|p| p.identifier() == id) {
+                return Some(ObjectRef::Point(point));
+            }
+        }
+        self.components.iter().find(|c| c.identifier() == id).map(ObjectRef::Component)
+    }
+
+    /// Returns a mutable reference to the anchor, guideline, contour, point or
+    /// component with the given identifier, if any.
+    pub fn find_by_identifier_mut(&mut self, id: &Identifier) -> Option<ObjectRefMut<'_>> {
+        let id = Some(id);
+        if let Some(anchor) = self.anchors.iter_mut().find(|a| a.identifier() == id) {
+            return Some(ObjectRefMut::Anchor(anchor));
+        }
+        if let Some(guideline) = self.guidelines.iter_mut().find(|g| g.identifier() == id) {
+            return Some(ObjectRefMut::Guideline(guideline));
+        }
+        for contour in &mut self.contours {
+            if contour.identifier() == id {
+                return Some(ObjectRefMut::Contour(contour));
+            }
+            if let Some(point) = contour.points.iter_mut().find(|p| p.identifier() == id) {
+                return Some(ObjectRefMut::Point(point));
+            }
+        }
+        self.components.iter_mut().find(|c| c.identifier() == id).map(ObjectRefMut::Component)
+    }
+
+    /// Returns the points of all contours, in outline order, each along with
+    /// its address: the index of its contour and its index within that
+    /// contour.
+    ///
+    /// Unlike identifiers, which are optional, these addresses exist for every
+    /// point, and are those used by [`PointSelection::with_index`]. They stay
+    /// valid as long as no contours or points are added or removed before the
+    /// point.
+    pub fn enumerate_points(&self) -> impl Iterator<Item = ((usize, usize), &ContourPoint)> {
+        self.contours.iter().enumerate().flat_map(|(contour_idx, contour)| {
+            let points = contour.points.iter().enumerate();
+            points.map(move |(point_idx, point)| ((contour_idx, point_idx), point))
+        })
+    }
+
+    /// Returns mutable references to the points of all contours, in outline
+    /// order, each along with its address, see [`Glyph::enumerate_points`].
+    pub fn enumerate_points_mut(
+        &mut self,
+    ) -> impl Iterator<Item = ((usize, usize), &mut ContourPoint)> {
+        self.contours.iter_mut().enumerate().flat_map(|(contour_idx, contour)| {
+            let points = contour.points.iter_mut().enumerate();
+            points.map(move |(point_idx, point)| ((contour_idx, point_idx), point))
+        })
+    }
+
+    /// Returns the points named `name`, in outline order, each along with its
+    /// address, see [`Glyph::enumerate_points`].
+    ///
+    /// Point names need not be unique, so all matching points are returned.
+    pub fn points_named<'b, 'a: 'b>(
+        &'a self,
+        name: &'b str,
+    ) -> impl Iterator<Item = ((usize, usize), &'a ContourPoint)> + 'b {
+        self.enumerate_points().filter(move |(_, point)| point.name.as_deref() == Some(name))
+    }
+
+    /// Returns the first point named `name`, along with its address, if any.
+    ///
+    /// See [`Glyph::points_named`] to find all points sharing a name.
+    pub fn point_named(&self, name: &str) -> Option<((usize, usize), &ContourPoint)> {
+        self.points_named(name).next()
+    }
+
+    /// Applies `transform` to the selected points, leaving all others in place.
+    ///
+    /// Off-curve points adjacent to a selected on-curve point are its handles
+    /// and are transformed along with it, so that the shape of the curves
+    /// around a moved point is preserved, as in a font editor.
+    ///
+    /// Returns an error, leaving the glyph unchanged, if the selection refers
+    /// to a contour or point index that is out of range or to an identifier
+    /// that no point in the glyph carries.
+    pub fn transform_selection(
+        &mut self,
+        selection: &PointSelection,
+        transform: AffineTransform,
+    ) -> Result<(), SelectionError> {
+        let selected = selection.resolve(self)?;
+        for (contour, selected) in self.contours.iter_mut().zip(selected) {
+            let len = contour.points.len();
+            let closed = contour.is_closed();
+            let mut affected = selected.clone();
+            for idx in (0..len).filter(|&idx| selected[idx]) {
+                if contour.points[idx].typ == PointType::OffCurve {
+                    continue;
+                }
+                let prev = match idx {
+                    0 if closed => Some(len - 1),
+                    0 => None,
+                    _ => Some(idx - 1),
+                };
+                let next = match idx + 1 {
+                    next if next < len => Some(next),
+                    _ if closed => Some(0),
+                    _ => None,
+                };
+                for neighbor in [prev, next].into_iter().flatten() {
+                    if contour.points[neighbor].typ == PointType::OffCurve {
+                        affected[neighbor] = true;
+                    }
+                }
+            }
+            for (point, affected) in contour.points.iter_mut().zip(affected) {
+                if affected {
+                    point.transform(transform);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Translates the point, anchor or component with the given identifier by
+    /// `dx` and `dy`.
+    ///
+    /// Moving an on-curve point also moves its handles, as with
+    /// [`Glyph::transform_selection`].
+    ///
+    /// Returns an error if no point, anchor or component has the identifier.
+    pub fn move_point(&mut self, id: &Identifier, dx: f64, dy: f64) -> Result<(), SelectionError> {
+        match self.find_by_identifier_mut(id) {
+            Some(ObjectRefMut::Anchor(anchor)) => {
+                anchor.x += dx;
+                anchor.y += dy;
+                Ok(())
+            }
+            Some(ObjectRefMut::Component(component)) => {
+                component.transform.x_offset += dx;
+                component.transform.y_offset += dy;
+                Ok(())
+            }
+            Some(ObjectRefMut::Point(_)) => {
+                let selection = PointSelection::new().with_identifier(id.clone());
+                let translation =
+                    AffineTransform { x_offset: dx, y_offset: dy, ..Default::default() };
+                self.transform_selection(&selection, translation)
+            }
+            _ => Err(SelectionError::UnknownIdentifier(id.clone())),
+        }
+    }
+
+    /// Moves the first anchor named `anchor_name` onto the nearest on-curve
+    /// point of the glyph's contours, if one lies within `tolerance` units.
+    ///
+    /// Components are not resolved, so only the glyph's own contours are
+    /// considered. Returns `true` if the anchor was moved, and `false` if
+    /// there is no such anchor or no on-curve point is close enough.
+    pub fn snap_anchor_to_outline(&mut self, anchor_name: &str, tolerance: f64) -> bool {
+        let Some(anchor) =
+            self.anchors.iter_mut().find(|anchor| anchor.name.as_deref() == Some(anchor_name))
+        else {
+            return false;
+        };
+        let nearest = self
+            .contours
+            .iter()
+            .flat_map(|contour| &contour.points)
+            .filter(|point| point.typ != PointType::OffCurve)
+            .map(|point| (point, (point.x - anchor.x).hypot(point.y - anchor.y)))
+            .filter(|(_, distance)| *distance <= tolerance)
+            .min_by(|(_, a), (_, b)| a.total_cmp(b));
+        match nearest {
+            Some((point, _)) => {
+                anchor.x = point.x;
+                anchor.y = point.y;
+                true
+            }
+            None => false,
+        }
+    }
+}
@@ -0,0 +1,178 @@
+//! Helpers for the serde implementations of glyph data.
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use super::builder::validate_points;
+use super::{AffineTransform, Anchor, Component, Contour, ContourPoint, Image, PointType};
+use crate::error::{ErrorKind, StoreError};
+use crate::{Color, Guideline, Identifier, Name, Plist};
+
+/// The unvalidated form of an [`Image`], checked with [`Image::new`].
+#[derive(Deserialize)]
+pub(super) struct RawImage {
+    #[serde(rename = "fileName")]
+    file_name: std::path::PathBuf,
+    #[serde(default)]
+    color: Option<Color>,
+    transform: AffineTransform,
+}
+
+impl TryFrom<RawImage> for Image {
+    type Error = StoreError;
+
+    fn try_from(raw: RawImage) -> Result<Self, Self::Error> {
+        Image::new(raw.file_name, raw.color, raw.transform)
+    }
+}
+
+/// Checks that an object with a lib has an identifier to store it under in
+/// `public.objectLibs`, as the lib setters ensure.
+fn check_lib(identifier: Option<&Identifier>, lib: Option<&Plist>) -> Result<(), ErrorKind> {
+    match (identifier, lib) {
+        (None, Some(_)) => Err(ErrorKind::LibWithoutIdentifier),
+        _ => Ok(()),
+    }
+}
+
+/// The unvalidated form of an [`Anchor`].
+#[derive(Deserialize)]
+pub(super) struct RawAnchor {
+    x: f64,
+    y: f64,
+    #[serde(default)]
+    name: Option<Name>,
+    #[serde(default)]
+    color: Option<Color>,
+    #[serde(default)]
+    identifier: Option<Identifier>,
+    #[serde(default)]
+    lib: Option<Plist>,
+}
+
+impl TryFrom<RawAnchor> for Anchor {
+    type Error = ErrorKind;
+
+    fn try_from(raw: RawAnchor) -> Result<Self, Self::Error> {
+        check_lib(raw.identifier.as_ref(), raw.lib.as_ref())?;
+        let RawAnchor { x, y, name, color, identifier, lib } = raw;
+        Ok(Anchor { x, y, name, color, identifier, lib })
+    }
+}
+
+/// The unvalidated form of a [`Component`].
+#[derive(Deserialize)]
+pub(super) struct RawComponent {
+    base: Name,
+    transform: AffineTransform,
+    #[serde(default)]
+    identifier: Option<Identifier>,
+    #[serde(default)]
+    lib: Option<Plist>,
+}
+
+impl TryFrom<RawComponent> for Component {
+    type Error = ErrorKind;
+
+    fn try_from(raw: RawComponent) -> Result<Self, Self::Error> {
+        check_lib(raw.identifier.as_ref(), raw.lib.as_ref())?;
+        let RawComponent { base, transform, identifier, lib } = raw;
+        Ok(Component { base, transform, identifier, lib })
+    }
+}
+
+/// The unvalidated form of a [`Contour`], whose points must form a valid
+/// contour.
+#[derive(Deserialize)]
+pub(super) struct RawContour {
+    points: Vec<ContourPoint>,
+    #[serde(default)]
+    identifier: Option<Identifier>,
+    #[serde(default)]
+    lib: Option<Plist>,
+}
+
+impl TryFrom<RawContour> for Contour {
+    type Error = ErrorKind;
+
+    fn try_from(raw: RawContour) -> Result<Self, Self::Error> {
+        check_lib(raw.identifier.as_ref(), raw.lib.as_ref())?;
+        validate_points(&raw.points)?;
+        let RawContour { points, identifier, lib } = raw;
+        Ok(Contour { points, identifier, lib })
+    }
+}
+
+/// The unvalidated form of a [`ContourPoint`].
+#[derive(Deserialize)]
+pub(super) struct RawContourPoint {
+    x: f64,
+    y: f64,
+    #[serde(rename = "type")]
+    typ: PointType,
+    #[serde(default)]
+    smooth: bool,
+    #[serde(default)]
+    name: Option<Name>,
+    #[serde(default)]
+    identifier: Option<Identifier>,
+    #[serde(default)]
+    lib: Option<Plist>,
+}
+
+impl TryFrom<RawContourPoint> for ContourPoint {
+    type Error = ErrorKind;
+
+    fn try_from(raw: RawContourPoint) -> Result<Self, Self::Error> {
+        check_lib(raw.identifier.as_ref(), raw.lib.as_ref())?;
+        let RawContourPoint { x, y, typ, smooth, name, identifier, lib } = raw;
+        Ok(ContourPoint { x, y, typ, smooth, name, identifier, lib })
+    }
+}
+
+/// Glyph guidelines, including their lib.
+///
+/// The serde implementation of [`Guideline`] follows the fontinfo.plist
+/// format, which has no room for a lib.
+pub(super) mod guidelines {
+    use super::*;
+
+    #[derive(Serialize)]
+    struct GuidelineRef<'a> {
+        #[serde(flatten)]
+        guideline: &'a Guideline,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        lib: Option<&'a Plist>,
+    }
+
+    #[derive(Deserialize)]
+    struct GuidelineWithLib {
+        #[serde(flatten)]
+        guideline: Guideline,
+        #[serde(default)]
+        lib: Option<Plist>,
+    }
+
+    pub(in crate::glyph) fn serialize<S: Serializer>(
+        guidelines: &[Guideline],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(
+            guidelines.iter().map(|guideline| GuidelineRef { guideline, lib: guideline.lib() }),
+        )
+    }
+
+    pub(in crate::glyph) fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Vec<Guideline>, D::Error> {
+        let guidelines = Vec::<GuidelineWithLib>::deserialize(deserializer)?;
+        guidelines
+            .into_iter()
+            .map(|GuidelineWithLib { mut guideline, lib }| {
+                check_lib(guideline.identifier(), lib.as_ref())
+                    .map_err(serde::de::Error::custom)?;
+                guideline.lib = lib;
+                Ok(guideline)
+            })
+            .collect()
+    }
+}
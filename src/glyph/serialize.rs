@@ -1,5 +1,6 @@
 //! Writing out .glif files
 
+use std::cmp::Ordering;
 use std::io::{self, Cursor, Write};
 
 use quick_xml::{
@@ -7,10 +8,10 @@ use quick_xml::{
     Writer,
 };
 
-use super::PUBLIC_OBJECT_LIBS_KEY;
+use super::{codepoints, PUBLIC_OBJECT_LIBS_KEY};
 use crate::{
-    util, AffineTransform, Anchor, Color, Component, Contour, ContourPoint, Glyph, Guideline,
-    Image, Line, Plist, PointType, WriteOptions,
+    util, AffineTransform, Anchor, Color, Component, Contour, ContourPoint, GlifVersion, Glyph,
+    Guideline, Image, Line, Plist, PointType, WriteOptions,
 };
 
 use crate::error::GlifWriteError;
@@ -56,7 +57,8 @@ impl Glyph {
         start.push_attribute(("name", &*self.name));
         // we always serialize 2.0
         //TODO: write out formatMinor if we start to support glif 2.1?
-        start.push_attribute(("format", "2"));
+        let format = GlifVersion::V2.as_u8().to_string();
+        start.push_attribute(("format", format.as_str()));
         writer.write_event(Event::Start(start)).map_err(GlifWriteError::Buffer)?;
 
         for codepoint in &self.codepoints {
@@ -76,7 +78,7 @@ impl Glyph {
         }
 
         if let Some(ref image) = self.image {
-            writer.write_event(image.to_event()).map_err(GlifWriteError::Buffer)?;
+            writer.write_event(image.to_event(options)).map_err(GlifWriteError::Buffer)?;
         }
 
         if !self.contours.is_empty() || !self.components.is_empty() {
@@ -94,12 +96,19 @@ impl Glyph {
                 .map_err(GlifWriteError::Buffer)?;
         }
 
-        for anchor in &self.anchors {
-            writer.write_event(anchor.to_event()).map_err(GlifWriteError::Buffer)?;
+        let mut anchors: Vec<&Anchor> = self.anchors.iter().collect();
+        let mut guidelines: Vec<&Guideline> = self.guidelines.iter().collect();
+        if options.sort_anchors {
+            anchors.sort_by(|a, b| compare_anchors(a, b));
+            guidelines.sort_by(|a, b| compare_guidelines(a, b));
         }
 
-        for guide in &self.guidelines {
-            writer.write_event(guide.to_event()).map_err(GlifWriteError::Buffer)?;
+        for anchor in anchors {
+            writer.write_event(anchor.to_event(options)).map_err(GlifWriteError::Buffer)?;
+        }
+
+        for guide in guidelines {
+            writer.write_event(guide.to_event(options)).map_err(GlifWriteError::Buffer)?;
         }
 
         // Object libs are treated specially. The UFO v3 format won't allow us
@@ -110,7 +119,7 @@ impl Glyph {
         // existing lib and insert them there for serialization, otherwise avoid
         // cloning and write out the original.
         let mut lib = self.lib.clone();
-        let object_libs = self.dump_object_libs();
+        let object_libs = self.dump_object_libs()?;
         if !object_libs.is_empty() {
             lib.insert(PUBLIC_OBJECT_LIBS_KEY.into(), object_libs.into());
         }
@@ -140,6 +149,32 @@ impl Glyph {
     }
 }
 
+/// Orders anchors by name (unnamed first), then by position.
+pub(super) fn compare_anchors(a: &Anchor, b: &Anchor) -> Ordering {
+    a.name.cmp(&b.name).then(a.x.total_cmp(&b.x)).then(a.y.total_cmp(&b.y))
+}
+
+/// Orders guidelines by name (unnamed first), then by line.
+///
+/// Vertical lines sort before horizontal ones, which sort before angled ones.
+pub(super) fn compare_guidelines(a: &Guideline, b: &Guideline) -> Ordering {
+    fn line_key(line: &Line) -> (u8, f64, f64, f64) {
+        match *line {
+            Line::Vertical(x) => (0, x, 0., 0.),
+            Line::Horizontal(y) => (1, 0., y, 0.),
+            Line::Angle { x, y, degrees } => (2, x, y, degrees),
+        }
+    }
+    let (a_kind, a_x, a_y, a_deg) = line_key(&a.line);
+    let (b_kind, b_x, b_y, b_deg) = line_key(&b.line);
+    a.name
+        .cmp(&b.name)
+        .then(a_kind.cmp(&b_kind))
+        .then(a_x.total_cmp(&b_x))
+        .then(a_y.total_cmp(&b_y))
+        .then(a_deg.total_cmp(&b_deg))
+}
+
 /// Writing out the embedded lib plist that a glif may have.
 ///
 /// To write the lib section we write the lib as a plist to an empty buffer,
@@ -179,7 +214,7 @@ fn write_lib_section<T: Write>(
 }
 
 impl Guideline {
-    fn to_event(&self) -> Event {
+    fn to_event(&self, options: &WriteOptions) -> Event<'_> {
         let mut start = BytesStart::new("guideline");
         let (x, y, angle) = match self.line {
             Line::Vertical(x) => (Some(x), None, None),
@@ -204,7 +239,10 @@ impl Guideline {
         }
 
         if let Some(color) = &self.color {
-            start.push_attribute(("color", color.to_rgba_string().as_str()));
+            start.push_attribute((
+                "color",
+                color.format_rgba(options.full_color_precision).as_str(),
+            ));
         }
 
         if let Some(id) = &self.identifier() {
@@ -215,7 +253,7 @@ impl Guideline {
 }
 
 impl Anchor {
-    fn to_event(&self) -> Event {
+    fn to_event(&self, options: &WriteOptions) -> Event<'_> {
         let mut start = BytesStart::new("anchor");
 
         if let Some(name) = &self.name {
@@ -226,7 +264,10 @@ impl Anchor {
         start.push_attribute(("y", self.y.to_string().as_str()));
 
         if let Some(color) = &self.color {
-            start.push_attribute(("color", color.to_rgba_string().as_str()));
+            start.push_attribute((
+                "color",
+                color.format_rgba(options.full_color_precision).as_str(),
+            ));
         }
 
         if let Some(id) = &self.identifier {
@@ -238,7 +279,7 @@ impl Anchor {
 }
 
 impl Component {
-    fn to_event(&self) -> Event {
+    fn to_event(&self) -> Event<'_> {
         let mut start = BytesStart::new("component");
         start.push_attribute(("base", &*self.base));
 
@@ -269,7 +310,7 @@ impl Contour {
 }
 
 impl ContourPoint {
-    fn to_event(&self) -> Event {
+    fn to_event(&self) -> Event<'_> {
         let mut start = BytesStart::new("point");
 
         if let Some(name) = &self.name {
@@ -314,6 +355,12 @@ impl Color {
     ///
     /// [0]: https://unifiedfontobject.org/versions/ufo3/conventions/#colors
     pub fn to_rgba_string(&self) -> String {
+        self.format_rgba(false)
+    }
+
+    /// Like [`Color::to_rgba_string`], but with `full_precision`, writes each
+    /// channel with as many digits as needed to read back the exact value.
+    pub(crate) fn format_rgba(&self, full_precision: bool) -> String {
         use std::fmt::Write;
 
         // TODO: Check that all channels are 0.0..=1.0
@@ -325,6 +372,10 @@ impl Color {
                 result.push(',');
             }
 
+            if full_precision {
+                let _ = write!(&mut result, "{}", channel);
+                continue;
+            }
             scratch.clear();
             // This can only fail on an allocation error, in which case we have other problems.
             let _ = write!(&mut scratch, "{:.3}", channel);
@@ -335,14 +386,17 @@ impl Color {
 }
 
 impl Image {
-    fn to_event(&self) -> Event {
+    fn to_event(&self, options: &WriteOptions) -> Event<'_> {
         let mut start = BytesStart::new("image");
         start.push_attribute(("fileName", self.file_name.to_str().expect("missing path")));
 
         write_transform_attributes(&mut start, &self.transform);
 
         if let Some(color) = &self.color {
-            start.push_attribute(("color", color.to_rgba_string().as_str()));
+            start.push_attribute((
+                "color",
+                color.format_rgba(options.full_color_precision).as_str(),
+            ));
         }
         Event::Empty(start)
     }
@@ -350,8 +404,7 @@ impl Image {
 
 fn char_to_event(c: char) -> Event<'static> {
     let mut start = BytesStart::new("unicode");
-    let hex = format!("{:04X}", c as u32);
-    start.push_attribute(("hex", hex.as_str()));
+    start.push_attribute(("hex", codepoints::to_hex(c).as_str()));
     Event::Empty(start)
 }
 
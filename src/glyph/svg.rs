@@ -0,0 +1,71 @@
+//! Converting glyph outlines to SVG path data.
+
+use std::fmt::Write;
+
+use super::segments::{Point, Segment};
+use super::Glyph;
+use crate::Layer;
+
+impl Glyph {
+    /// Returns the glyph's outline as SVG path data, suitable for the `d`
+    /// attribute of a `path` element.
+    ///
+    /// Components are resolved against `layer`, as with [`Glyph::bounds`].
+    /// Each contour starts with a move command and consists of line (`L`),
+    /// quadratic (`Q`) and cubic (`C`) commands; closed contours end with `Z`,
+    /// which implies a final straight line back to the start.
+    ///
+    /// Font coordinates point up, while SVG coordinates point down. With
+    /// `flip_y`, y coordinates are negated, so the baseline is at `y=0` and
+    /// the glyph extends into negative y; a `viewBox` of
+    /// `0 -{ascender} {width} {ascender - descender}` then frames it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::{Contour, ContourPoint, Glyph, Layer, PointType};
+    /// let mut glyph = Glyph::new("triangle");
+    /// let point = |x, y| ContourPoint::new(x, y, PointType::Line, false, None, None);
+    /// glyph.contours.push(Contour::new(vec![point(0., 0.), point(100., 0.), point(50., 80.)], None));
+    ///
+    /// let layer = Layer::default();
+    /// assert_eq!(glyph.to_svg_path(&layer, false), "M0 0 L100 0 L50 80 Z");
+    /// assert_eq!(glyph.to_svg_path(&layer, true), "M0 0 L100 0 L50 -80 Z");
+    /// ```
+    pub fn to_svg_path(&self, layer: &Layer, flip_y: bool) -> String {
+        let mut path = String::new();
+        self.visit_resolved_contours(layer, |contour, transform| {
+            let mut segments: Vec<Segment> =
+                contour.segments().into_iter().map(|s| s.transform(transform)).collect();
+            let Some(start) = segments.first().map(Segment::start) else { return };
+            let closed = contour.is_closed();
+            if closed && matches!(segments.last(), Some(Segment::Line(..))) {
+                segments.pop();
+            }
+
+            let point = |(x, y): Point| {
+                // Adding zero turns a negated zero into a plain zero.
+                let y = if flip_y { -y } else { y };
+                format!("{} {}", x + 0.0, y + 0.0)
+            };
+            if !path.is_empty() {
+                path.push(' ');
+            }
+            // Writing to a String cannot fail.
+            let _ = write!(path, "M{}", point(start));
+            for segment in &segments {
+                let _ = match *segment {
+                    Segment::Line(_, p1) => write!(path, " L{}", point(p1)),
+                    Segment::Quad(_, p1, p2) => write!(path, " Q{} {}", point(p1), point(p2)),
+                    Segment::Cubic(_, p1, p2, p3) => {
+                        write!(path, " C{} {} {}", point(p1), point(p2), point(p3))
+                    }
+                };
+            }
+            if closed {
+                path.push_str(" Z");
+            }
+        });
+        path
+    }
+}
@@ -1,9 +1,20 @@
 use super::parse::parse_glyph;
 use super::*;
+use crate::error::SelectionError;
 use crate::write::QuoteChar;
 use std::path::PathBuf;
 use std::str::FromStr;
 
+/// Returns an unsmooth point without name or identifier.
+pub(crate) fn point(x: f64, y: f64, typ: PointType) -> ContourPoint {
+    ContourPoint::new(x, y, typ, false, None, None)
+}
+
+/// Returns a contour without identifier of [`point`]s.
+pub(crate) fn contour(points: &[(f64, f64, PointType)]) -> Contour {
+    Contour::new(points.iter().map(|(x, y, typ)| point(*x, *y, typ.clone())).collect(), None)
+}
+
 #[test]
 #[allow(clippy::float_cmp)]
 fn transform() {
@@ -51,6 +62,56 @@ fn parse_format_unsupported_major() {
     let _ = parse_glyph(data.as_bytes()).unwrap();
 }
 
+#[test]
+#[should_panic(expected = "UnsupportedGlifVersion")]
+fn parse_format_unsupported_minor() {
+    let data = r#"
+ <?xml version="1.0" encoding="UTF-8"?>
+ <glyph name="a" format="2" formatMinor="1">
+ </glyph>
+     "#
+    .trim();
+    let _ = parse_glyph(data.as_bytes()).unwrap();
+}
+
+#[test]
+fn parse_trailing_content() {
+    let glif = |trailer: &str| {
+        format!("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<glyph name=\"a\" format=\"2\">\n</glyph>\n{trailer}")
+    };
+    assert!(parse_glyph(glif("").as_bytes()).is_ok());
+    assert!(parse_glyph(glif("<!-- a comment -->\n\n").as_bytes()).is_ok());
+    assert!(parse_glyph(glif("<?editor state=\"closed\"?>\n").as_bytes()).is_ok());
+    for trailer in ["garbage", "<glyph name=\"b\" format=\"2\">\n</glyph>\n", "<advance/>"] {
+        assert!(
+            matches!(
+                parse_glyph(glif(trailer).as_bytes()),
+                Err(GlifLoadError::Parse(ErrorKind::TrailingContent))
+            ),
+            "{trailer}"
+        );
+    }
+}
+
+#[test]
+fn from_reader() {
+    let data = include_bytes!("../../testdata/sample_period.glif");
+    let glyph = Glyph::from_reader(&data[..]).unwrap();
+    assert_eq!(glyph, parse_glyph(data).unwrap());
+
+    let mut data = data.to_vec();
+    data.extend_from_slice(b"</glyph>");
+    assert!(Glyph::from_reader(data.as_slice()).is_err());
+}
+
+#[test]
+fn glif_version_ordering() {
+    assert!(GlifVersion::V1 < GlifVersion::V2);
+    assert_eq!(GlifVersion::V1.as_u8(), 1);
+    assert_eq!(GlifVersion::from_u8(2), Some(GlifVersion::V2));
+    assert_eq!(GlifVersion::from_u8(3), None);
+}
+
 #[test]
 fn serialize_empty_glyph_explicit_line_ending_check() {
     let glyph = Glyph::new("a");
@@ -100,6 +161,33 @@ fn serialize_with_default_formatting() {
     );
 }
 
+#[test]
+fn smart_component_lib_round_trip() {
+    let data = include_bytes!("../../testdata/smart_component.glif");
+    let glyph = parse_glyph(data).unwrap();
+    assert!(!glyph.lib.contains_key("public.objectLibs"));
+    let values = glyph.components[0].lib().unwrap();
+    let values = values["com.schriftgestaltung.Glyphs.smartComponentValues"].as_dictionary();
+    assert_eq!(values.unwrap()["Height"].as_real(), Some(37.25));
+
+    let axes = glyph.lib["com.schriftgestaltung.Glyphs.glyph.smartComponentAxes"].as_array();
+    let height = axes.unwrap()[0].as_dictionary().unwrap();
+    assert_eq!(height["topValue"].as_real(), Some(100.5));
+    assert_eq!(height["bottomValue"].as_signed_integer(), Some(0));
+    let user_data = glyph.lib["com.schriftgestaltung.Glyphs.userData"].as_dictionary().unwrap();
+    assert_eq!(user_data["thumbnail"].as_data(), Some(&b"\x89PNG\r\n\x1a\n"[..]));
+    assert!(glyph.lib["com.schriftgestaltung.Glyphs.lastChange"].as_date().is_some());
+
+    // Nothing may be lost or altered, down to integers staying integers.
+    let encoded = glyph.encode_xml().unwrap();
+    let reparsed = parse_glyph(&encoded).unwrap();
+    assert_eq!(reparsed, glyph);
+    let info = &reparsed.lib["com.schriftgestaltung.Glyphs.ComponentInfo"].as_array().unwrap()[0];
+    let piece = info.as_dictionary().unwrap()["piece"].as_dictionary().unwrap();
+    assert_eq!(piece["Width"], plist::Value::Integer((-40).into()));
+    assert_eq!(piece["Height"], plist::Value::Real(62.5));
+}
+
 #[test]
 fn serialize_with_custom_whitespace() {
     let data = include_str!("../../testdata/small_lib.glif");
@@ -193,6 +281,47 @@ fn serialize_with_custom_whitespace_and_single_quote_style() {
     );
 }
 
+#[test]
+fn serialize_with_sorted_anchors() {
+    let mut glyph = Glyph::new("a");
+    glyph.anchors.push(Anchor::new(10., 20., Some(Name::new_raw("top")), None, None));
+    glyph.anchors.push(Anchor::new(30., 40., Some(Name::new_raw("bottom")), None, None));
+    glyph.anchors.push(Anchor::new(50., 60., None, None, None));
+    glyph.guidelines.push(Guideline::new(Line::Horizontal(500.), None, None, None));
+    glyph.guidelines.push(Guideline::new(Line::Vertical(100.), None, None, None));
+
+    let unsorted = glyph.encode_xml().unwrap();
+    let unsorted = std::str::from_utf8(&unsorted).unwrap();
+    pretty_assertions::assert_eq!(
+        unsorted,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="2">
+	<anchor name="top" x="10" y="20"/>
+	<anchor name="bottom" x="30" y="40"/>
+	<anchor x="50" y="60"/>
+	<guideline y="500"/>
+	<guideline x="100"/>
+</glyph>
+"#
+    );
+
+    let options = WriteOptions::default().sort_anchors(true);
+    let sorted = glyph.encode_xml_with_options(&options).unwrap();
+    let sorted = std::str::from_utf8(&sorted).unwrap();
+    pretty_assertions::assert_eq!(
+        sorted,
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="2">
+	<anchor x="50" y="60"/>
+	<anchor name="bottom" x="30" y="40"/>
+	<anchor name="top" x="10" y="20"/>
+	<guideline x="100"/>
+	<guideline y="500"/>
+</glyph>
+"#
+    );
+}
+
 #[test]
 fn parse() {
     let bytes = include_bytes!("../../testdata/sample_period.glif");
@@ -389,6 +518,47 @@ fn if_no_one_uses_your_lib_is_it_broken() {
     assert!(glyph.lib.get("public.objectLibs").is_none());
 }
 
+#[test]
+fn lib_keys_include_object_libs() {
+    let data = r#"
+<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="period" format="2">
+    <anchor name="top" x="74" y="197" identifier="anchor1"/>
+    <outline>
+        <contour>
+            <point x="0" y="0" type="line" identifier="point1"/>
+        </contour>
+    </outline>
+    <lib>
+        <dict>
+            <key>com.example.glyph</key>
+            <true/>
+            <key>public.objectLibs</key>
+            <dict>
+                <key>anchor1</key>
+                <dict>
+                    <key>com.example.anchor</key>
+                    <true/>
+                </dict>
+                <key>point1</key>
+                <dict>
+                    <key>com.example.glyph</key>
+                    <true/>
+                    <key>com.example.point</key>
+                    <true/>
+                </dict>
+            </dict>
+        </dict>
+    </lib>
+</glyph>
+"#;
+    let glyph = parse_glyph(data.as_bytes()).unwrap();
+    assert_eq!(
+        glyph.lib_keys().into_iter().collect::<Vec<_>>(),
+        ["com.example.anchor", "com.example.glyph", "com.example.point"]
+    );
+}
+
 #[test]
 fn parse_note() {
     let bytes = include_bytes!("../../testdata/note.glif");
@@ -862,6 +1032,16 @@ fn has_component_with_base() {
     assert!(!glyph.has_component_with_base("Z"));
 }
 
+#[test]
+fn remove_components_with_base() {
+    let bytes = include_bytes!("../../testdata/MutatorSansLightWide.ufo/glyphs/quotedblbase.glif");
+    let mut glyph = parse_glyph(bytes).expect("initial load failed");
+    assert_eq!(glyph.remove_components_with_base("Z"), 0);
+    assert_eq!(glyph.component_count(), 2);
+    assert_eq!(glyph.remove_components_with_base("comma"), 2);
+    assert!(!glyph.has_component());
+}
+
 #[test]
 fn deduplicate_unicodes2() {
     let data = r#"
@@ -895,3 +1075,1823 @@ fn bom_glif() {
     let glyph = parse_glyph(bytes).expect("initial load failed");
     assert_eq!(glyph.lib.get("hi").unwrap().as_string(), Some("hello"));
 }
+
+#[test]
+fn non_utf8_encoding_is_rejected() {
+    let mut data = b"<?xml version=\"1.0\" encoding=\"ISO-8859-1\"?>\n".to_vec();
+    data.extend_from_slice(b"<glyph name=\"a\" format=\"2\">\n<note>caf\xE9</note>\n</glyph>\n");
+    assert!(matches!(
+        parse_glyph(&data),
+        Err(GlifLoadError::Parse(ErrorKind::UnsupportedEncoding))
+    ));
+
+    let data = "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<glyph name=\"a\" format=\"2\">\n<note>café</note>\n</glyph>\n";
+    assert_eq!(parse_glyph(data.as_bytes()).unwrap().note.as_deref(), Some("café"));
+}
+
+#[test]
+fn contour_is_open() {
+    let open = contour(&[(0., 0., PointType::Move), (100., 0., PointType::Line)]);
+    assert!(open.is_open());
+    assert!(!open.is_closed());
+
+    let closed = contour(&[(0., 0., PointType::Line), (100., 0., PointType::Line)]);
+    assert!(!closed.is_open());
+    assert!(closed.is_closed());
+}
+
+#[test]
+#[cfg(feature = "kurbo")]
+fn open_contour_to_kurbo() {
+    use kurbo::{PathEl, Point};
+
+    let contour = contour(&[
+        (0., 0., PointType::Move),
+        (100., 0., PointType::Line),
+        (100., 100., PointType::Line),
+    ]);
+    let path = contour.to_kurbo().unwrap();
+    assert_eq!(
+        path.elements(),
+        &[
+            PathEl::MoveTo(Point::new(0., 0.)),
+            PathEl::LineTo(Point::new(100., 0.)),
+            PathEl::LineTo(Point::new(100., 100.)),
+        ]
+    );
+}
+
+#[test]
+#[cfg(feature = "kurbo")]
+fn closed_contour_to_kurbo() {
+    use kurbo::{PathEl, Point};
+
+    let triangle = contour(&[
+        (0., 0., PointType::Line),
+        (100., 0., PointType::Line),
+        (100., 100., PointType::Line),
+    ]);
+    let path = triangle.to_kurbo().unwrap();
+    assert_eq!(
+        path.elements(),
+        &[
+            PathEl::MoveTo(Point::new(100., 100.)),
+            PathEl::LineTo(Point::new(0., 0.)),
+            PathEl::LineTo(Point::new(100., 0.)),
+            PathEl::ClosePath,
+        ]
+    );
+
+    let curved = contour(&[
+        (0., 0., PointType::Curve),
+        (0., 50., PointType::OffCurve),
+        (50., 100., PointType::OffCurve),
+        (100., 100., PointType::Curve),
+        (100., 50., PointType::OffCurve),
+        (50., 0., PointType::OffCurve),
+    ]);
+    let path = curved.to_kurbo().unwrap();
+    assert_eq!(
+        path.elements(),
+        &[
+            PathEl::MoveTo(Point::new(100., 100.)),
+            PathEl::CurveTo(Point::new(100., 50.), Point::new(50., 0.), Point::new(0., 0.)),
+            PathEl::CurveTo(Point::new(0., 50.), Point::new(50., 100.), Point::new(100., 100.)),
+            PathEl::ClosePath,
+        ]
+    );
+}
+
+#[test]
+fn simplify_contour() {
+    // A closed square with redundant points along two of its edges.
+    let mut square = contour(&[
+        (0., 0., PointType::Line),
+        (50., 0.2, PointType::Line),
+        (100., 0., PointType::Line),
+        (100., 50., PointType::Line),
+        (100., 100., PointType::Line),
+        (0., 100., PointType::Line),
+    ]);
+    assert_eq!(square.simplify(0.5), 2);
+    assert!(square.is_closed());
+    let coords: Vec<_> = square.points.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(coords, [(0., 0.), (100., 0.), (100., 100.), (0., 100.)]);
+
+    // Points outside the tolerance stay.
+    let mut open = contour(&[
+        (0., 0., PointType::Move),
+        (50., 10., PointType::Line),
+        (100., 0., PointType::Line),
+    ]);
+    assert_eq!(open.simplify(1.), 0);
+    assert!(open.is_open());
+}
+
+#[test]
+fn quantize() {
+    let mut glyph = Glyph::new("a");
+    glyph.width = 501.25;
+    glyph.contours.push(contour(&[(0.4, 1.3, PointType::Line), (98.7, 3.6, PointType::Line)]));
+    let transform =
+        AffineTransform { x_scale: 0.3, x_offset: 11.0, y_offset: -6.4, ..Default::default() };
+    glyph.components.push(Component::new(Name::new_raw("b"), transform, None));
+    glyph.anchors.push(Anchor::new(248.9, 700.2, None, None, None));
+    glyph.guidelines.push(Guideline::new(
+        Line::Angle { x: 12.6, y: 3.1, degrees: 33.3 },
+        None,
+        None,
+        None,
+    ));
+
+    let mut coarse = glyph.clone();
+    assert_eq!(coarse.quantize(2.5), 1.25);
+    let coords: Vec<_> = coarse.contours[0].points.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(coords, [(0.0, 2.5), (97.5, 2.5)]);
+    let transform = coarse.components[0].transform;
+    assert_eq!((transform.x_scale, transform.x_offset, transform.y_offset), (0.3, 10.0, -7.5));
+    assert_eq!((coarse.anchors[0].x, coarse.anchors[0].y), (250.0, 700.0));
+    assert_eq!(coarse.guidelines[0].line, Line::Angle { x: 12.5, y: 2.5, degrees: 33.3 });
+    assert_eq!(coarse.width, 502.5);
+
+    let mut fine = glyph.clone();
+    fine.quantize(0.5);
+    let coords: Vec<_> = fine.contours[0].points.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(coords, [(0.5, 1.5), (98.5, 3.5)]);
+    assert_eq!(fine.quantize(0.5), 0.0);
+
+    let mut unchanged = glyph.clone();
+    assert_eq!(unchanged.quantize(0.0), 0.0);
+    assert_eq!(unchanged, glyph);
+}
+
+#[test]
+fn point_tuples() {
+    let tuples: Vec<PointTuple> = vec![
+        (0.0, 0.0, Some("move"), false, Some("start"), None),
+        (10.0, 20.0, None, false, None, None),
+        (30.0, 20.0, None, false, None, None),
+        (40.0, 0.0, Some("curve"), true, None, Some("id1")),
+    ];
+    let contour = Contour::from_tuples(tuples.clone()).unwrap();
+    assert!(contour.is_open());
+    assert_eq!(contour.points[1].typ, PointType::OffCurve);
+    assert_eq!(contour.points[0].name.as_deref(), Some("start"));
+    assert_eq!(contour.points[3].identifier().map(Identifier::as_str), Some("id1"));
+    assert!(contour.points[3].smooth);
+    assert_eq!(contour.to_tuples(), tuples);
+
+    let closed = Contour::from_tuples([(0.0, 0.0, Some("qcurve"), false, None, None)]).unwrap();
+    assert!(closed.is_closed());
+    assert_eq!(closed.to_tuples()[0].2, Some("qcurve"));
+
+    let unknown = ContourPoint::from_tuple((0.0, 0.0, Some("arc"), false, None, None));
+    assert!(matches!(unknown, Err(ErrorKind::UnknownPointType)));
+    let bad_name = ContourPoint::from_tuple((0.0, 0.0, None, false, Some(""), None));
+    assert!(matches!(bad_name, Err(ErrorKind::InvalidName)));
+    let late_move =
+        [(0.0, 0.0, Some("line"), false, None, None), (1.0, 1.0, Some("move"), false, None, None)];
+    assert!(Contour::from_tuples(late_move).is_err());
+}
+
+#[test]
+fn simplify_keeps_curves_and_tagged_points() {
+    let points = vec![
+        point(0., 0., PointType::Move),
+        point(50., 0., PointType::Line),
+        point(100., 0., PointType::Line),
+        point(150., 0., PointType::OffCurve),
+        point(200., 0., PointType::OffCurve),
+        point(250., 0., PointType::Curve),
+        ContourPoint::new(300., 0., PointType::Line, false, Some(Name::new_raw("tag")), None),
+        point(350., 0., PointType::Line),
+    ];
+
+    let mut contour = Contour::new(points.clone(), None);
+    assert_eq!(contour.simplify(1.), 1);
+    assert_eq!(contour.points.len(), 7);
+    assert!(contour.points.iter().all(|p| p.x != 50.));
+    assert!(contour.is_open());
+
+    let mut contour = Contour::new(points, None);
+    assert_eq!(contour.simplify_all(1.), 2);
+    assert!(contour.points.iter().all(|p| p.name.is_none()));
+    assert_eq!(contour.points.last().unwrap().x, 350.);
+}
+
+#[test]
+fn visually_eq_ignores_metadata() {
+    let glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+
+    let mut other = glyph.clone();
+    other.lib.insert("com.example.foo".into(), plist::Value::Boolean(true));
+    other.note = Some("a note".into());
+    other.contours[0].replace_identifier(Identifier::new_raw("new-contour-id"));
+    other.contours[0].points[0].replace_identifier(Identifier::new_raw("new-point-id"));
+    other.anchors.reverse();
+    assert_ne!(glyph, other);
+    assert!(glyph.visually_eq(&other));
+
+    other.contours[0].points[0].x += 1.;
+    assert!(!glyph.visually_eq(&other));
+
+    let mut other = glyph.clone();
+    other.width += 1.;
+    assert!(!glyph.visually_eq(&other));
+
+    let mut other = glyph.clone();
+    other.anchors[0].y += 1.;
+    assert!(!glyph.visually_eq(&other));
+}
+
+#[test]
+fn outline_eq_ignores_spacing() {
+    let glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+
+    let mut other = glyph.clone();
+    other.width += 10.;
+    other.anchors[0].x += 10.;
+    other.contours[0].points[0].replace_identifier(Identifier::new_raw("new-point-id"));
+    assert!(!glyph.visually_eq(&other));
+    assert!(glyph.outline_eq(&other));
+
+    other.contours[0].points[0].smooth = !other.contours[0].points[0].smooth;
+    assert!(!glyph.outline_eq(&other));
+
+    let mut other = glyph.clone();
+    other.components[0].transform.x_offset += 1.;
+    assert!(!glyph.outline_eq(&other));
+}
+
+#[test]
+fn dedup_anchors() {
+    use crate::error::DuplicateAnchorsError;
+    use crate::DuplicateAnchors;
+
+    let anchor = |name: Option<&str>, x| Anchor::new(x, 0., name.map(Name::new_raw), None, None);
+    let mut glyph = Glyph::new("a");
+    glyph.anchors = vec![
+        anchor(Some("top"), 1.),
+        anchor(Some("bottom"), 2.),
+        anchor(None, 3.),
+        anchor(Some("top"), 4.),
+        anchor(None, 5.),
+        anchor(Some("top"), 6.),
+    ];
+    let positions = |glyph: &Glyph| glyph.anchors.iter().map(|a| a.x).collect::<Vec<_>>();
+
+    let mut first = glyph.clone();
+    assert_eq!(first.dedup_anchors(DuplicateAnchors::KeepFirst).unwrap(), ["top"]);
+    assert_eq!(positions(&first), [1., 2., 3., 5.]);
+    let mut last = glyph.clone();
+    assert_eq!(last.dedup_anchors(DuplicateAnchors::KeepLast).unwrap(), ["top"]);
+    assert_eq!(positions(&last), [2., 3., 5., 6.]);
+    let mut error = glyph.clone();
+    assert_eq!(
+        error.dedup_anchors(DuplicateAnchors::Error),
+        Err(DuplicateAnchorsError { names: vec![Name::new_raw("top")] })
+    );
+    assert_eq!(error, glyph);
+
+    assert!(first.dedup_anchors(DuplicateAnchors::Error).unwrap().is_empty());
+}
+
+#[test]
+fn transform_selection() {
+    let mut glyph = Glyph::new("a");
+    glyph.contours.push(Contour::new(
+        vec![
+            point(0., 0., PointType::Curve),
+            point(50., 0., PointType::OffCurve),
+            point(100., 50., PointType::OffCurve),
+            point(100., 100., PointType::Curve),
+            ContourPoint::new(
+                0.,
+                100.,
+                PointType::Line,
+                false,
+                None,
+                Some(Identifier::new("tl").unwrap()),
+            ),
+            point(-20., 60., PointType::OffCurve),
+            point(-20., 20., PointType::OffCurve),
+        ],
+        None,
+    ));
+    let translate = AffineTransform { x_offset: 10., y_offset: 5., ..Default::default() };
+
+    // Moving the first point drags its two handles, including the wrapped-around one.
+    let selection = PointSelection::new().with_index(0, 0);
+    glyph.transform_selection(&selection, translate).unwrap();
+    let coords: Vec<_> = glyph.contours[0].points.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(
+        coords,
+        [(10., 5.), (60., 5.), (100., 50.), (100., 100.), (0., 100.), (-20., 60.), (-10., 25.)]
+    );
+
+    let selection = PointSelection::new().with_identifier(Identifier::new("tl").unwrap());
+    glyph.transform_selection(&selection, translate).unwrap();
+    let coords: Vec<_> = glyph.contours[0].points.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(
+        coords,
+        [(10., 5.), (60., 5.), (100., 50.), (100., 100.), (10., 105.), (-10., 65.), (-10., 25.)]
+    );
+
+    let before = glyph.clone();
+    assert!(matches!(
+        glyph.transform_selection(&PointSelection::new().with_index(1, 0), translate),
+        Err(SelectionError::ContourIndexOutOfRange(1))
+    ));
+    assert!(matches!(
+        glyph.transform_selection(&PointSelection::new().with_index(0, 7), translate),
+        Err(SelectionError::PointIndexOutOfRange { contour: 0, point: 7 })
+    ));
+    let selection =
+        PointSelection::new().with_index(0, 3).with_identifier(Identifier::new("nope").unwrap());
+    assert!(matches!(
+        glyph.transform_selection(&selection, translate),
+        Err(SelectionError::UnknownIdentifier(id)) if id == "nope"
+    ));
+    assert_eq!(glyph, before);
+}
+
+#[test]
+fn move_point_by_identifier() {
+    let mut glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+
+    glyph.move_point(&Identifier::new("spare-id").unwrap(), 10., -10.).unwrap();
+    let point = &glyph.contours[1].points[2];
+    assert_eq!((point.x, point.y), (2010., 3990.));
+
+    let anchor_id = Identifier::new("90b7eb80-e21a-4a79-a8c0-7634c25ddc18").unwrap();
+    glyph.move_point(&anchor_id, 1., 1.).unwrap();
+    assert_eq!((glyph.anchors[1].x, glyph.anchors[1].y), (4., 5.));
+
+    let component_id = Identifier::new("a50e8ccd-2ba4-4279-a011-4c82a8075dd9").unwrap();
+    glyph.move_point(&component_id, 5., 6.).unwrap();
+    glyph.move_point(&component_id, 5., 6.).unwrap();
+    let transform = glyph.components[0].transform;
+    assert_eq!((transform.x_offset, transform.y_offset), (10., 12.));
+    assert_eq!(glyph.components[1].transform, AffineTransform::default());
+
+    assert!(matches!(
+        glyph.move_point(&Identifier::new("nope").unwrap(), 1., 1.),
+        Err(SelectionError::UnknownIdentifier(_))
+    ));
+}
+
+#[test]
+fn composite_and_mixed() {
+    let mut glyph = Glyph::new("a");
+    assert!(!glyph.is_composite());
+    assert!(!glyph.is_mixed());
+
+    glyph.components.push(Component::new(Name::new_raw("b"), AffineTransform::default(), None));
+    assert!(glyph.is_composite());
+    assert!(!glyph.is_mixed());
+
+    glyph.contours.push(Contour::default());
+    assert!(!glyph.is_composite());
+    assert!(glyph.is_mixed());
+
+    glyph.components.clear();
+    assert!(!glyph.is_composite());
+    assert!(!glyph.is_mixed());
+}
+
+#[test]
+fn truetype_lib_keys() {
+    let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="2">
+	<outline>
+		<component base="b" identifier="c1"/>
+	</outline>
+	<lib>
+		<dict>
+			<key>public.objectLibs</key>
+			<dict>
+				<key>c1</key>
+				<dict>
+					<key>public.truetype.useMyMetrics</key>
+					<true/>
+				</dict>
+			</dict>
+			<key>public.truetype.instructions</key>
+			<dict>
+				<key>assembly</key>
+				<string>PUSHB[ ] 0 MDAP[1]</string>
+				<key>com.example.extra</key>
+				<integer>1</integer>
+				<key>formatVersion</key>
+				<string>1</string>
+				<key>id</key>
+				<string>abc</string>
+			</dict>
+			<key>public.truetype.overlap</key>
+			<true/>
+		</dict>
+	</lib>
+</glyph>
+"#;
+    let mut glyph = parse_glyph(data.as_bytes()).unwrap();
+    let instructions = glyph.truetype_instructions().unwrap();
+    assert_eq!(
+        instructions,
+        TrueTypeInstructions {
+            format_version: "1".into(),
+            id: "abc".into(),
+            assembly: "PUSHB[ ] 0 MDAP[1]".into(),
+        }
+    );
+    assert_eq!(glyph.truetype_overlap(), Some(true));
+    assert_eq!(glyph.components[0].truetype_use_my_metrics(), Some(true));
+    assert_eq!(glyph.components[0].truetype_round_offset_to_grid(), None);
+    assert_eq!(std::str::from_utf8(&glyph.encode_xml().unwrap()).unwrap(), data);
+
+    glyph.set_truetype_instructions(Some(TrueTypeInstructions {
+        assembly: String::new(),
+        ..instructions
+    }));
+    glyph.set_truetype_overlap(None);
+    let reparsed = parse_glyph(&glyph.encode_xml().unwrap()).unwrap();
+    assert_eq!(reparsed.truetype_instructions().unwrap().assembly, "");
+    assert!(reparsed.lib["public.truetype.instructions"]
+        .as_dictionary()
+        .unwrap()
+        .contains_key("com.example.extra"));
+    assert_eq!(reparsed.truetype_overlap(), None);
+}
+
+#[test]
+#[cfg(feature = "object-libs")]
+fn set_component_truetype_flags() {
+    let mut component = Component::new(Name::new_raw("b"), AffineTransform::default(), None);
+    component.set_truetype_round_offset_to_grid(Some(true));
+    assert!(component.identifier().is_some());
+    component.set_truetype_use_my_metrics(Some(false));
+    assert_eq!(component.truetype_round_offset_to_grid(), Some(true));
+    assert_eq!(component.truetype_use_my_metrics(), Some(false));
+    component.set_truetype_round_offset_to_grid(None);
+    assert_eq!(component.truetype_round_offset_to_grid(), None);
+}
+
+#[test]
+fn flatten_components_once() {
+    use crate::error::ComponentError;
+    use crate::{Layer, SequentialIdentifiers};
+
+    let translate =
+        |x_offset, y_offset| AffineTransform { x_offset, y_offset, ..Default::default() };
+    let scale = |factor| AffineTransform { x_scale: factor, y_scale: factor, ..Default::default() };
+
+    let mut layer = Layer::default();
+    let mut dot = Glyph::new("dot");
+    dot.contours.push(Contour::new(
+        vec![
+            point(0., 0., PointType::Line),
+            point(10., 0., PointType::Line),
+            point(10., 10., PointType::Line),
+        ],
+        Some(Identifier::new_raw("dot-contour")),
+    ));
+    layer.insert_glyph(dot);
+    let mut colon = Glyph::new("colon");
+    colon.contours.push(contour(&[(0., 0., PointType::Line), (5., 5., PointType::Line)]));
+    colon.components.push(Component::new(
+        Name::new_raw("dot"),
+        translate(0., 100.),
+        Some(Identifier::new_raw("colon-dot")),
+    ));
+    layer.insert_glyph(colon);
+
+    let mut glyph = Glyph::new("big-colon");
+    glyph.components.push(Component::new(Name::new_raw("colon"), scale(2.), None));
+    let mut ids = SequentialIdentifiers::new("new-").unwrap();
+    glyph.flatten_components_once_with(&layer, &mut ids).unwrap();
+
+    assert_eq!(glyph.contours.len(), 1);
+    let coords: Vec<_> = glyph.contours[0].points.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(coords, [(0., 0.), (10., 10.)]);
+    assert_eq!(glyph.components.len(), 1);
+    assert_eq!(glyph.components[0].base.as_str(), "dot");
+    assert_eq!(
+        glyph.components[0].transform,
+        AffineTransform { x_scale: 2., y_scale: 2., y_offset: 200., ..Default::default() }
+    );
+    assert_eq!(glyph.components[0].identifier().unwrap().as_str(), "new-0");
+
+    // One more level resolves everything.
+    glyph.flatten_components_once_with(&layer, &mut ids).unwrap();
+    assert!(glyph.components.is_empty());
+    assert_eq!(glyph.contours.len(), 2);
+    let coords: Vec<_> = glyph.contours[1].points.iter().map(|p| (p.x, p.y)).collect();
+    assert_eq!(coords, [(0., 200.), (20., 200.), (20., 220.)]);
+    assert_eq!(glyph.contours[1].identifier().unwrap().as_str(), "new-1");
+
+    let mut missing = Glyph::new("missing");
+    missing.components.push(Component::new(Name::new_raw("nope"), Default::default(), None));
+    assert!(matches!(
+        missing.flatten_components_once_with(&layer, &mut ids),
+        Err(ComponentError::MissingBase(name)) if name == "nope"
+    ));
+
+    let mut a = Glyph::new("a");
+    a.components.push(Component::new(Name::new_raw("b"), Default::default(), None));
+    let mut b = Glyph::new("b");
+    b.components.push(Component::new(Name::new_raw("a"), Default::default(), None));
+    layer.insert_glyph(a.clone());
+    layer.insert_glyph(b);
+    let before = a.clone();
+    assert!(matches!(
+        a.flatten_components_once_with(&layer, &mut ids),
+        Err(ComponentError::Cycle(_))
+    ));
+    assert_eq!(a, before);
+}
+
+#[test]
+fn find_by_identifier() {
+    let mut glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+    let id = |s| Identifier::new_raw(s);
+
+    assert!(matches!(
+        glyph.find_by_identifier(&id("90b7eb80-e21a-4a79-a8c0-7634c25ddc18")),
+        Some(ObjectRef::Anchor(anchor)) if anchor.name.as_deref() == Some("bottom")
+    ));
+    assert!(matches!(
+        glyph.find_by_identifier(&id("c76955c2-e9f2-4adf-8b51-1ae03da11dca")),
+        Some(ObjectRef::Guideline(_))
+    ));
+    assert!(matches!(
+        glyph.find_by_identifier(&id("9bf0591d-6281-4c76-8c13-9ff3d93eec4f")),
+        Some(ObjectRef::Contour(contour)) if contour.points.len() == 3
+    ));
+    assert!(matches!(
+        glyph.find_by_identifier(&id("spare-id")),
+        Some(ObjectRef::Point(point)) if point.x == 2000.
+    ));
+    assert!(matches!(
+        glyph.find_by_identifier(&id("a50e8ccd-2ba4-4279-a011-4c82a8075dd9")),
+        Some(ObjectRef::Component(_))
+    ));
+    assert!(glyph.find_by_identifier(&id("nope")).is_none());
+
+    if let Some(ObjectRefMut::Point(point)) = glyph.find_by_identifier_mut(&id("spare-id")) {
+        point.name = Some(Name::new_raw("found"));
+    }
+    assert_eq!(glyph.contours[1].points[2].name.as_deref(), Some("found"));
+}
+
+#[test]
+fn enumerate_points() {
+    let mut glyph = Glyph::new("a");
+    glyph.contours.push(contour(&[(0., 0., PointType::Line), (10., 0., PointType::Line)]));
+    glyph.contours.push(Contour::new(vec![], None));
+    glyph.contours.push(contour(&[(5., 5., PointType::Line)]));
+
+    let addresses: Vec<_> = glyph.enumerate_points().map(|(idx, p)| (idx, p.x)).collect();
+    assert_eq!(addresses, [((0, 0), 0.), ((0, 1), 10.), ((2, 0), 5.)]);
+
+    for ((contour_idx, point_idx), point) in glyph.enumerate_points_mut() {
+        point.y = (contour_idx * 10 + point_idx) as f64;
+    }
+    assert_eq!(glyph.contours[0].points[1].y, 1.);
+    assert_eq!(glyph.contours[2].points[0].y, 20.);
+    assert_eq!(Glyph::new("b").enumerate_points().count(), 0);
+}
+
+#[test]
+fn points_named() {
+    let point = |name: Option<&str>| {
+        ContourPoint::new(0., 0., PointType::Line, false, name.map(Name::new_raw), None)
+    };
+    let mut glyph = Glyph::new("a");
+    glyph.contours.push(Contour::new(vec![point(None), point(Some("corner"))], None));
+    glyph.contours.push(Contour::new(vec![point(Some("tip")), point(Some("corner"))], None));
+
+    let corners: Vec<_> = glyph.points_named("corner").map(|(address, _)| address).collect();
+    assert_eq!(corners, [(0, 1), (1, 1)]);
+    let (address, point) = glyph.point_named("tip").unwrap();
+    assert_eq!(address, (1, 0));
+    assert!(std::ptr::eq(point, &glyph.contours[1].points[0]));
+    assert!(glyph.point_named("missing").is_none());
+}
+
+#[test]
+fn is_compatible() {
+    use crate::error::IncompatibilityReason;
+
+    let glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+    let mut other = glyph.clone();
+    other.contours[0].points[0].x += 10.;
+    other.anchors.clear();
+    assert_eq!(glyph.is_compatible(&other), Ok(()));
+
+    let mut other = glyph.clone();
+    other.contours.pop();
+    assert!(matches!(
+        glyph.is_compatible(&other),
+        Err(IncompatibilityReason::ContourCount { expected: 2, found: 1 })
+    ));
+
+    let mut other = glyph.clone();
+    other.contours[1].points.pop();
+    assert!(matches!(
+        glyph.is_compatible(&other),
+        Err(IncompatibilityReason::PointCount { contour: 1, .. })
+    ));
+
+    let mut other = glyph.clone();
+    other.contours[0].points[1].typ = PointType::OffCurve;
+    assert_eq!(
+        glyph.is_compatible(&other),
+        Err(IncompatibilityReason::PointType {
+            contour: 0,
+            point: 1,
+            expected: glyph.contours[0].points[1].typ.clone(),
+            found: PointType::OffCurve,
+        })
+    );
+
+    let mut other = glyph.clone();
+    other.components[0].base = Name::new_raw("b");
+    assert!(matches!(
+        glyph.is_compatible(&other),
+        Err(IncompatibilityReason::ComponentBase { component: 0, .. })
+    ));
+}
+
+#[test]
+fn node_signature() {
+    let contour = contour(&[
+        (0., 0., PointType::Move),
+        (10., 0., PointType::OffCurve),
+        (20., 0., PointType::OffCurve),
+        (30., 0., PointType::Curve),
+    ]);
+    assert_eq!(
+        contour.node_signature(),
+        [PointType::Move, PointType::OffCurve, PointType::OffCurve, PointType::Curve]
+    );
+
+    let mut other = contour.clone();
+    other.points.iter_mut().for_each(|point| point.y += 5.);
+    assert_eq!(other.node_signature(), contour.node_signature());
+    other.points[1].typ = PointType::Line;
+    assert_ne!(other.node_signature(), contour.node_signature());
+    assert!(Contour::default().node_signature().is_empty());
+}
+
+#[test]
+#[allow(clippy::float_cmp)]
+fn interpolate() {
+    use crate::error::IncompatibilityReason;
+
+    let a = parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+    let mut b = a.clone();
+    b.width = 100.;
+    b.contours[0].points[1].x = 300.;
+    b.anchors[0].y = 12.;
+    b.components[0].transform.x_offset = 50.;
+    b.components[0].transform.x_scale = 2.;
+    b.guidelines[0].line = Line::Vertical(500.);
+
+    let mid = Glyph::interpolate(&a, &b, 0.5).unwrap();
+    assert_eq!(mid.name, a.name);
+    assert_eq!(mid.width, 50.);
+    assert_eq!(mid.contours[0].points[1].x, 200.);
+    assert_eq!(mid.contours[0].points[1].y, 200.);
+    assert_eq!(mid.anchors[0].y, 7.);
+    assert_eq!(mid.components[0].transform.x_offset, 25.);
+    assert_eq!(mid.components[0].transform.x_scale, 1.5);
+    assert_eq!(mid.guidelines[0].line, Line::Vertical(400.));
+    assert_eq!(Glyph::interpolate(&a, &b, 0.).unwrap(), a);
+
+    b.guidelines[0].line = Line::Horizontal(500.);
+    assert_eq!(Glyph::interpolate(&a, &b, 0.5), Err(IncompatibilityReason::GuidelineKind(0)));
+    b.anchors.pop();
+    assert!(matches!(
+        Glyph::interpolate(&a, &b, 0.5),
+        Err(IncompatibilityReason::AnchorCount { expected: 2, found: 1 })
+    ));
+    b.contours.clear();
+    assert!(matches!(
+        Glyph::interpolate(&a, &b, 0.5),
+        Err(IncompatibilityReason::ContourCount { .. })
+    ));
+}
+
+#[test]
+fn mark_color() {
+    let mut glyph = Glyph::new("a");
+    assert_eq!(glyph.mark_color(), None);
+    glyph.set_mark_color(Some(Color::new(1., 0., 0.5, 1.).unwrap()));
+    assert_eq!(glyph.lib.get("public.markColor").unwrap().as_string(), Some("1,0,0.5,1"));
+    assert_eq!(glyph.mark_color(), Some(Color::new(1., 0., 0.5, 1.).unwrap()));
+    glyph.lib.insert("public.markColor".into(), "red".into());
+    assert_eq!(glyph.mark_color(), None);
+    glyph.set_mark_color(None);
+    assert!(glyph.lib.is_empty());
+}
+
+#[test]
+fn canonical_bytes() {
+    let glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+    let bytes = glyph.canonical_bytes().unwrap();
+    let text = std::str::from_utf8(&bytes).unwrap();
+    assert!(!text.contains("spare-id"));
+    assert!(text.contains("com.test.anchorTool"));
+
+    let mut other = glyph.clone();
+    other.anchors.reverse();
+    other.contours[1].points[2].replace_identifier(Identifier::new_raw("other-id"));
+    other.contours[0].points[0].y = -0.0;
+    let mut lib = other.anchors[0].take_lib().unwrap();
+    lib.insert("com.test.a".into(), true.into());
+    other.anchors[0].replace_lib_with(lib, &mut || Identifier::new_raw("new-id"));
+    assert_ne!(other.canonical_bytes().unwrap(), bytes);
+    other.anchors[0].lib_mut().unwrap().remove("com.test.a");
+    assert_eq!(other.canonical_bytes().unwrap(), bytes);
+
+    other.contours[0].points[0].y = 1.0;
+    assert_ne!(other.canonical_bytes().unwrap(), bytes);
+}
+
+#[test]
+fn codepoints_from_str() {
+    let mut glyph = Glyph::new("A");
+    assert_eq!(glyph.unicode_value(), None);
+    glyph.set_codepoints_from_str("AaA");
+    assert_eq!(glyph.codepoints, Codepoints::new(['A', 'a']));
+    assert_eq!(glyph.unicode_value(), Some(0x41));
+    glyph.set_codepoints_from_str("");
+    assert!(glyph.codepoints.is_empty());
+}
+
+#[test]
+fn outline_visitor() {
+    #[derive(Default)]
+    struct Counter {
+        events: Vec<String>,
+    }
+
+    impl OutlineVisitor for Counter {
+        fn anchor(&mut self, anchor: &Anchor) {
+            self.events.push(format!("anchor {}", anchor.name.as_deref().unwrap_or("")));
+        }
+        fn begin_contour(&mut self, contour: &Contour) {
+            self.events.push(format!("begin {}", contour.points.len()));
+        }
+        fn end_contour(&mut self, _contour: &Contour) {
+            self.events.push("end".into());
+        }
+        fn component(&mut self, component: &Component) {
+            self.events.push(format!("component {}", component.base));
+        }
+    }
+
+    let glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+    let mut counter = Counter::default();
+    glyph.accept(&mut counter);
+    assert_eq!(
+        counter.events,
+        [
+            "anchor top",
+            "anchor bottom",
+            "begin 3",
+            "end",
+            "begin 3",
+            "end",
+            "component component",
+            "component component"
+        ]
+    );
+
+    let mut bounds = ControlBounds::default();
+    glyph.accept(&mut bounds);
+    assert_eq!(bounds.bounds(), Some((0., 0., 2000., 4000.)));
+    let mut bounds = ControlBounds::default();
+    Glyph::new("empty").accept(&mut bounds);
+    assert_eq!(bounds.bounds(), None);
+}
+
+#[test]
+fn summary() {
+    let mut glyph =
+        parse_glyph(include_bytes!("../../testdata/identifiers.ufo/glyphs/test.glif")).unwrap();
+    glyph.codepoints = Codepoints::new(['a', '\u{1F600}']);
+    assert_eq!(
+        glyph.summary(),
+        "test: 2 contours, 6 points, 2 components, advance 0, U+0061 U+1F600, anchors: top, bottom"
+    );
+    glyph.contours.truncate(1);
+    glyph.anchors[0].name = None;
+    assert_eq!(
+        glyph.to_string(),
+        "test: 1 contour, 3 points, 2 components, advance 0, U+0061 U+1F600, anchors: <unnamed>, bottom"
+    );
+}
+
+#[test]
+fn bounds_and_side_bearings() {
+    use crate::Layer;
+
+    let mut layer = Layer::default();
+    let mut arch = Glyph::new("arch");
+    arch.width = 120.;
+    arch.contours.push(contour(&[
+        (0., 0., PointType::Line),
+        (0., 100., PointType::OffCurve),
+        (100., 100., PointType::OffCurve),
+        (100., 0., PointType::Curve),
+    ]));
+    assert_eq!(arch.contours[0].bounds(), Some((0., 0., 100., 75.)));
+    assert_eq!(arch.bounds(&layer), Some((0., 0., 100., 75.)));
+    assert_eq!(arch.left_side_bearing(&layer), Some(0.));
+    assert_eq!(arch.right_side_bearing(&layer), Some(20.));
+    layer.insert_glyph(arch);
+
+    // Quadratic contours, including one with only implied on-curve points.
+    let quad = contour(&[
+        (0., 0., PointType::Line),
+        (50., 100., PointType::OffCurve),
+        (100., 0., PointType::QCurve),
+    ]);
+    assert_eq!(quad.bounds(), Some((0., 0., 100., 50.)));
+    let implied = Contour::new(
+        [(0., 50.), (50., 100.), (100., 50.), (50., 0.)]
+            .into_iter()
+            .map(|(x, y)| point(x, y, PointType::OffCurve))
+            .collect(),
+        None,
+    );
+    assert_eq!(implied.bounds(), Some((12.5, 12.5, 87.5, 87.5)));
+
+    // Components are resolved with their full transform.
+    let mut composite = Glyph::new("composite");
+    composite.width = 100.;
+    let rotate = AffineTransform {
+        x_scale: 0.,
+        xy_scale: 1.,
+        yx_scale: -1.,
+        y_scale: 0.,
+        x_offset: 80.,
+        y_offset: 0.,
+    };
+    composite.components.push(Component::new(Name::new_raw("arch"), rotate, None));
+    composite.components.push(Component::new(Name::new_raw("missing"), rotate, None));
+    assert_eq!(composite.bounds(&layer), Some((5., 0., 80., 100.)));
+    assert_eq!(composite.left_side_bearing(&layer), Some(5.));
+    assert_eq!(composite.right_side_bearing(&layer), Some(20.));
+
+    // Cycles are cut off rather than followed.
+    let mut cyclic = Glyph::new("cyclic");
+    cyclic.components.push(Component::new(Name::new_raw("cyclic"), Default::default(), None));
+    layer.insert_glyph(cyclic.clone());
+    assert_eq!(cyclic.bounds(&layer), None);
+
+    let empty = Glyph::new("space");
+    assert_eq!(empty.bounds(&layer), None);
+    assert_eq!(empty.left_side_bearing(&layer), None);
+    assert_eq!(empty.right_side_bearing(&layer), None);
+}
+
+#[test]
+fn bounds_with_resolver() {
+    use crate::{ComponentResolver, Layer};
+
+    let component = |base, x_scale, xy_scale, yx_scale, y_scale, x_offset, y_offset| {
+        let transform =
+            AffineTransform { x_scale, xy_scale, yx_scale, y_scale, x_offset, y_offset };
+        Component::new(Name::new_raw(base), transform, None)
+    };
+    let mut layer = Layer::default();
+    let mut arch = Glyph::new("arch");
+    arch.contours.push(contour(&[
+        (0., 0., PointType::Line),
+        (0., 100., PointType::OffCurve),
+        (100., 100., PointType::OffCurve),
+        (100., 0., PointType::Curve),
+    ]));
+    layer.insert_glyph(arch);
+    let mut pair = Glyph::new("pair");
+    pair.components.push(component("arch", 1., 0., 0., 1., 0., 0.));
+    pair.components.push(component("arch", -2., 0., 0., -1., 300., 200.));
+    layer.insert_glyph(pair);
+    let mut rotated = Glyph::new("rotated");
+    rotated.components.push(component("pair", 0., 1., -1., 0., 80., 0.));
+    layer.insert_glyph(rotated);
+    let mut nested = Glyph::new("nested");
+    nested.components.push(component("pair", 2., 0., 0., 0.5, 10., 10.));
+    nested.components.push(component("rotated", 1., 0., 0., 1., 0., 500.));
+    nested.components.push(component("missing", 1., 0., 0., 1., 0., 0.));
+    layer.insert_glyph(nested);
+    let mut cyclic = Glyph::new("cyclic");
+    cyclic.components.push(component("cyclic", 1., 0., 0., 1., 0., 0.));
+    layer.insert_glyph(cyclic);
+
+    let mut resolver = ComponentResolver::new(&layer);
+    assert_eq!(
+        layer.get_glyph("pair").unwrap().bounds_with(&mut resolver),
+        Some((0., 0., 300., 200.))
+    );
+    for glyph in layer.iter() {
+        assert_eq!(glyph.bounds_with(&mut resolver), glyph.bounds(&layer), "{}", glyph.name());
+    }
+    assert_eq!(Glyph::new("space").bounds_with(&mut resolver), None);
+}
+
+#[test]
+fn set_side_bearings() {
+    use crate::{Guideline, Layer};
+
+    let mut layer = Layer::default();
+    let mut bar = Glyph::new("bar");
+    bar.width = 100.;
+    bar.contours.push(contour(&[
+        (20., 0., PointType::Line),
+        (70., 0., PointType::Line),
+        (70., 50., PointType::Line),
+    ]));
+    bar.anchors.push(Anchor::new(45., 50., Some(Name::new_raw("top")), None, None));
+    bar.guidelines.push(Guideline::new(Line::Vertical(45.), None, None, None));
+    bar.guidelines.push(Guideline::new(Line::Horizontal(10.), None, None, None));
+    layer.insert_glyph(bar.clone());
+
+    bar.set_left_side_bearing(40., &layer);
+    assert_eq!(bar.width, 120.);
+    assert_eq!(bar.left_side_bearing(&layer), Some(40.));
+    assert_eq!(bar.right_side_bearing(&layer), Some(30.));
+    assert_eq!(bar.anchors[0].x, 65.);
+    assert_eq!(bar.guidelines[0].line, Line::Vertical(65.));
+    assert_eq!(bar.guidelines[1].line, Line::Horizontal(10.));
+
+    bar.set_right_side_bearing(10., &layer);
+    assert_eq!(bar.width, 100.);
+    assert_eq!(bar.left_side_bearing(&layer), Some(40.));
+    assert_eq!(bar.right_side_bearing(&layer), Some(10.));
+
+    // Composites move their components, not the base glyph.
+    let mut composite = Glyph::new("composite");
+    composite.width = 100.;
+    composite.components.push(Component::new(Name::new_raw("bar"), Default::default(), None));
+    composite.set_left_side_bearing(0., &layer);
+    assert_eq!(composite.components[0].transform.x_offset, -20.);
+    assert_eq!(composite.width, 80.);
+    assert_eq!(composite.left_side_bearing(&layer), Some(0.));
+    assert_eq!(layer.get_glyph("bar").unwrap().contours[0].points[0].x, 20.);
+
+    let mut empty = Glyph::new("space");
+    empty.width = 250.;
+    empty.set_left_side_bearing(10., &layer);
+    empty.set_right_side_bearing(10., &layer);
+    assert_eq!(empty.width, 250.);
+}
+
+#[test]
+fn set_metrics() {
+    use crate::Layer;
+
+    let layer = Layer::default();
+    let mut bar = Glyph::new("bar");
+    bar.width = 100.;
+    bar.contours.push(contour(&[
+        (20., 0., PointType::Line),
+        (70., 0., PointType::Line),
+        (70., 50., PointType::Line),
+    ]));
+    bar.anchors.push(Anchor::new(45., 50., None, None, None));
+    let metrics = |glyph: &Glyph| {
+        (glyph.left_side_bearing(&layer), glyph.right_side_bearing(&layer), glyph.width)
+    };
+
+    let cases = [
+        ((Some(10.), Some(15.), None), (10., 15., 75.)),
+        ((Some(10.), None, Some(90.)), (10., 30., 90.)),
+        ((None, Some(15.), Some(90.)), (25., 15., 90.)),
+        // Conflicting values: the right side bearing gives way.
+        ((Some(10.), Some(99.), Some(90.)), (10., 30., 90.)),
+        ((Some(10.), None, None), (10., 30., 90.)),
+        ((None, Some(15.), None), (20., 15., 85.)),
+        ((None, None, Some(90.)), (20., 20., 90.)),
+        ((None, None, None), (20., 30., 100.)),
+    ];
+    for ((lsb, rsb, width), (expected_lsb, expected_rsb, expected_width)) in cases {
+        let mut glyph = bar.clone();
+        glyph.set_metrics(lsb, rsb, width, &layer);
+        let expected = (Some(expected_lsb), Some(expected_rsb), expected_width);
+        assert_eq!(metrics(&glyph), expected, "{lsb:?} {rsb:?} {width:?}");
+        assert_eq!(glyph.anchors[0].x - glyph.contours[0].points[0].x, 25.);
+    }
+
+    let mut empty = Glyph::new("space");
+    empty.set_metrics(Some(10.), Some(10.), None, &layer);
+    assert_eq!(empty.width, 0.);
+    empty.set_metrics(Some(10.), Some(10.), Some(250.), &layer);
+    assert_eq!(empty.width, 250.);
+}
+
+#[test]
+fn ensure_advance() {
+    let cases: [(&str, &[char], bool, bool); 6] = [
+        ("space", &[' '], false, true),
+        ("a", &[], true, true),
+        (".null", &[], false, false),
+        ("acutecomb", &['\u{0301}'], true, false),
+        ("uni200D", &['\u{200D}'], false, false),
+        ("uni034F", &['\u{034F}'], false, false),
+    ];
+    for (name, codepoints, outline, expected) in cases {
+        let mut glyph = Glyph::new(name);
+        glyph.codepoints = Codepoints::new(codepoints.iter().copied());
+        if outline {
+            glyph.contours.push(contour(&[(0., 0., PointType::Line)]));
+        }
+        assert_eq!(glyph.ensure_advance(500.), expected, "{name}");
+        assert_eq!(glyph.width, if expected { 500. } else { 0. }, "{name}");
+    }
+}
+
+#[test]
+fn vertical_side_bearings() {
+    use crate::Layer;
+
+    let layer = Layer::default();
+    let mut glyph = Glyph::new("uni4E00");
+    glyph.height = 1000.;
+    glyph.contours.push(contour(&[
+        (0., 300., PointType::Line),
+        (100., 300., PointType::Line),
+        (100., 400., PointType::Line),
+    ]));
+    assert_eq!(glyph.vertical_origin(), None);
+    assert_eq!(glyph.top_side_bearing(&layer), None);
+    assert_eq!(glyph.bottom_side_bearing(&layer), None);
+
+    glyph.set_vertical_origin(Some(880.));
+    assert_eq!(glyph.vertical_origin(), Some(880.));
+    assert_eq!(glyph.top_side_bearing(&layer), Some(480.));
+    assert_eq!(glyph.bottom_side_bearing(&layer), Some(420.));
+
+    // Integer values, as written by most tools, are accepted too.
+    glyph.lib.insert("public.verticalOrigin".into(), plist::Value::Integer(900.into()));
+    assert_eq!(glyph.vertical_origin(), Some(900.));
+
+    glyph.set_vertical_origin(None);
+    assert!(glyph.lib.is_empty());
+
+    let mut empty = Glyph::new("space");
+    empty.set_vertical_origin(Some(880.));
+    assert_eq!(empty.top_side_bearing(&layer), None);
+    assert_eq!(empty.bottom_side_bearing(&layer), None);
+}
+
+#[test]
+fn full_color_precision() {
+    let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="2">
+	<image fileName="sketch.png" color="0.1234,1,0,0.5678"/>
+	<anchor x="0" y="0" color="0.0626,0,1,1"/>
+</glyph>
+"#;
+    let glyph = parse_glyph(data.as_bytes()).unwrap();
+
+    let quantized = glyph.encode_xml().unwrap();
+    let quantized = std::str::from_utf8(&quantized).unwrap();
+    assert!(quantized.contains(r#"color="0.123,1,0,0.568""#));
+    assert!(quantized.contains(r#"color="0.063,0,1,1""#));
+
+    let options = WriteOptions::default().full_color_precision(true);
+    let full = glyph.encode_xml_with_options(&options).unwrap();
+    pretty_assertions::assert_eq!(std::str::from_utf8(&full).unwrap(), data);
+}
+
+#[test]
+fn on_curve_neighbors() {
+    let closed = contour(&[
+        (0., 0., PointType::OffCurve),
+        (0., 0., PointType::Curve),
+        (0., 0., PointType::Line),
+        (0., 0., PointType::OffCurve),
+        (0., 0., PointType::OffCurve),
+    ]);
+    // The first point is a handle of the segment wrapping around the end.
+    assert_eq!(closed.on_curve_neighbors(0), (Some(2), Some(1)));
+    assert_eq!(closed.on_curve_neighbors(1), (Some(2), Some(2)));
+    assert_eq!(closed.on_curve_neighbors(2), (Some(1), Some(1)));
+    assert_eq!(closed.on_curve_neighbors(3), (Some(2), Some(1)));
+    assert_eq!(closed.on_curve_neighbors(4), (Some(2), Some(1)));
+    assert_eq!(closed.on_curve_neighbors(5), (None, None));
+
+    let open = contour(&[
+        (0., 0., PointType::Move),
+        (0., 0., PointType::OffCurve),
+        (0., 0., PointType::OffCurve),
+        (0., 0., PointType::Curve),
+    ]);
+    assert_eq!(open.on_curve_neighbors(0), (None, Some(3)));
+    assert_eq!(open.on_curve_neighbors(1), (Some(0), Some(3)));
+    assert_eq!(open.on_curve_neighbors(3), (Some(0), None));
+
+    let single = contour(&[(0., 0., PointType::Line), (0., 0., PointType::OffCurve)]);
+    assert_eq!(single.on_curve_neighbors(0), (None, None));
+    assert_eq!(single.on_curve_neighbors(1), (Some(0), Some(0)));
+}
+
+#[test]
+fn snap_anchor_to_outline() {
+    let mut glyph = Glyph::new("o");
+    glyph.contours.push(contour(&[
+        (250., 0., PointType::Curve),
+        (300., 0., PointType::OffCurve),
+        (500., 200., PointType::OffCurve),
+        (500., 250., PointType::Curve),
+        (250., 500., PointType::Line),
+    ]));
+    glyph.anchors.push(Anchor::new(260., 490., Some(Name::new_raw("top")), None, None));
+    glyph.anchors.push(Anchor::new(300., 5., Some(Name::new_raw("bottom")), None, None));
+
+    assert!(glyph.snap_anchor_to_outline("top", 20.));
+    assert_eq!((glyph.anchors[0].x, glyph.anchors[0].y), (250., 500.));
+
+    // The off-curve point at (300, 0) is ignored, and (250, 0) is too far.
+    assert!(!glyph.snap_anchor_to_outline("bottom", 20.));
+    assert_eq!((glyph.anchors[1].x, glyph.anchors[1].y), (300., 5.));
+    assert!(glyph.snap_anchor_to_outline("bottom", 60.));
+    assert_eq!((glyph.anchors[1].x, glyph.anchors[1].y), (250., 0.));
+
+    assert!(!glyph.snap_anchor_to_outline("missing", 1000.));
+}
+
+#[test]
+fn contour_extrema() {
+    // A bowl whose bottom-right extrema lie on curves, not on points.
+    let bowl = contour(&[
+        (0., 100., PointType::Line),
+        (0., -50., PointType::OffCurve),
+        (200., -50., PointType::OffCurve),
+        (200., 100., PointType::Curve),
+        (100., 150., PointType::Line),
+    ]);
+    let extrema = bowl.extrema().unwrap();
+    assert_eq!(extrema, Extrema { left: 0, right: 3, bottom: 0, top: 4 });
+    assert_eq!(bowl.curve_extrema(), [(100., -12.5)]);
+
+    let handles = contour(&[(0., 0., PointType::OffCurve)]);
+    assert_eq!(handles.extrema(), None);
+    assert_eq!(Contour::default().curve_extrema(), []);
+}
+
+#[test]
+#[cfg(feature = "kurbo")]
+fn add_extrema() {
+    let coords = |contour: &Contour| -> Vec<_> {
+        contour.points.iter().map(|p| (p.x, p.y, p.typ.clone())).collect()
+    };
+    let mut bowl = contour(&[
+        (0., 0., PointType::OffCurve),
+        (200., 0., PointType::OffCurve),
+        (200., 100., PointType::Curve),
+        (100., 150., PointType::Line),
+        (0., 100., PointType::Line),
+    ]);
+    let before = bowl.bounds();
+    assert_eq!(bowl.add_extrema(), 1);
+    assert_eq!(
+        coords(&bowl),
+        [
+            (0., 50., PointType::OffCurve),
+            (50., 25., PointType::OffCurve),
+            (100., 25., PointType::Curve),
+            (150., 25., PointType::OffCurve),
+            (200., 50., PointType::OffCurve),
+            (200., 100., PointType::Curve),
+            (100., 150., PointType::Line),
+            (0., 100., PointType::Line),
+        ]
+    );
+    assert!(bowl.points[2].smooth);
+    assert_eq!(bowl.bounds(), before);
+    // Existing extrema are not inserted again.
+    assert_eq!(bowl.add_extrema(), 0);
+
+    let mut quad = contour(&[
+        (0., 0., PointType::Move),
+        (50., 100., PointType::OffCurve),
+        (100., 0., PointType::QCurve),
+    ]);
+    assert_eq!(quad.add_extrema(), 1);
+    assert_eq!(
+        coords(&quad),
+        [
+            (0., 0., PointType::Move),
+            (25., 50., PointType::OffCurve),
+            (50., 50., PointType::QCurve),
+            (75., 50., PointType::OffCurve),
+            (100., 0., PointType::QCurve),
+        ]
+    );
+
+    let mut lines = contour(&[(0., 0., PointType::Line), (10., 10., PointType::Line)]);
+    assert_eq!(lines.add_extrema(), 0);
+}
+
+#[test]
+fn error_codes() {
+    let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="2">
+	<outline>
+		<contour>
+			<point x="0" y="0" type="bogus"/>
+		</contour>
+	</outline>
+</glyph>
+"#;
+    let err = parse_glyph(data.as_bytes()).unwrap_err();
+    assert_eq!(err.code(), "unknown-point-type");
+    assert_eq!(ErrorKind::DuplicateElement("advance").code(), "duplicate-element");
+    assert_eq!(
+        GlifLoadError::PublicObjectLibsMustBeDictionary.code(),
+        "public-object-libs-must-be-dictionary"
+    );
+}
+
+#[test]
+fn glif_error_context() {
+    let data = r#"<?xml version="1.0" encoding="UTF-8"?>
+<glyph name="a" format="2">
+	<outline>
+		<contour>
+			<point x="0" y="0" type="bogus"/>
+			<point x="10" y="0" type="line"/>
+		</contour>
+	</outline>
+</glyph>
+"#;
+    let err = Glyph::from_bytes(data.as_bytes()).unwrap_err();
+    assert!(matches!(err.error(), GlifLoadError::Parse(ErrorKind::UnknownPointType)));
+    assert_eq!(&data[err.position() - 2..err.position()], "/>");
+    pretty_assertions::assert_eq!(
+        err.context(data.as_bytes()),
+        "  3 | \t<outline>\n  4 | \t\t<contour>\n> 5 | \t\t\t<point x=\"0\" y=\"0\" type=\"bogus\"/>\n  6 | \t\t\t<point x=\"10\" y=\"0\" type=\"line\"/>\n  7 | \t\t</contour>\n"
+    );
+
+    // Syntax errors point at the broken markup, also with a byte order mark.
+    let data = b"\xEF\xBB\xBF<glyph name=\"a\" format=\"2\">\n<advance width=\"1\"/>\n<note\n";
+    let err = Glyph::from_bytes(data).unwrap_err();
+    assert!(matches!(err.error(), GlifLoadError::Xml(_)));
+    assert!(err.context(data).starts_with("  1 | "));
+    assert!(err.context(data).contains("> 3 | <note"));
+    assert!(Glyph::from_bytes(b"<glyph name=\"a\" format=\"2\"></glyph>").is_ok());
+}
+
+#[test]
+fn to_svg_path() {
+    use crate::Layer;
+
+    let mut layer = Layer::default();
+    let mut base = Glyph::new("base");
+    // A closed cubic contour starting with the handles of its closing curve.
+    base.contours.push(contour(&[
+        (0., 50., PointType::OffCurve),
+        (50., 0., PointType::OffCurve),
+        (100., 0., PointType::Curve),
+        (100., 100., PointType::Line),
+    ]));
+    // An open quadratic contour with an implied on-curve point.
+    base.contours.push(contour(&[
+        (0., 0., PointType::Move),
+        (10., 20., PointType::OffCurve),
+        (30., 20., PointType::OffCurve),
+        (40., 0., PointType::QCurve),
+    ]));
+    layer.insert_glyph(base);
+
+    let mut glyph = Glyph::new("composite");
+    let offset = AffineTransform { x_offset: 200., ..Default::default() };
+    glyph.components.push(Component::new(Name::new_raw("base"), offset, None));
+    assert_eq!(
+        glyph.to_svg_path(&layer, false),
+        "M300 0 L300 100 C200 50 250 0 300 0 Z M200 0 Q210 20 220 20 Q230 20 240 0"
+    );
+    assert_eq!(
+        glyph.to_svg_path(&layer, true),
+        "M300 0 L300 -100 C200 -50 250 0 300 0 Z M200 0 Q210 -20 220 -20 Q230 -20 240 0"
+    );
+    assert_eq!(Glyph::new("space").to_svg_path(&layer, false), "");
+}
+
+#[test]
+fn json_round_trip() {
+    let mut glyph = Glyph::new("A");
+    glyph.width = 500.0;
+    glyph.codepoints = Codepoints::new(['A']);
+    let lib: Plist = [("com.example.key".to_string(), plist::Value::from(1))].into_iter().collect();
+    let mut smooth = ContourPoint::new(0.0, 0.0, PointType::Line, true, None, None);
+    smooth.identifier = Some(Identifier::new("point").unwrap());
+    smooth.lib = Some(lib.clone());
+    glyph.contours.push(Contour::new(
+        vec![
+            smooth,
+            point(100.0, 0.0, PointType::OffCurve),
+            point(100.0, 100.0, PointType::QCurve),
+        ],
+        None,
+    ));
+    let mut guideline = Guideline::new(Line::Vertical(10.0), None, None, None);
+    guideline.identifier = Some(Identifier::new("guideline").unwrap());
+    guideline.lib = Some(lib.clone());
+    glyph.guidelines.push(guideline);
+    glyph.components.push(Component::new(Name::new_raw("B"), AffineTransform::default(), None));
+    glyph.image = Some(Image::new("image.png".into(), None, AffineTransform::default()).unwrap());
+    glyph.lib = lib;
+
+    let json = serde_json::to_value(&glyph).unwrap();
+    assert_eq!(
+        json,
+        serde_json::json!({
+            "name": "A",
+            "height": 0.0,
+            "width": 500.0,
+            "codepoints": ["A"],
+            "guidelines": [{
+                "x": 10.0,
+                "y": null,
+                "angle": null,
+                "name": null,
+                "color": null,
+                "identifier": "guideline",
+                "lib": { "com.example.key": 1 },
+            }],
+            "components": [{
+                "base": "B",
+                "transform": {
+                    "xScale": 1.0,
+                    "xyScale": 0.0,
+                    "yxScale": 0.0,
+                    "yScale": 1.0,
+                    "xOffset": 0.0,
+                    "yOffset": 0.0,
+                },
+            }],
+            "contours": [{
+                "points": [
+                    {
+                        "x": 0.0,
+                        "y": 0.0,
+                        "type": "line",
+                        "smooth": true,
+                        "identifier": "point",
+                        "lib": { "com.example.key": 1 },
+                    },
+                    { "x": 100.0, "y": 0.0, "type": "offcurve" },
+                    { "x": 100.0, "y": 100.0, "type": "qcurve" },
+                ],
+            }],
+            "image": {
+                "fileName": "image.png",
+                "transform": {
+                    "xScale": 1.0,
+                    "xyScale": 0.0,
+                    "yxScale": 0.0,
+                    "yScale": 1.0,
+                    "xOffset": 0.0,
+                    "yOffset": 0.0,
+                },
+            },
+            "lib": { "com.example.key": 1 },
+        })
+    );
+    assert_eq!(serde_json::from_value::<Glyph>(json).unwrap(), glyph);
+
+    let bad_image = serde_json::json!({
+        "name": "A",
+        "height": 0.0,
+        "width": 0.0,
+        "image": { "fileName": "images/image.png", "transform": AffineTransform::default() },
+    });
+    assert!(serde_json::from_value::<Glyph>(bad_image).is_err());
+}
+
+#[test]
+fn deserialize_rejects_invalid_objects() {
+    // The lib setters assign an identifier; deserialized objects must bring one.
+    let lib = serde_json::json!({ "com.example.key": 1 });
+    let point = serde_json::json!({ "x": 0.0, "y": 0.0, "type": "line" });
+    let anchor = serde_json::json!({ "x": 0.0, "y": 0.0, "lib": lib });
+    let guideline = serde_json::json!({ "x": 0.0, "lib": lib });
+    let contour = serde_json::json!({ "points": [point, point], "lib": lib });
+    for (key, value) in [("anchors", anchor), ("guidelines", guideline), ("contours", contour)] {
+        let json = serde_json::json!({ "name": "A", "height": 0.0, "width": 0.0, key: [value] });
+        let err = serde_json::from_value::<Glyph>(json).unwrap_err();
+        assert!(err.to_string().contains("must have an identifier"), "{key}: {err}");
+    }
+    let point_with_lib = serde_json::json!({ "x": 0.0, "y": 0.0, "type": "line", "lib": lib });
+    assert!(serde_json::from_value::<ContourPoint>(point_with_lib).is_err());
+
+    let off_curve = serde_json::json!({ "x": 0.0, "y": 0.0, "type": "offcurve" });
+    let move_point = serde_json::json!({ "x": 0.0, "y": 0.0, "type": "move" });
+    let trailing = serde_json::json!({ "points": [move_point, point, off_curve] });
+    assert!(serde_json::from_value::<Contour>(trailing).is_err());
+    let late_move = serde_json::json!({ "points": [point, move_point] });
+    assert!(serde_json::from_value::<Contour>(late_move).is_err());
+    let valid = serde_json::json!({ "points": [move_point, point] });
+    assert!(serde_json::from_value::<Contour>(valid).unwrap().is_open());
+}
+
+#[test]
+fn affine_transform_serde() {
+    use serde_test::{assert_tokens, Token};
+
+    let transform = AffineTransform { x_offset: 10.0, y_offset: -5.0, ..Default::default() };
+    assert_tokens(
+        &transform,
+        &[
+            Token::Struct { name: "AffineTransform", len: 6 },
+            Token::Str("xScale"),
+            Token::F64(1.0),
+            Token::Str("xyScale"),
+            Token::F64(0.0),
+            Token::Str("yxScale"),
+            Token::F64(0.0),
+            Token::Str("yScale"),
+            Token::F64(1.0),
+            Token::Str("xOffset"),
+            Token::F64(10.0),
+            Token::Str("yOffset"),
+            Token::F64(-5.0),
+            Token::StructEnd,
+        ],
+    );
+}
+
+#[test]
+fn insert_and_remove_points() {
+    let mut contour = contour(&[
+        (0., 0., PointType::Move),
+        (0., 0., PointType::Line),
+        (0., 0., PointType::Curve),
+    ]);
+
+    contour.insert_point(2, point(0., 0., PointType::OffCurve)).unwrap();
+    contour.insert_point(2, point(0., 0., PointType::OffCurve)).unwrap();
+    assert_eq!(contour.points.len(), 5);
+    let original = contour.clone();
+
+    let illegal = [
+        (1, point(0., 0., PointType::Move), ErrorKind::UnexpectedMove),
+        (2, point(0., 0., PointType::OffCurve), ErrorKind::TooManyOffCurves),
+        (4, point(0., 0., PointType::Line), ErrorKind::UnexpectedPointAfterOffCurve),
+        (5, point(0., 0., PointType::OffCurve), ErrorKind::TrailingOffCurves),
+    ];
+    for (index, point, error) in illegal {
+        assert_eq!(contour.insert_point(index, point).unwrap_err().code(), error.code());
+        assert_eq!(contour, original);
+    }
+    let mut smooth = point(0., 0., PointType::OffCurve);
+    smooth.smooth = true;
+    assert!(matches!(contour.insert_point(3, smooth), Err(ErrorKind::UnexpectedSmooth)));
+
+    // Removing the curve point would leave the open contour ending with off-curves.
+    assert!(matches!(contour.remove_point(4), Err(ErrorKind::TrailingOffCurves)));
+    assert_eq!(contour, original);
+    assert_eq!(contour.remove_point(2).unwrap().typ, PointType::OffCurve);
+    assert_eq!(contour.points.len(), 4);
+    contour.remove_point(0).unwrap();
+    assert!(contour.is_closed());
+}
+
+#[test]
+fn signed_area_and_tiny_contours() {
+    let square = contour(&[
+        (0., 0., PointType::Line),
+        (100., 0., PointType::Line),
+        (100., 100., PointType::Line),
+        (0., 100., PointType::Line),
+    ]);
+    assert_eq!(square.signed_area(), 10000.);
+    let mut reversed = square.clone();
+    reversed.points.reverse();
+    assert_eq!(reversed.signed_area(), -10000.);
+
+    let quad = contour(&[
+        (0., 0., PointType::QCurve),
+        (100., 0., PointType::Line),
+        (50., 100., PointType::OffCurve),
+    ]);
+    assert!((quad.signed_area() - 10000. / 3.).abs() < 1e-9);
+    let cubic = contour(&[
+        (0., 0., PointType::Curve),
+        (100., 0., PointType::Line),
+        (100., 75., PointType::OffCurve),
+        (0., 75., PointType::OffCurve),
+    ]);
+    assert!((cubic.signed_area() - 4500.).abs() < 1e-9);
+
+    // Open contours are closed by a straight line.
+    let open = contour(&[
+        (0., 0., PointType::Move),
+        (100., 0., PointType::Line),
+        (100., 100., PointType::Line),
+    ]);
+    assert_eq!(open.signed_area(), 5000.);
+
+    let mut glyph = Glyph::new("a");
+    glyph.contours = vec![
+        square,
+        contour(&[(10., 10., PointType::Move)]),
+        contour(&[
+            (0., 0., PointType::Line),
+            (1., 0., PointType::Line),
+            (0., -1., PointType::Line),
+        ]),
+        reversed,
+    ];
+    assert_eq!(glyph.tiny_contours(1.), [1, 2]);
+}
+
+#[test]
+fn wrongly_wound_contours() {
+    use crate::Layer;
+
+    // A counter-clockwise rectangle, or a clockwise one if `clockwise` is set.
+    let rect = |x0: f64, y0: f64, x1: f64, y1: f64, clockwise: bool| {
+        let mut corners = vec![(x0, y0), (x1, y0), (x1, y1), (x0, y1)];
+        if clockwise {
+            corners.reverse();
+        }
+        let points = corners.into_iter().map(|(x, y)| point(x, y, PointType::Line)).collect();
+        Contour::new(points, None)
+    };
+    let layer = Layer::default();
+
+    let mut o = Glyph::new("O");
+    o.contours = vec![rect(0., 0., 100., 100., false), rect(20., 20., 80., 80., true)];
+    assert!(o.has_consistent_directions(&layer));
+    o.contours[1] = rect(20., 20., 80., 80., false);
+    assert_eq!(o.wrongly_wound_contours(&layer), [1]);
+
+    // An island in the counter, and overlapping outer contours.
+    o.contours = vec![
+        rect(0., 0., 100., 100., false),
+        rect(20., 20., 80., 80., true),
+        rect(40., 40., 60., 60., true),
+        rect(90., 90., 150., 150., false),
+        rect(200., 0., 300., 100., true),
+    ];
+    assert_eq!(o.wrongly_wound_contours(&layer), [2, 4]);
+
+    // The contours of components count for nesting.
+    let mut layer = Layer::default();
+    let mut base = Glyph::new("square");
+    base.contours.push(rect(0., 0., 100., 100., false));
+    layer.insert_glyph(base);
+    let mut glyph = Glyph::new("framed");
+    glyph.components.push(Component::new(
+        Name::new_raw("square"),
+        AffineTransform::default(),
+        None,
+    ));
+    glyph.contours.push(rect(20., 20., 80., 80., true));
+    assert!(glyph.has_consistent_directions(&layer));
+    assert!(!glyph.has_consistent_directions(&Layer::default()));
+}
+
+#[test]
+fn image_bounding_box() {
+    let rotate = AffineTransform {
+        x_scale: 0.,
+        xy_scale: 1.,
+        yx_scale: -1.,
+        y_scale: 0.,
+        x_offset: 0.,
+        y_offset: 0.,
+    };
+    let image = Image::new("a.png".into(), None, rotate).unwrap();
+    assert_eq!(image.corners(40., 20.), [(0., 0.), (0., 40.), (-20., 40.), (-20., 0.)]);
+    assert_eq!(image.bounding_box(40., 20.), (-20., 0., 0., 40.));
+}
+
+#[test]
+fn self_intersections() {
+    let line = |x, y| point(x, y, PointType::Line);
+    let square =
+        Contour::new(vec![line(0., 0.), line(100., 0.), line(100., 100.), line(0., 100.)], None);
+    assert!(!square.has_self_intersections(1.));
+
+    // A bow tie crosses itself once, in the middle.
+    let bow_tie =
+        Contour::new(vec![line(0., 0.), line(100., 100.), line(100., 0.), line(0., 100.)], None);
+    assert_eq!(bow_tie.self_intersections(1.), [(50., 50.)]);
+
+    // Open contours have no closing edge.
+    let mut open = square.clone();
+    open.points[0].typ = PointType::Move;
+    assert!(!open.has_self_intersections(1.));
+    open.points.push(line(100., -100.));
+    assert_eq!(open.self_intersections(1.), [(50., 0.)]);
+
+    // Touching at a point is not crossing.
+    let touching = Contour::new(
+        vec![
+            line(0., 0.),
+            line(50., 50.),
+            line(100., 0.),
+            line(100., 100.),
+            line(50., 50.),
+            line(0., 100.),
+        ],
+        None,
+    );
+    assert!(!touching.has_self_intersections(1.));
+
+    // Crossing through a point of the contour is crossing.
+    let through_center = contour(&[
+        (0., 0., PointType::Line),
+        (50., 50., PointType::Line),
+        (100., 100., PointType::Line),
+        (100., 0., PointType::Line),
+        (50., 50., PointType::Line),
+        (0., 100., PointType::Line),
+    ]);
+    assert_eq!(through_center.self_intersections(1.), [(50., 50.)]);
+    let mut open = through_center.clone();
+    open.points[0].typ = PointType::Move;
+    assert_eq!(open.self_intersections(1.), [(50., 50.)]);
+    open.points.truncate(5);
+    assert!(!open.has_self_intersections(1.));
+
+    // A cubic whose control points cross makes a loop.
+    let loop_ = Contour::new(
+        vec![
+            line(0., 0.),
+            point(150., 100., PointType::OffCurve),
+            point(-50., 100., PointType::OffCurve),
+            point(100., 0., PointType::Curve),
+        ],
+        None,
+    );
+    let intersections = loop_.self_intersections(0.1);
+    assert_eq!(intersections.len(), 1);
+    let (x, y) = intersections[0];
+    assert!((x - 50.).abs() < 0.5 && y > 0., "{x}, {y}");
+    assert!(!Contour::new(vec![], None).has_self_intersections(1.));
+}
+
+#[test]
+fn flatten_contour() {
+    let polygon_area = |points: &[(f64, f64)]| {
+        let closing = [points[points.len() - 1], points[0]];
+        points
+            .windows(2)
+            .chain([&closing[..]])
+            .map(|w| w[0].0 * w[1].1 - w[1].0 * w[0].1)
+            .sum::<f64>()
+            * 0.5
+    };
+
+    let square = contour(&[
+        (0., 0., PointType::Line),
+        (100., 0., PointType::Line),
+        (100., 100., PointType::Line),
+        (0., 100., PointType::Line),
+    ]);
+    assert_eq!(square.flatten(1.0), [(0., 0.), (100., 0.), (100., 100.), (0., 100.)]);
+
+    let cubic = contour(&[
+        (0., 0., PointType::Curve),
+        (100., 0., PointType::Line),
+        (100., 75., PointType::OffCurve),
+        (0., 75., PointType::OffCurve),
+    ]);
+    let coarse = cubic.flatten(5.0);
+    let fine = cubic.flatten(0.01);
+    assert_eq!(coarse[..2], [(0., 0.), (100., 0.)]);
+    assert!(coarse.len() < fine.len());
+    assert!((polygon_area(&fine) - cubic.signed_area()).abs() < 1.0);
+    assert!((polygon_area(&coarse) - cubic.signed_area()).abs() > 1.0);
+
+    let quad = contour(&[
+        (0., 0., PointType::Move),
+        (50., 100., PointType::OffCurve),
+        (100., 0., PointType::QCurve),
+    ]);
+    let open = quad.flatten(0.5);
+    assert_eq!((open[0], open[open.len() - 1]), ((0., 0.), (100., 0.)));
+    assert!(open.contains(&(50., 50.)));
+
+    // Control points on the chord's line, but beyond its end points.
+    let overshoot = contour(&[
+        (0., 0., PointType::Move),
+        (100., 0., PointType::OffCurve),
+        (-90., 0., PointType::OffCurve),
+        (10., 0., PointType::Curve),
+    ]);
+    let flat = overshoot.flatten(1.0);
+    assert_eq!((flat[0], flat[flat.len() - 1]), ((0., 0.), (10., 0.)));
+    assert!(flat.iter().any(|&(x, _)| x > 25.), "{flat:?}");
+    assert!(flat.iter().any(|&(x, _)| x < -15.), "{flat:?}");
+
+    assert_eq!(contour(&[(10., 10., PointType::Move)]).flatten(1.0), [(10., 10.)]);
+    assert!(Contour::default().flatten(1.0).is_empty());
+    // Degenerate tolerances terminate.
+    assert!(cubic.flatten(0.0).len() > fine.len());
+}
+
+#[test]
+fn reverse_contour() {
+    let closed = contour(&[
+        (0., 0., PointType::Curve),
+        (100., 0., PointType::Line),
+        (100., 75., PointType::OffCurve),
+        (0., 75., PointType::OffCurve),
+    ]);
+    let mut reversed = closed.clone();
+    reversed.reverse();
+    assert_eq!(
+        reversed,
+        contour(&[
+            (0., 0., PointType::Line),
+            (0., 75., PointType::OffCurve),
+            (100., 75., PointType::OffCurve),
+            (100., 0., PointType::Curve),
+        ])
+    );
+    assert_eq!(reversed.signed_area(), -closed.signed_area());
+    reversed.reverse();
+    assert_eq!(reversed, closed);
+
+    let mut open = contour(&[
+        (0., 0., PointType::Move),
+        (100., 0., PointType::Line),
+        (150., 50., PointType::OffCurve),
+        (150., 100., PointType::OffCurve),
+        (100., 100., PointType::Curve),
+    ]);
+    open.reverse();
+    assert_eq!(
+        open,
+        contour(&[
+            (100., 100., PointType::Move),
+            (150., 100., PointType::OffCurve),
+            (150., 50., PointType::OffCurve),
+            (100., 0., PointType::Curve),
+            (0., 0., PointType::Line),
+        ])
+    );
+}
+
+#[test]
+fn correct_winding() {
+    use crate::Layer;
+
+    // A clockwise circle of radius `r` around `(x, y)`, drawn with cubics.
+    let circle = |x: f64, y: f64, r: f64| {
+        let k = r * 0.552;
+        let points = [
+            (x - r, y, PointType::Curve),
+            (x - r, y + k, PointType::OffCurve),
+            (x - k, y + r, PointType::OffCurve),
+            (x, y + r, PointType::Curve),
+            (x + k, y + r, PointType::OffCurve),
+            (x + r, y + k, PointType::OffCurve),
+            (x + r, y, PointType::Curve),
+            (x + r, y - k, PointType::OffCurve),
+            (x + k, y - r, PointType::OffCurve),
+            (x, y - r, PointType::Curve),
+            (x - k, y - r, PointType::OffCurve),
+            (x - r, y - k, PointType::OffCurve),
+        ];
+        let points = points
+            .into_iter()
+            .map(|(x, y, typ)| ContourPoint::new(x, y, typ, true, None, None))
+            .collect();
+        Contour::new(points, None)
+    };
+    let layer = Layer::default();
+
+    // An "O" with a counter, a dot in the counter and a hole in the dot, next
+    // to a second "O", all drawn clockwise.
+    let mut glyph = Glyph::new("O");
+    glyph.contours = vec![
+        circle(300., 300., 300.),
+        circle(300., 300., 200.),
+        circle(300., 300., 100.),
+        circle(300., 300., 50.),
+        circle(1000., 300., 300.),
+        circle(1000., 300., 200.),
+    ];
+
+    assert_eq!(glyph.correct_winding(&layer), [0, 2, 4]);
+    assert!(glyph.has_consistent_directions(&layer));
+    let signs: Vec<bool> = glyph.contours.iter().map(|c| c.signed_area() > 0.0).collect();
+    assert_eq!(signs, [true, false, true, false, true, false]);
+    assert!(glyph.correct_winding(&layer).is_empty());
+}
@@ -0,0 +1,143 @@
+//! Typed access to the TrueType-related glyph and component lib keys.
+//!
+//! The following keys, as used by [ufo2ft] and font editors, are recognized:
+//!
+//! - `public.truetype.instructions` in the glyph lib: a dictionary holding
+//!   the glyph's TrueType assembly (see [`TrueTypeInstructions`]);
+//! - `public.truetype.overlap` in the glyph lib: a boolean setting the
+//!   `OVERLAP_SIMPLE`/`OVERLAP_COMPOUND` flag;
+//! - `public.truetype.roundOffsetToGrid` in a component lib: a boolean
+//!   setting the `ROUND_XY_TO_GRID` flag;
+//! - `public.truetype.useMyMetrics` in a component lib: a boolean setting the
+//!   `USE_MY_METRICS` flag.
+//!
+//! The values are stored in the libs as plain plist data, so they round-trip
+//! unchanged whether or not these accessors are used.
+//!
+//! [ufo2ft]: https://github.com/googlefonts/ufo2ft
+
+use plist::Value;
+
+use super::{Component, Glyph};
+#[cfg(feature = "object-libs")]
+use crate::Plist;
+
+use crate::shared_types::keys::{
+    TRUETYPE_INSTRUCTIONS as INSTRUCTIONS_KEY, TRUETYPE_OVERLAP as OVERLAP_KEY,
+    TRUETYPE_ROUND_OFFSET_TO_GRID as ROUND_OFFSET_TO_GRID_KEY,
+    TRUETYPE_USE_MY_METRICS as USE_MY_METRICS_KEY,
+};
+
+static INSTRUCTIONS_FORMAT_VERSION_KEY: &str = "formatVersion";
+static INSTRUCTIONS_ID_KEY: &str = "id";
+static INSTRUCTIONS_ASSEMBLY_KEY: &str = "assembly";
+
+/// The TrueType instructions of a glyph, stored under the
+/// `public.truetype.instructions` glyph lib key.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TrueTypeInstructions {
+    /// The format version of the assembly, currently always `"1"`.
+    pub format_version: String,
+    /// An identifier for the glyph outline the instructions were written for,
+    /// used to detect outdated instructions.
+    pub id: String,
+    /// The TrueType assembly.
+    pub assembly: String,
+}
+
+impl Glyph {
+    /// Returns the glyph's TrueType instructions, if present.
+    ///
+    /// Returns `None` if the lib entry is missing or is not a dictionary with
+    /// string `formatVersion`, `id` and `assembly` values.
+    pub fn truetype_instructions(&self) -> Option<TrueTypeInstructions> {
+        let dict = self.lib.get(INSTRUCTIONS_KEY)?.as_dictionary()?;
+        let get = |key: &str| dict.get(key).and_then(Value::as_string).map(String::from);
+        Some(TrueTypeInstructions {
+            format_version: get(INSTRUCTIONS_FORMAT_VERSION_KEY)?,
+            id: get(INSTRUCTIONS_ID_KEY)?,
+            assembly: get(INSTRUCTIONS_ASSEMBLY_KEY)?,
+        })
+    }
+
+    /// Sets or, if `instructions` is `None`, removes the glyph's TrueType
+    /// instructions.
+    ///
+    /// Any additional keys already present in the lib entry are kept.
+    pub fn set_truetype_instructions(&mut self, instructions: Option<TrueTypeInstructions>) {
+        let Some(instructions) = instructions else {
+            self.lib.remove(INSTRUCTIONS_KEY);
+            return;
+        };
+        let mut dict = match self.lib.remove(INSTRUCTIONS_KEY) {
+            Some(Value::Dictionary(dict)) => dict,
+            _ => Default::default(),
+        };
+        dict.insert(INSTRUCTIONS_FORMAT_VERSION_KEY.into(), instructions.format_version.into());
+        dict.insert(INSTRUCTIONS_ID_KEY.into(), instructions.id.into());
+        dict.insert(INSTRUCTIONS_ASSEMBLY_KEY.into(), instructions.assembly.into());
+        self.lib.insert(INSTRUCTIONS_KEY.into(), dict.into());
+    }
+
+    /// Returns the glyph's `public.truetype.overlap` flag, if set.
+    pub fn truetype_overlap(&self) -> Option<bool> {
+        self.lib.get(OVERLAP_KEY).and_then(Value::as_boolean)
+    }
+
+    /// Sets or, if `overlap` is `None`, removes the glyph's
+    /// `public.truetype.overlap` flag.
+    pub fn set_truetype_overlap(&mut self, overlap: Option<bool>) {
+        match overlap {
+            Some(overlap) => self.lib.insert(OVERLAP_KEY.into(), overlap.into()),
+            None => self.lib.remove(OVERLAP_KEY),
+        };
+    }
+}
+
+impl Component {
+    /// Returns the component's `public.truetype.roundOffsetToGrid` flag, if set.
+    pub fn truetype_round_offset_to_grid(&self) -> Option<bool> {
+        self.lib()?.get(ROUND_OFFSET_TO_GRID_KEY).and_then(Value::as_boolean)
+    }
+
+    /// Returns the component's `public.truetype.useMyMetrics` flag, if set.
+    pub fn truetype_use_my_metrics(&self) -> Option<bool> {
+        self.lib()?.get(USE_MY_METRICS_KEY).and_then(Value::as_boolean)
+    }
+
+    /// Sets or, if `round` is `None`, removes the component's
+    /// `public.truetype.roundOffsetToGrid` flag.
+    ///
+    /// A lib and a UUID v4 identifier are created for the component if needed.
+    #[cfg(feature = "object-libs")]
+    pub fn set_truetype_round_offset_to_grid(&mut self, round: Option<bool>) {
+        self.set_lib_flag(ROUND_OFFSET_TO_GRID_KEY, round);
+    }
+
+    /// Sets or, if `use_my_metrics` is `None`, removes the component's
+    /// `public.truetype.useMyMetrics` flag.
+    ///
+    /// A lib and a UUID v4 identifier are created for the component if needed.
+    #[cfg(feature = "object-libs")]
+    pub fn set_truetype_use_my_metrics(&mut self, use_my_metrics: Option<bool>) {
+        self.set_lib_flag(USE_MY_METRICS_KEY, use_my_metrics);
+    }
+
+    #[cfg(feature = "object-libs")]
+    fn set_lib_flag(&mut self, key: &str, value: Option<bool>) {
+        match (value, self.lib_mut()) {
+            (Some(value), Some(lib)) => {
+                lib.insert(key.into(), value.into());
+            }
+            (Some(value), None) => {
+                let mut lib = Plist::new();
+                lib.insert(key.into(), value.into());
+                self.replace_lib(lib);
+            }
+            (None, Some(lib)) => {
+                lib.remove(key);
+            }
+            (None, None) => (),
+        }
+    }
+}
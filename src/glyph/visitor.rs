@@ -0,0 +1,81 @@
+//! Read-only traversal of glyph outlines.
+
+use super::metrics::extend_bounds;
+use super::{Anchor, Component, Contour, ContourPoint, Glyph};
+
+/// A visitor for the outline of a [`Glyph`], see [`Glyph::accept`].
+///
+/// All methods do nothing by default, so implementors only need to override
+/// the ones they are interested in.
+pub trait OutlineVisitor {
+    /// Called for each anchor, before the contours.
+    fn anchor(&mut self, _anchor: &Anchor) {}
+
+    /// Called at the start of each contour, before its points.
+    fn begin_contour(&mut self, _contour: &Contour) {}
+
+    /// Called for each point of the current contour, in order.
+    fn point(&mut self, _point: &ContourPoint) {}
+
+    /// Called at the end of each contour, after its points.
+    fn end_contour(&mut self, _contour: &Contour) {}
+
+    /// Called for each component, after the contours.
+    fn component(&mut self, _component: &Component) {}
+}
+
+/// An [`OutlineVisitor`] computing the control bounds of the visited contours:
+/// the bounding box of all their points, including off-curve points.
+///
+/// Components and anchors are ignored.
+///
+/// # Examples
+///
+/// ```
+/// # use norad::{ContourPoint, Contour, ControlBounds, Glyph, PointType};
+/// let mut glyph = Glyph::new("a");
+/// let point = |x, y| ContourPoint::new(x, y, PointType::Line, false, None, None);
+/// glyph.contours.push(Contour::new(vec![point(10., 0.), point(50., 80.)], None));
+///
+/// let mut bounds = ControlBounds::default();
+/// glyph.accept(&mut bounds);
+/// assert_eq!(bounds.bounds(), Some((10., 0., 50., 80.)));
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct ControlBounds {
+    bounds: Option<(f64, f64, f64, f64)>,
+}
+
+impl ControlBounds {
+    /// Returns the bounds as `(x_min, y_min, x_max, y_max)`, or `None` if no
+    /// point was visited.
+    pub fn bounds(&self) -> Option<(f64, f64, f64, f64)> {
+        self.bounds
+    }
+}
+
+impl OutlineVisitor for ControlBounds {
+    fn point(&mut self, point: &ContourPoint) {
+        self.bounds = extend_bounds(self.bounds, (point.x, point.y));
+    }
+}
+
+impl Glyph {
+    /// Walks the glyph's anchors, contours and components, in that order,
+    /// calling the corresponding methods of `visitor`.
+    pub fn accept(&self, visitor: &mut impl OutlineVisitor) {
+        for anchor in &self.anchors {
+            visitor.anchor(anchor);
+        }
+        for contour in &self.contours {
+            visitor.begin_contour(contour);
+            for point in &contour.points {
+                visitor.point(point);
+            }
+            visitor.end_contour(contour);
+        }
+        for component in &self.components {
+            visitor.component(component);
+        }
+    }
+}
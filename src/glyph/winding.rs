@@ -0,0 +1,125 @@
+//! Contour directions and how contours nest.
+
+use super::segments::{Point, Segment};
+use super::{AffineTransform, Contour, Glyph};
+use crate::Layer;
+
+/// The number of straight pieces each curve is split into to test
+/// containment.
+const CURVE_STEPS: usize = 16;
+
+/// A closed contour, resolved against its transform and flattened to a
+/// polygon.
+struct Shape {
+    area: f64,
+    polygon: Vec<Point>,
+}
+
+impl Shape {
+    /// Returns `None` for open contours and contours enclosing no area, which
+    /// have no meaningful direction.
+    fn new(contour: &Contour, transform: AffineTransform) -> Option<Self> {
+        if contour.is_open() {
+            return None;
+        }
+        let segments: Vec<Segment> =
+            contour.segments().into_iter().map(|segment| segment.transform(transform)).collect();
+        let area = segments.iter().map(Segment::area).sum::<f64>();
+        if area == 0.0 {
+            return None;
+        }
+        let mut polygon = Vec::new();
+        for segment in &segments {
+            match segment {
+                Segment::Line(start, _) => polygon.push(*start),
+                _ => polygon.extend(
+                    (0..CURVE_STEPS).map(|step| segment.eval(step as f64 / CURVE_STEPS as f64)),
+                ),
+            }
+        }
+        Some(Shape { area, polygon })
+    }
+
+    /// Whether `point` is inside the polygon, by the even-odd rule.
+    fn contains(&self, (x, y): Point) -> bool {
+        let mut inside = false;
+        let mut previous = self.polygon[self.polygon.len() - 1];
+        for &current in &self.polygon {
+            if (current.1 > y) != (previous.1 > y)
+                && x < (previous.0 - current.0) * (y - current.1) / (previous.1 - current.1)
+                    + current.0
+            {
+                inside = !inside;
+            }
+            previous = current;
+        }
+        inside
+    }
+
+    /// Whether `other` lies entirely inside this shape.
+    fn encloses(&self, other: &Shape) -> bool {
+        self.area.abs() > other.area.abs() && other.polygon.iter().all(|&p| self.contains(p))
+    }
+}
+
+impl Glyph {
+    /// Returns the indices of the closed contours running the wrong way for
+    /// their nesting, resolving components against `layer`.
+    ///
+    /// Following the PostScript convention used by UFOs, outer contours run
+    /// counter-clockwise, the holes they enclose clockwise, the contours
+    /// inside those holes counter-clockwise again, and so on. A contour's
+    /// nesting depth is the number of contours, including those of
+    /// components, that entirely enclose it; contours that merely overlap do
+    /// not count.
+    ///
+    /// Open contours and contours enclosing no area are never reported. Only
+    /// the glyph's own contours are checked, as those of components belong to
+    /// their base glyphs.
+    pub fn wrongly_wound_contours(&self, layer: &Layer) -> Vec<usize> {
+        let mut shapes = Vec::new();
+        // Own contours are visited first, so their indices match.
+        self.visit_resolved_contours(layer, |contour, transform| {
+            shapes.push(Shape::new(contour, transform));
+        });
+        let all: Vec<&Shape> = shapes.iter().flatten().collect();
+        shapes[..self.contours.len()]
+            .iter()
+            .enumerate()
+            .filter_map(|(idx, shape)| {
+                let shape = shape.as_ref()?;
+                let depth = all
+                    .iter()
+                    .filter(|other| !std::ptr::eq(**other, shape) && other.encloses(shape))
+                    .count();
+                let outer = depth % 2 == 0;
+                (outer != (shape.area > 0.0)).then_some(idx)
+            })
+            .collect()
+    }
+
+    /// Whether all closed contours run the right way for their nesting,
+    /// resolving components against `layer`.
+    ///
+    /// See [`Glyph::wrongly_wound_contours`] for the convention checked.
+    /// Consistent directions are a precondition of removing overlaps, and of
+    /// rendering correctly with the nonzero fill rule.
+    pub fn has_consistent_directions(&self, layer: &Layer) -> bool {
+        self.wrongly_wound_contours(layer).is_empty()
+    }
+
+    /// Reverses the contours running the wrong way for their nesting, see
+    /// [`Glyph::wrongly_wound_contours`], and returns their indices.
+    ///
+    /// Afterwards, outer contours run counter-clockwise and the holes they
+    /// enclose clockwise, per the PostScript convention. This is the "correct
+    /// path direction" step of font production. Components are resolved
+    /// against `layer` to decide nesting but are not changed themselves.
+    pub fn correct_winding(&mut self, layer: &Layer) -> Vec<usize> {
+        let wrong = self.wrongly_wound_contours(layer);
+        for &idx in &wrong {
+            self.contours[idx].reverse();
+        }
+        wrong
+    }
+}
@@ -2,9 +2,17 @@ use serde::de::Deserializer;
 use serde::ser::{SerializeStruct, Serializer};
 use serde::{de, ser, Deserialize, Serialize};
 
+use crate::identifier::IdentifierSource;
+#[cfg(feature = "object-libs")]
+use crate::identifier::UuidV4Identifiers;
 use crate::{Color, Identifier, Name, Plist};
 
 /// A guideline associated with a glyph.
+///
+/// With serde, a guideline is a struct with the optional `x`, `y`, `angle`,
+/// `name`, `color` and `identifier` fields, as in `fontinfo.plist`. A
+/// vertical line only has an `x`, a horizontal one only a `y`, and an angled
+/// one all three coordinates. The lib is not included.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Guideline {
     /// The line itself.
@@ -15,7 +23,7 @@ pub struct Guideline {
     pub color: Option<Color>,
     /// Unique identifier for the guideline within the glyph. This attribute is only required
     /// when a lib is present and should otherwise only be added as needed.
-    identifier: Option<Identifier>,
+    pub(crate) identifier: Option<Identifier>,
     /// The guideline's lib for arbitrary data.
     pub(crate) lib: Option<Plist>,
 }
@@ -65,8 +73,19 @@ impl Guideline {
     /// lib if present. Sets a new UUID v4 identifier if none is set already.
     #[cfg(feature = "object-libs")]
     pub fn replace_lib(&mut self, lib: Plist) -> Option<Plist> {
+        self.replace_lib_with(lib, &mut UuidV4Identifiers)
+    }
+
+    /// Replaces the actual lib by the lib given in parameter, returning the old
+    /// lib if present. Sets a new identifier taken from `ids` if none is set
+    /// already.
+    pub fn replace_lib_with(
+        &mut self,
+        lib: Plist,
+        ids: &mut impl IdentifierSource,
+    ) -> Option<Plist> {
         if self.identifier.is_none() {
-            self.identifier.replace(Identifier::from_uuidv4());
+            self.identifier.replace(ids.next_identifier());
         }
         self.lib.replace(lib)
     }
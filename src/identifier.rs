@@ -49,6 +49,77 @@ impl Identifier {
     }
 }
 
+/// A source of new [`Identifier`]s, used when norad has to assign an
+/// identifier to an object.
+///
+/// This is implemented for [`UuidV4Identifiers`] (the default), for
+/// [`SequentialIdentifiers`], and for any closure returning an [`Identifier`],
+/// so deterministic pipelines and tests can control the identifiers that end
+/// up in the output.
+///
+/// # Examples
+///
+/// ```
+/// # use norad::{Anchor, Identifier, Plist, SequentialIdentifiers};
+/// let mut ids = SequentialIdentifiers::new("anchor-").unwrap();
+/// let mut anchor = Anchor::new(0.0, 0.0, None, None, None);
+/// anchor.replace_lib_with(Plist::new(), &mut ids);
+/// assert_eq!(anchor.identifier().unwrap().as_str(), "anchor-0");
+/// ```
+pub trait IdentifierSource {
+    /// Returns a new identifier.
+    fn next_identifier(&mut self) -> Identifier;
+}
+
+impl<F: FnMut() -> Identifier> IdentifierSource for F {
+    fn next_identifier(&mut self) -> Identifier {
+        self()
+    }
+}
+
+/// An [`IdentifierSource`] that generates random UUID v4 identifiers.
+#[cfg(feature = "object-libs")]
+#[derive(Debug, Clone, Copy, Default)]
+pub struct UuidV4Identifiers;
+
+#[cfg(feature = "object-libs")]
+impl IdentifierSource for UuidV4Identifiers {
+    fn next_identifier(&mut self) -> Identifier {
+        Identifier::from_uuidv4()
+    }
+}
+
+/// An [`IdentifierSource`] that generates identifiers made of a fixed prefix
+/// followed by a counter, starting at zero.
+#[derive(Debug, Clone)]
+pub struct SequentialIdentifiers {
+    prefix: String,
+    next: u64,
+}
+
+impl SequentialIdentifiers {
+    /// Returns a new source generating identifiers starting with `prefix`.
+    ///
+    /// Returns an error if `prefix` contains characters not allowed in
+    /// identifiers or is too long to leave room for the counter.
+    pub fn new(prefix: &str) -> Result<Self, ErrorKind> {
+        // Leave room for the decimal digits of any u64.
+        if prefix.len() <= 100 - 20 && is_valid_identifier(prefix) {
+            Ok(SequentialIdentifiers { prefix: prefix.into(), next: 0 })
+        } else {
+            Err(ErrorKind::BadIdentifier)
+        }
+    }
+}
+
+impl IdentifierSource for SequentialIdentifiers {
+    fn next_identifier(&mut self) -> Identifier {
+        let id = Identifier(format!("{}{}", self.prefix, self.next).into());
+        self.next += 1;
+        id
+    }
+}
+
 fn is_valid_identifier(s: &str) -> bool {
     s.len() <= 100 && s.bytes().all(|b| (0x20..=0x7E).contains(&b))
 }
@@ -128,4 +199,16 @@ mod tests {
         let i3 = Identifier::new("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa");
         assert!(i3.is_err());
     }
+
+    #[test]
+    fn sequential_identifiers() {
+        let mut ids = SequentialIdentifiers::new("id-").unwrap();
+        assert_eq!(ids.next_identifier(), "id-0");
+        assert_eq!(ids.next_identifier(), "id-1");
+        assert!(SequentialIdentifiers::new("ä").is_err());
+        assert!(SequentialIdentifiers::new(&"a".repeat(81)).is_err());
+
+        let mut closure = || Identifier::new_raw("fixed");
+        assert_eq!(closure.next_identifier(), "fixed");
+    }
 }
@@ -1,6 +1,7 @@
-use std::collections::{BTreeMap, HashSet};
+use std::collections::{btree_map, BTreeMap, HashSet};
 use std::fs;
 use std::path::{Path, PathBuf};
+use std::sync::Arc;
 
 #[cfg(feature = "rayon")]
 use rayon::prelude::*;
@@ -8,9 +9,12 @@ use rayon::prelude::*;
 use serde::Deserialize;
 
 use crate::data_request::LayerFilter;
-use crate::error::{FontLoadError, LayerLoadError, LayerWriteError, NamingError};
+use crate::error::{
+    FontLoadError, GlifWriteError, GlyphLoadError, LayerLoadError, LayerWriteError, NamingError,
+};
 use crate::names::NameList;
 use crate::shared_types::Color;
+use crate::write::CustomSerializationError;
 use crate::Name;
 use crate::{util, Glyph, Plist, WriteOptions};
 
@@ -54,11 +58,14 @@ impl LayerContents {
     /// we will assume the pre-UFOv3 behaviour, and expect a single glyphs dir.
     ///
     /// The `glyph_names` argument allows norad to reuse glyph name strings,
-    /// reducing memory use.
+    /// reducing memory use. Glyphs that fail to load are left out and added
+    /// to `skipped` if it is given, see [`Layer::load_impl`].
     pub(crate) fn load(
         base_dir: &Path,
         glyph_names: &NameList,
         filter: &LayerFilter,
+        strict_object_libs: bool,
+        mut skipped: Option<&mut Vec<GlyphLoadError>>,
     ) -> Result<LayerContents, FontLoadError> {
         let layer_contents_path = base_dir.join(LAYER_CONTENTS_FILE);
         let to_load: Vec<(Name, PathBuf)> = if layer_contents_path.exists() {
@@ -73,12 +80,18 @@ impl LayerContents {
             .filter(|(name, path)| filter.should_load(name, path))
             .map(|(name, path)| {
                 let layer_path = base_dir.join(path);
-                Layer::load_impl(&layer_path, name.clone(), glyph_names).map_err(|source| {
-                    FontLoadError::Layer {
-                        name: name.to_string(),
-                        path: layer_path,
-                        source: Box::new(source),
-                    }
+                let skipped = skipped.as_deref_mut();
+                Layer::load_impl(
+                    &layer_path,
+                    name.clone(),
+                    glyph_names,
+                    strict_object_libs,
+                    skipped,
+                )
+                .map_err(|source| FontLoadError::Layer {
+                    name: name.to_string(),
+                    path: layer_path,
+                    source: Box::new(source),
                 })
             })
             .collect::<Result<_, _>>()?;
@@ -87,14 +100,23 @@ impl LayerContents {
             layers.push(Layer::default());
         }
 
-        // move the default layer to the front
+        // move the default layer to the front, keeping the others in file order
         let default_idx = layers
             .iter()
             .position(|l| l.path.to_str() == Some(DEFAULT_GLYPHS_DIRNAME))
             .ok_or(FontLoadError::MissingDefaultLayer)?;
-        layers.rotate_left(default_idx);
+        let default_layer = layers.remove(default_idx);
+        layers.insert(0, default_layer);
+
+        Ok(LayerContents::from_layers(layers))
+    }
 
-        Ok(LayerContents { layers, path_set: HashSet::new() })
+    /// Returns a [`LayerContents`] made of `layers`, the first being the
+    /// default layer.
+    pub(crate) fn from_layers(layers: Vec<Layer>) -> Self {
+        let path_set =
+            layers.iter().skip(1).map(|l| l.path.to_string_lossy().to_lowercase()).collect();
+        LayerContents { layers, path_set }
     }
 
     /// Returns the number of layers in the set.
@@ -150,13 +172,38 @@ impl LayerContents {
         } else {
             let name = Name::new(name).map_err(|_| NamingError::Invalid(name.into()))?;
             let path = util::default_file_name_for_layer_name(&name, &self.path_set);
-            let layer = Layer::new(name, path);
+            let layer = Layer::empty(name, path);
             self.path_set.insert(layer.path.to_string_lossy().to_lowercase());
             self.layers.push(layer);
             Ok(self.layers.last_mut().unwrap())
         }
     }
 
+    /// Adds `layer` to the set.
+    ///
+    /// The layer's directory is derived anew from its name, avoiding those of
+    /// the other layers. A layer named "public.default" becomes the default
+    /// layer; the previous default layer is then kept as a regular layer.
+    ///
+    /// Returns an error if a layer with the same name exists.
+    pub fn insert_layer(&mut self, mut layer: Layer) -> Result<(), NamingError> {
+        if self.get(&layer.name).is_some() {
+            return Err(NamingError::Duplicate(layer.name.to_string()));
+        }
+        if layer.name == DEFAULT_LAYER_NAME {
+            layer.path = DEFAULT_GLYPHS_DIRNAME.into();
+            let mut previous = std::mem::replace(&mut self.layers[0], layer);
+            previous.path = util::default_file_name_for_layer_name(&previous.name, &self.path_set);
+            self.path_set.insert(previous.path.to_string_lossy().to_lowercase());
+            self.layers.insert(1, previous);
+        } else {
+            layer.path = util::default_file_name_for_layer_name(&layer.name, &self.path_set);
+            self.path_set.insert(layer.path.to_string_lossy().to_lowercase());
+            self.layers.push(layer);
+        }
+        Ok(())
+    }
+
     /// Returns a mutable reference to a layer, by name, creating the layer if it doesn't exist
     pub fn get_or_create_layer(&mut self, name: &str) -> Result<&mut Layer, NamingError> {
         let index = self.layers.iter().position(|l| l.name.as_str() == name);
@@ -242,9 +289,16 @@ impl LayerContents {
         self.layers.retain(|layer| layer.is_default() || predicate(layer));
     }
 
-    /// Removes any layers that contain no glyphs
-    pub fn remove_empty_layers(&mut self) {
-        self.retain(|l| !l.is_empty());
+    /// Removes any layers that contain no glyphs, returning their names.
+    ///
+    /// The default layer is kept even if it is empty.
+    pub fn remove_empty_layers(&mut self) -> Vec<Name> {
+        let empty: Vec<Name> =
+            self.layers.iter().skip(1).filter(|l| l.is_empty()).map(|l| l.name.clone()).collect();
+        for name in &empty {
+            self.remove(name);
+        }
+        empty
     }
 }
 
@@ -259,10 +313,14 @@ impl Default for LayerContents {
 ///
 /// Conceptually, a layer is just a collection of glyphs.
 ///
+/// The glyphs are shared between clones of a layer until they are modified,
+/// so cloning a layer, or a whole [`Font`](crate::Font), is cheap: a glyph is
+/// only copied when it is first borrowed mutably from a clone.
+///
 /// [UFO layer]: http://unifiedfontobject.org/versions/ufo3/glyphs/
 #[derive(Debug, Clone, PartialEq)]
 pub struct Layer {
-    pub(crate) glyphs: BTreeMap<Name, Glyph>,
+    pub(crate) glyphs: BTreeMap<Name, Arc<Glyph>>,
     pub(crate) name: Name,
     pub(crate) path: PathBuf,
     contents: BTreeMap<Name, PathBuf>,
@@ -280,11 +338,54 @@ pub struct Layer {
 }
 
 impl Layer {
-    /// Returns a new [`Layer`] with the provided `name` and `path`.
+    /// Returns a new [`Layer`] named `name`, holding `glyphs`.
+    ///
+    /// Glyph file names are derived from glyph names as
+    /// [`Layer::insert_glyph`] does, avoiding names that clash on
+    /// case-insensitive file systems. The layer's directory name is derived
+    /// from `name` in the same way, or is `glyphs` for the default layer; it
+    /// is derived again when the layer is added to a font with
+    /// [`LayerContents::insert_layer`], so as not to clash with other layers.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if `name` is not a valid name, or if two glyphs share
+    /// a name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # use norad::{Glyph, Layer};
+    /// let layer = Layer::new("sketches", [Glyph::new("a"), Glyph::new("A")])?;
+    /// assert_eq!(layer.len(), 2);
+    /// assert_eq!(layer.get_path("a").unwrap(), std::path::Path::new("a.glif"));
+    /// assert_eq!(layer.get_path("A").unwrap(), std::path::Path::new("A_.glif"));
+    ///
+    /// assert!(Layer::new("sketches", [Glyph::new("a"), Glyph::new("a")]).is_err());
+    /// # Ok::<(), norad::error::NamingError>(())
+    /// ```
+    pub fn new(name: &str, glyphs: impl IntoIterator<Item = Glyph>) -> Result<Layer, NamingError> {
+        let name = Name::new(name).map_err(|_| NamingError::Invalid(name.into()))?;
+        let path = if name == DEFAULT_LAYER_NAME {
+            DEFAULT_GLYPHS_DIRNAME.into()
+        } else {
+            util::default_file_name_for_layer_name(&name, &HashSet::new())
+        };
+        let mut layer = Layer::empty(name, path);
+        for glyph in glyphs {
+            if layer.contains_glyph(&glyph.name) {
+                return Err(NamingError::Duplicate(glyph.name.to_string()));
+            }
+            layer.insert_glyph(glyph);
+        }
+        Ok(layer)
+    }
+
+    /// Returns a new empty [`Layer`] with the provided `name` and `path`.
     ///
     /// The `path` argument will be the directory within the UFO that the layer
     /// is saved.
-    pub(crate) fn new(name: Name, path: PathBuf) -> Self {
+    pub(crate) fn empty(name: Name, path: PathBuf) -> Self {
         Layer {
             glyphs: BTreeMap::new(),
             name,
@@ -296,6 +397,28 @@ impl Layer {
         }
     }
 
+    /// Returns a [`Layer`] made of previously loaded parts, where `contents`
+    /// maps glyph names to file names, as in `contents.plist`.
+    #[cfg(feature = "cache")]
+    pub(crate) fn from_parts(
+        name: Name,
+        path: PathBuf,
+        contents: BTreeMap<Name, PathBuf>,
+        glyphs: BTreeMap<Name, Glyph>,
+        color: Option<Color>,
+        lib: Plist,
+    ) -> Self {
+        let path_set = contents.values().map(|p| p.to_string_lossy().to_lowercase()).collect();
+        let glyphs = glyphs.into_iter().map(|(name, glyph)| (name, Arc::new(glyph))).collect();
+        Layer { glyphs, name, path, contents, path_set, color, lib }
+    }
+
+    /// Returns the mapping of glyph names to file names.
+    #[cfg(feature = "cache")]
+    pub(crate) fn contents(&self) -> &BTreeMap<Name, PathBuf> {
+        &self.contents
+    }
+
     /// Returns a new [`Layer`] that is loaded from `path` with the provided `name`.
     ///
     /// Internal callers should use `load_impl` directly, so that glyph names
@@ -308,50 +431,62 @@ impl Layer {
         let path = path.as_ref();
         let names = NameList::default();
         let name = Name::new_raw(name);
-        Layer::load_impl(path, name, &names)
+        Layer::load_impl(path, name, &names, false, None)
     }
 
     /// The actual loading logic.
     ///
     /// `names` is a map of glyphnames; we pass it throughout parsing
     /// so that we reuse the same `Arc<str>` for identical names.
+    ///
+    /// If `skipped` is given, glyphs that fail to load are added to it and
+    /// left out of the layer, instead of failing the whole layer.
     pub(crate) fn load_impl(
         path: &Path,
         name: Name,
         names: &NameList,
+        strict_object_libs: bool,
+        mut skipped: Option<&mut Vec<GlyphLoadError>>,
     ) -> Result<Layer, LayerLoadError> {
-        let contents_path = path.join(CONTENTS_FILE);
-        if !contents_path.exists() {
-            return Err(LayerLoadError::MissingContentsFile);
-        }
-        // these keys are never used; a future optimization would be to skip the
-        // names and deserialize to a vec; that would not be a one-liner, though.
-        let contents: BTreeMap<Name, PathBuf> = plist::from_file(&contents_path)
-            .map_err(|source| LayerLoadError::ParsePlist { name: CONTENTS_FILE, source })?;
-        let path_set = contents.values().map(|p| p.to_string_lossy().to_lowercase()).collect();
+        let mut contents = Self::load_contents(path)?;
 
         #[cfg(feature = "rayon")]
         let iter = contents.par_iter();
         #[cfg(not(feature = "rayon"))]
         let iter = contents.iter();
 
-        let glyphs = iter
+        let results: Vec<_> = iter
             .map(|(name, glyph_path)| {
                 let name = names.get(name);
                 let glyph_path = path.join(glyph_path);
 
-                Glyph::load_with_names(&glyph_path, names)
-                    .map_err(|source| LayerLoadError::Glyph {
-                        name: name.to_string(),
-                        path: glyph_path,
-                        source,
-                    })
-                    .map(|mut glyph| {
+                match Glyph::load_with_names(&glyph_path, names, strict_object_libs) {
+                    Ok(mut glyph) => {
                         glyph.name = name.clone();
-                        (name, glyph)
-                    })
+                        Ok((name, Arc::new(glyph)))
+                    }
+                    Err(source) => Err((name, glyph_path, source)),
+                }
             })
-            .collect::<Result<_, _>>()?;
+            .collect();
+
+        let mut glyphs = BTreeMap::new();
+        for result in results {
+            match (result, skipped.as_deref_mut()) {
+                (Ok((name, glyph)), _) => {
+                    glyphs.insert(name, glyph);
+                }
+                (Err((glyph_name, path, source)), Some(skipped)) => {
+                    contents.remove(&glyph_name);
+                    let layer = name.clone();
+                    skipped.push(GlyphLoadError { layer, name: glyph_name, path, source });
+                }
+                (Err((name, path, source)), None) => {
+                    return Err(LayerLoadError::Glyph { name: name.to_string(), path, source });
+                }
+            }
+        }
+        let path_set = contents.values().map(|p| p.to_string_lossy().to_lowercase()).collect();
 
         let layerinfo_path = path.join(LAYER_INFO_FILE);
         let (color, lib) = if layerinfo_path.exists() {
@@ -366,6 +501,17 @@ impl Layer {
         Ok(Layer { glyphs, name, path, contents, path_set, color, lib })
     }
 
+    /// Loads the mapping of glyph names to file names from the `contents.plist`
+    /// of the layer directory at `path`.
+    pub(crate) fn load_contents(path: &Path) -> Result<BTreeMap<Name, PathBuf>, LayerLoadError> {
+        let contents_path = path.join(CONTENTS_FILE);
+        if !contents_path.exists() {
+            return Err(LayerLoadError::MissingContentsFile);
+        }
+        plist::from_file(&contents_path)
+            .map_err(|source| LayerLoadError::ParsePlist { name: CONTENTS_FILE, source })
+    }
+
     fn parse_layer_info(path: &Path) -> Result<(Option<Color>, Plist), LayerLoadError> {
         // Pluck apart the data found in the file, as we want to insert it into `Layer`.
         #[derive(Deserialize)]
@@ -379,13 +525,11 @@ impl Layer {
         Ok((layerinfo.color, layerinfo.lib))
     }
 
-    fn layerinfo_to_file_if_needed(
-        &self,
-        path: &Path,
-        options: &WriteOptions,
-    ) -> Result<(), LayerWriteError> {
+    /// Returns the contents of the layerinfo.plist file, or `None` if the
+    /// layer has no color or lib and the file is not needed.
+    fn layerinfo(&self) -> Option<Plist> {
         if self.color.is_none() && self.lib.is_empty() {
-            return Ok(());
+            return None;
         }
 
         let mut dict = plist::dictionary::Dictionary::new();
@@ -398,35 +542,31 @@ impl Layer {
         }
 
         util::recursive_sort_plist_keys(&mut dict);
-
-        crate::write::write_xml_to_file(&path.join(LAYER_INFO_FILE), &dict, options)
-            .map_err(LayerWriteError::LayerInfo)
+        Some(dict)
     }
 
-    /// Serialize this layer to the given path with the default
-    /// [`WriteOptions`] serialization format configuration.
+    /// Calls `f` with the path, under the layer directory `dir`, and the
+    /// contents of each file written when saving the layer.
     ///
-    /// The path should not exist.
-    #[cfg(test)]
-    pub(crate) fn save(&self, path: impl AsRef<Path>) -> Result<(), LayerWriteError> {
-        let options = WriteOptions::default();
-        self.save_with_options(path.as_ref(), &options)
-    }
-
-    /// Serialize this layer to the given `path` with a custom
-    /// [`WriteOptions`] serialization format configuration.
-    ///
-    /// The path should not exist.
-    pub(crate) fn save_with_options(
+    /// Glyph files are visited in parallel if the `rayon` feature is enabled.
+    /// Errors returned by `f` are reported as failures to write the file.
+    pub(crate) fn visit_files_for_writing(
         &self,
-        path: &Path,
+        dir: &Path,
         opts: &WriteOptions,
+        f: &(impl Fn(&Path, &[u8]) -> std::io::Result<()> + Sync),
     ) -> Result<(), LayerWriteError> {
-        fs::create_dir(path).map_err(LayerWriteError::CreateDir)?;
-        crate::write::write_xml_to_file(&path.join(CONTENTS_FILE), &self.contents, opts)
+        let data = crate::write::write_xml_to_bytes(&self.contents, opts)
             .map_err(LayerWriteError::Contents)?;
-
-        self.layerinfo_to_file_if_needed(path, opts)?;
+        f(&dir.join(CONTENTS_FILE), &data)
+            .map_err(|e| LayerWriteError::Contents(CustomSerializationError::WriteFile(e)))?;
+
+        if let Some(dict) = self.layerinfo() {
+            let data = crate::write::write_xml_to_bytes(&dict, opts)
+                .map_err(LayerWriteError::LayerInfo)?;
+            f(&dir.join(LAYER_INFO_FILE), &data)
+                .map_err(|e| LayerWriteError::LayerInfo(CustomSerializationError::WriteFile(e)))?;
+        }
 
         #[cfg(feature = "rayon")]
         let iter = self.contents.par_iter();
@@ -435,15 +575,46 @@ impl Layer {
 
         iter.try_for_each(|(name, glyph_path)| {
             let glyph = self.glyphs.get(name).expect("all glyphs in contents must exist.");
-            let glyph_path = path.join(glyph_path);
-            glyph.save_with_options(&glyph_path, opts).map_err(|source| LayerWriteError::Glyph {
-                name: glyph.name.to_string(),
-                path: glyph_path,
-                source,
+            let path = dir.join(glyph_path);
+            let data = match glyph.encode_for_writing(opts) {
+                Ok(data) => data,
+                Err(source) => {
+                    return Err(LayerWriteError::Glyph { name: name.to_string(), path, source })
+                }
+            };
+            f(&path, &data).map_err(|e| LayerWriteError::Glyph {
+                name: name.to_string(),
+                path,
+                source: GlifWriteError::Io(e),
             })
         })
     }
 
+    /// Serialize this layer to the given path with the default
+    /// [`WriteOptions`] serialization format configuration.
+    ///
+    /// The directory at `path` is created and holds the layer's
+    /// `contents.plist`, `layerinfo.plist` and glyph files, as in a UFO. The
+    /// path should not exist.
+    pub fn save(&self, path: impl AsRef<Path>) -> Result<(), LayerWriteError> {
+        let options = WriteOptions::default();
+        self.save_with_options(path, &options)
+    }
+
+    /// Serialize this layer to the given `path` with a custom
+    /// [`WriteOptions`] serialization format configuration.
+    ///
+    /// The path should not exist.
+    pub fn save_with_options(
+        &self,
+        path: impl AsRef<Path>,
+        opts: &WriteOptions,
+    ) -> Result<(), LayerWriteError> {
+        let path = path.as_ref();
+        fs::create_dir(path).map_err(LayerWriteError::CreateDir)?;
+        self.visit_files_for_writing(path, opts, &|file, data| close_already::fs::write(file, data))
+    }
+
     /// Returns the number of [`Glyph`]s in the layer.
     pub fn len(&self) -> usize {
         self.glyphs.len()
@@ -479,18 +650,29 @@ impl Layer {
     }
 
     /// Gets the given key's corresponding entry in the map for in-place manipulation.
-    pub fn entry(&mut self, glyph: Name) -> std::collections::btree_map::Entry<Name, Glyph> {
-        self.glyphs.entry(glyph)
+    pub fn entry(&mut self, glyph: Name) -> GlyphEntry<'_> {
+        let contents = &mut self.contents;
+        let path_set = &mut self.path_set;
+        match self.glyphs.entry(glyph) {
+            btree_map::Entry::Vacant(entry) => {
+                GlyphEntry::Vacant(VacantGlyphEntry { entry, contents, path_set })
+            }
+            btree_map::Entry::Occupied(entry) => {
+                GlyphEntry::Occupied(OccupiedGlyphEntry { entry, contents, path_set })
+            }
+        }
     }
 
     /// Returns a reference to the glyph with the given name, if it exists.
     pub fn get_glyph(&self, glyph: &str) -> Option<&Glyph> {
-        self.glyphs.get(glyph)
+        self.glyphs.get(glyph).map(Arc::as_ref)
     }
 
     /// Returns a mutable reference to the glyph with the given name, if it exists.
+    ///
+    /// The glyph is copied first if it is shared with a clone of the layer.
     pub fn get_glyph_mut(&mut self, glyph: &str) -> Option<&mut Glyph> {
-        self.glyphs.get_mut(glyph)
+        self.glyphs.get_mut(glyph).map(Arc::make_mut)
     }
 
     /// Returns `true` if this layer contains a glyph with this `name`.
@@ -504,12 +686,8 @@ impl Layer {
     /// the glyph's name.
     pub fn insert_glyph(&mut self, glyph: impl Into<Glyph>) {
         let glyph = glyph.into();
-        if !self.contents.contains_key(&glyph.name) {
-            let path = crate::util::default_file_name_for_glyph_name(&glyph.name, &self.path_set);
-            self.path_set.insert(path.to_string_lossy().to_lowercase());
-            self.contents.insert(glyph.name.clone(), path);
-        }
-        self.glyphs.insert(glyph.name.clone(), glyph);
+        add_glyph_file(&mut self.contents, &mut self.path_set, &glyph.name);
+        self.glyphs.insert(glyph.name.clone(), Arc::new(glyph));
     }
 
     /// Remove all glyphs in the layer. Leave color and the lib untouched.
@@ -521,10 +699,8 @@ impl Layer {
 
     /// Remove the named glyph from this layer and return it, if it exists.
     pub fn remove_glyph(&mut self, name: &str) -> Option<Glyph> {
-        if let Some(path) = self.contents.remove(name) {
-            self.path_set.remove(&path.to_string_lossy().to_lowercase());
-        }
-        self.glyphs.remove(name)
+        remove_glyph_file(&mut self.contents, &mut self.path_set, name);
+        self.glyphs.remove(name).map(Arc::unwrap_or_clone)
     }
 
     /// Rename a glyph.
@@ -556,20 +732,37 @@ impl Layer {
 
     /// Returns an iterator over the glyphs in this layer.
     pub fn iter(&self) -> impl Iterator<Item = &Glyph> + '_ {
-        self.glyphs.values()
+        self.glyphs.values().map(Arc::as_ref)
+    }
+
+    /// Returns an iterator over the names of the glyphs in this layer, in
+    /// sorted order.
+    pub fn glyph_names(&self) -> impl Iterator<Item = &Name> + '_ {
+        self.glyphs.keys()
     }
 
     /// Returns an iterator over the glyphs in this layer, mutably.
-    pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut Glyph> {
-        self.glyphs.values_mut()
+    ///
+    /// A glyph shared with a clone of the layer is only copied when it is
+    /// first modified, not as it is reached, see [`GlyphMut`].
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = GlyphMut<'_>> {
+        self.glyphs.values_mut().map(GlyphMut)
     }
 
     /// Retains only the elements specified by the predicate.
     ///
-    /// In other words, remove all pairs `(k, v)` for which `f(&k, &mut v)` returns `false`.
-    /// The elements are visited in unsorted (and unspecified) order.
-    pub fn retain(&mut self, f: impl FnMut(&Name, &mut Glyph) -> bool) {
-        self.glyphs.retain(f);
+    /// In other words, remove all pairs `(k, v)` for which `f(&k, &v)` returns `false`.
+    /// The elements are visited in name order.
+    pub fn retain(&mut self, mut f: impl FnMut(&Name, &Glyph) -> bool) {
+        let contents = &mut self.contents;
+        let path_set = &mut self.path_set;
+        self.glyphs.retain(|name, glyph| {
+            let keep = f(name, glyph);
+            if !keep {
+                remove_glyph_file(contents, path_set, name);
+            }
+            keep
+        });
     }
 
     /// Returns the path to the .glif file of a given glyph `name`.
@@ -582,7 +775,253 @@ impl Layer {
 
 impl Default for Layer {
     fn default() -> Self {
-        Layer::new(Name::new_raw(DEFAULT_LAYER_NAME), DEFAULT_GLYPHS_DIRNAME.into())
+        Layer::empty(Name::new_raw(DEFAULT_LAYER_NAME), DEFAULT_GLYPHS_DIRNAME.into())
+    }
+}
+
+/// Adds a file name for the glyph `name` to a layer's contents, unless it has
+/// one already.
+fn add_glyph_file(
+    contents: &mut BTreeMap<Name, PathBuf>,
+    path_set: &mut HashSet<String>,
+    name: &Name,
+) {
+    if !contents.contains_key(name) {
+        let path = util::default_file_name_for_glyph_name(name, path_set);
+        path_set.insert(path.to_string_lossy().to_lowercase());
+        contents.insert(name.clone(), path);
+    }
+}
+
+/// Removes the file name of the glyph `name` from a layer's contents.
+fn remove_glyph_file(
+    contents: &mut BTreeMap<Name, PathBuf>,
+    path_set: &mut HashSet<String>,
+    name: &str,
+) {
+    if let Some(path) = contents.remove(name) {
+        path_set.remove(&path.to_string_lossy().to_lowercase());
+    }
+}
+
+/// A view into a single glyph of a [`Layer`], which may be vacant or
+/// occupied.
+///
+/// This is returned by [`Layer::entry`]. Glyphs inserted through an entry
+/// get a file name as with [`Layer::insert_glyph`], and are named after the
+/// entry's key.
+#[derive(Debug)]
+pub enum GlyphEntry<'a> {
+    /// The layer has no glyph with this name.
+    Vacant(VacantGlyphEntry<'a>),
+    /// The layer has a glyph with this name.
+    Occupied(OccupiedGlyphEntry<'a>),
+}
+
+/// A vacant [`GlyphEntry`].
+#[derive(Debug)]
+pub struct VacantGlyphEntry<'a> {
+    entry: btree_map::VacantEntry<'a, Name, Arc<Glyph>>,
+    contents: &'a mut BTreeMap<Name, PathBuf>,
+    path_set: &'a mut HashSet<String>,
+}
+
+/// An occupied [`GlyphEntry`].
+#[derive(Debug)]
+pub struct OccupiedGlyphEntry<'a> {
+    entry: btree_map::OccupiedEntry<'a, Name, Arc<Glyph>>,
+    contents: &'a mut BTreeMap<Name, PathBuf>,
+    path_set: &'a mut HashSet<String>,
+}
+
+impl<'a> GlyphEntry<'a> {
+    /// Returns the name of the glyph.
+    pub fn key(&self) -> &Name {
+        match self {
+            GlyphEntry::Vacant(entry) => entry.key(),
+            GlyphEntry::Occupied(entry) => entry.key(),
+        }
+    }
+
+    /// Inserts `default` if the entry is vacant, and returns a mutable
+    /// reference to the glyph.
+    pub fn or_insert(self, default: Glyph) -> &'a mut Glyph {
+        self.or_insert_with(|| default)
+    }
+
+    /// Inserts the result of `default` if the entry is vacant, and returns a
+    /// mutable reference to the glyph.
+    pub fn or_insert_with(self, default: impl FnOnce() -> Glyph) -> &'a mut Glyph {
+        match self {
+            GlyphEntry::Vacant(entry) => entry.insert(default()),
+            GlyphEntry::Occupied(entry) => entry.into_mut(),
+        }
+    }
+}
+
+impl<'a> VacantGlyphEntry<'a> {
+    /// Returns the name of the glyph.
+    pub fn key(&self) -> &Name {
+        self.entry.key()
+    }
+
+    /// Inserts `glyph`, renamed to the entry's key, and returns a mutable
+    /// reference to it.
+    pub fn insert(self, mut glyph: Glyph) -> &'a mut Glyph {
+        add_glyph_file(self.contents, self.path_set, self.entry.key());
+        glyph.name = self.entry.key().clone();
+        Arc::make_mut(self.entry.insert(Arc::new(glyph)))
+    }
+}
+
+impl<'a> OccupiedGlyphEntry<'a> {
+    /// Returns the name of the glyph.
+    pub fn key(&self) -> &Name {
+        self.entry.key()
+    }
+
+    /// Returns a reference to the glyph.
+    pub fn get(&self) -> &Glyph {
+        self.entry.get()
+    }
+
+    /// Returns a mutable reference to the glyph.
+    ///
+    /// The glyph is copied first if it is shared with a clone of the layer.
+    pub fn get_mut(&mut self) -> &mut Glyph {
+        Arc::make_mut(self.entry.get_mut())
+    }
+
+    /// Converts the entry into a mutable reference to the glyph, copying it
+    /// first if it is shared with a clone of the layer.
+    pub fn into_mut(self) -> &'a mut Glyph {
+        Arc::make_mut(self.entry.into_mut())
+    }
+
+    /// Replaces the glyph with `glyph`, renamed to the entry's key, and
+    /// returns the previous one.
+    pub fn insert(&mut self, mut glyph: Glyph) -> Glyph {
+        glyph.name = self.entry.key().clone();
+        Arc::unwrap_or_clone(self.entry.insert(Arc::new(glyph)))
+    }
+
+    /// Removes the glyph from the layer and returns it.
+    pub fn remove(self) -> Glyph {
+        remove_glyph_file(self.contents, self.path_set, self.entry.key());
+        Arc::unwrap_or_clone(self.entry.remove())
+    }
+}
+
+/// A glyph of a layer, borrowed mutably.
+///
+/// Reading the glyph through this never copies it. It is copied when it is
+/// first modified, if it is shared with a clone of the layer.
+#[derive(Debug)]
+pub struct GlyphMut<'a>(pub(crate) &'a mut Arc<Glyph>);
+
+impl std::ops::Deref for GlyphMut<'_> {
+    type Target = Glyph;
+
+    fn deref(&self) -> &Glyph {
+        self.0
+    }
+}
+
+impl std::ops::DerefMut for GlyphMut<'_> {
+    fn deref_mut(&mut self) -> &mut Glyph {
+        Arc::make_mut(self.0)
+    }
+}
+
+/// Writes glyphs to a layer directory one at a time, without holding the
+/// whole layer in memory.
+///
+/// Each glyph is written as soon as it is passed to
+/// [`LayerWriter::write_glyph`], with a file name derived from its name as
+/// [`Layer::insert_glyph`] does. The layer's `contents.plist` is only written
+/// by [`LayerWriter::finish`], so a writer that is dropped without finishing
+/// leaves an incomplete layer behind.
+///
+/// # Examples
+///
+/// Generating the glyphs of a font straight to disk:
+///
+/// ```no_run
+/// # use norad::{Font, Glyph, LayerWriter};
+/// Font::new().save("Generated.ufo")?;
+/// let mut writer = LayerWriter::new("Generated.ufo/glyphs")?;
+/// for idx in 0..100_000 {
+///     writer.write_glyph(&Glyph::new(&format!("glyph{idx}")))?;
+/// }
+/// writer.finish()?;
+/// # Ok::<(), Box<dyn std::error::Error>>(())
+/// ```
+#[derive(Debug)]
+pub struct LayerWriter {
+    path: PathBuf,
+    options: WriteOptions,
+    contents: BTreeMap<Name, PathBuf>,
+    path_set: HashSet<String>,
+}
+
+impl LayerWriter {
+    /// Returns a writer for the layer directory at `path`, with the default
+    /// [`WriteOptions`].
+    ///
+    /// The directory is created if it does not exist. Files already in it are
+    /// left alone: glyph file names are chosen so as not to clash with them,
+    /// and they are not listed in the new `contents.plist`.
+    pub fn new(path: impl AsRef<Path>) -> Result<Self, LayerWriteError> {
+        Self::with_options(path, WriteOptions::default())
+    }
+
+    /// Returns a writer for the layer directory at `path`, with custom
+    /// [`WriteOptions`].
+    pub fn with_options(
+        path: impl AsRef<Path>,
+        options: WriteOptions,
+    ) -> Result<Self, LayerWriteError> {
+        let path = path.as_ref().to_owned();
+        fs::create_dir_all(&path).map_err(LayerWriteError::CreateDir)?;
+        let path_set = fs::read_dir(&path)
+            .and_then(|entries| {
+                entries
+                    .map(|entry| Ok(entry?.file_name().to_string_lossy().to_lowercase()))
+                    .collect()
+            })
+            .map_err(LayerWriteError::ListExisting)?;
+        Ok(LayerWriter { path, options, contents: BTreeMap::new(), path_set })
+    }
+
+    /// Writes `glyph` to the layer directory.
+    ///
+    /// Writing a glyph with the name of one written before overwrites its file.
+    pub fn write_glyph(&mut self, glyph: &Glyph) -> Result<(), LayerWriteError> {
+        let file_name = match self.contents.get(&glyph.name) {
+            Some(file_name) => file_name.clone(),
+            None => {
+                let file_name = util::default_file_name_for_glyph_name(&glyph.name, &self.path_set);
+                self.path_set.insert(file_name.to_string_lossy().to_lowercase());
+                self.contents.insert(glyph.name.clone(), file_name.clone());
+                file_name
+            }
+        };
+        let path = self.path.join(file_name);
+        glyph.save_with_options(&path, &self.options).map_err(|source| LayerWriteError::Glyph {
+            name: glyph.name.to_string(),
+            path,
+            source,
+        })
+    }
+
+    /// Writes the layer's `contents.plist`, listing all glyphs written.
+    pub fn finish(self) -> Result<(), LayerWriteError> {
+        crate::write::write_xml_to_file(
+            &self.path.join(CONTENTS_FILE),
+            &self.contents,
+            &self.options,
+        )
+        .map_err(LayerWriteError::Contents)
     }
 }
 
@@ -650,6 +1089,66 @@ mod tests {
         assert!(!dir.join("layerinfo.plist").exists());
     }
 
+    #[test]
+    fn new_layer_from_glyphs() {
+        let glyphs = ["a", "A", "a.alt", "A.alt"].map(Glyph::new);
+        let layer = Layer::new("sketches", glyphs).unwrap();
+        assert_eq!(layer.path(), Path::new("glyphs.sketches"));
+        assert_eq!(layer.get_path("A.alt").unwrap(), Path::new("A_.alt.glif"));
+        assert_eq!(Layer::new("public.default", []).unwrap().path(), Path::new("glyphs"));
+
+        let err = Layer::new("sketches", [Glyph::new("a"), Glyph::new("a")]).unwrap_err();
+        assert!(matches!(err, NamingError::Duplicate(name) if name == "a"));
+        assert!(matches!(Layer::new("", []), Err(NamingError::Invalid(_))));
+    }
+
+    #[test]
+    fn insert_layer() {
+        let drafts = Layer::empty(Name::new_raw("drafts"), "glyphs.sketches".into());
+        let mut layers = LayerContents::from_layers(vec![Layer::default(), drafts]);
+
+        layers.insert_layer(Layer::new("sketches", [Glyph::new("a")]).unwrap()).unwrap();
+        let sketches = layers.get("sketches").unwrap();
+        assert_eq!(sketches.path(), Path::new("glyphs.sketches01"));
+        assert!(sketches.contains_glyph("a"));
+
+        let err = layers.insert_layer(Layer::new("drafts", []).unwrap()).unwrap_err();
+        assert!(matches!(err, NamingError::Duplicate(name) if name == "drafts"));
+        let err = layers.insert_layer(Layer::new("public.default", []).unwrap()).unwrap_err();
+        assert!(matches!(err, NamingError::Duplicate(_)));
+
+        layers.rename_layer("public.default", "foreground", false).unwrap();
+        layers.insert_layer(Layer::new("public.default", [Glyph::new("b")]).unwrap()).unwrap();
+        assert_eq!(layers.default_layer().name().as_str(), "public.default");
+        assert!(layers.default_layer().contains_glyph("b"));
+        let names: Vec<_> = layers.names().map(Name::as_str).collect();
+        assert_eq!(names, ["public.default", "foreground", "drafts", "sketches"]);
+        assert_eq!(layers.get("foreground").unwrap().path(), Path::new("glyphs.foreground"));
+    }
+
+    #[test]
+    fn glyph_entry_keeps_contents() {
+        let mut layer = Layer::new("sketches", [Glyph::new("a")]).unwrap();
+        let copy = layer.clone();
+
+        layer.entry(Name::new_raw("B")).or_insert(Glyph::new("whatever")).width = 500.;
+        assert_eq!(layer.get_glyph("B").unwrap().name().as_str(), "B");
+        assert_eq!(layer.get_path("B"), Some(Path::new("B_.glif")));
+
+        let GlyphEntry::Occupied(mut entry) = layer.entry(Name::new_raw("a")) else { panic!() };
+        assert!(std::ptr::eq(entry.get(), copy.get_glyph("a").unwrap()));
+        entry.get_mut().width = 200.;
+        assert_eq!(copy.get_glyph("a").unwrap().width, 0.);
+        assert_eq!(entry.remove().width, 200.);
+        assert_eq!(layer.get_path("a"), None);
+
+        layer.insert_glyph(Glyph::new("c"));
+        layer.retain(|name, _| *name != "c");
+        assert_eq!(layer.get_path("c"), None);
+        let temp_dir = TempDir::new().unwrap();
+        layer.save(temp_dir.path().join("sketches")).unwrap();
+    }
+
     #[test]
     fn delete() {
         let layer_path = "testdata/MutatorSansLightWide.ufo/glyphs";
@@ -862,6 +1361,59 @@ mod tests {
         assert_eq!(layer.contents.get("Ab").unwrap().as_os_str(), "A_b.glif");
     }
 
+    #[test]
+    fn clones_share_glyphs() {
+        let layer_path = "testdata/MutatorSansLightWide.ufo/glyphs";
+        let layer = Layer::load(layer_path, DEFAULT_LAYER_NAME).unwrap();
+        let mut clone = layer.clone();
+        assert!(Arc::ptr_eq(&layer.glyphs["A"], &clone.glyphs["A"]));
+
+        clone.get_glyph_mut("A").unwrap().width = 1.0;
+        assert_ne!(layer.get_glyph("A").unwrap().width, 1.0);
+        assert!(!Arc::ptr_eq(&layer.glyphs["A"], &clone.glyphs["A"]));
+        assert!(Arc::ptr_eq(&layer.glyphs["B"], &clone.glyphs["B"]));
+        assert_eq!(clone.remove_glyph("B"), layer.get_glyph("B").cloned());
+
+        // Glyphs reached by `iter_mut` are only copied when modified.
+        let mut clone = layer.clone();
+        for mut glyph in clone.iter_mut() {
+            if *glyph.name() == "E" {
+                glyph.width = 1.0;
+            }
+        }
+        assert!(!Arc::ptr_eq(&layer.glyphs["E"], &clone.glyphs["E"]));
+        assert!(Arc::ptr_eq(&layer.glyphs["F"], &clone.glyphs["F"]));
+    }
+
+    #[test]
+    fn layer_writer() {
+        let dir = tempfile::tempdir().unwrap();
+        let font = crate::Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+        crate::Font::new().save(dir.path()).unwrap();
+        let stray = dir.path().join("glyphs/x.glif");
+        fs::write(&stray, "stray").unwrap();
+
+        let mut writer = LayerWriter::new(dir.path().join("glyphs")).unwrap();
+        writer.write_glyph(&Glyph::new("x")).unwrap();
+        for glyph in font.default_layer().iter() {
+            writer.write_glyph(glyph).unwrap();
+        }
+        writer.write_glyph(font.get_glyph("A").unwrap()).unwrap();
+        writer.write_glyph(&Glyph::new("a_")).unwrap();
+        writer.finish().unwrap();
+
+        let loaded = crate::Font::load(dir.path()).unwrap();
+        let layer = loaded.default_layer();
+        assert_eq!(layer.len(), font.default_layer().len() + 2);
+        assert_eq!(layer.contents.get("x").unwrap().as_os_str(), "x01.glif");
+        assert_eq!(fs::read_to_string(stray).unwrap(), "stray");
+        assert_eq!(layer.contents.get("A").unwrap().as_os_str(), "A_.glif");
+        assert_eq!(layer.contents.get("a_").unwrap().as_os_str(), "a_01.glif");
+        for glyph in font.default_layer().iter() {
+            assert_eq!(layer.get_glyph(glyph.name()), Some(glyph));
+        }
+    }
+
     #[test]
     fn test_filter() {
         static UFO_DIR: &str = "testdata/MutatorSansLightWide.ufo/";
@@ -870,31 +1422,31 @@ mod tests {
         let names = NameList::default();
 
         let request = DataRequest::all();
-        let layerset = LayerContents::load(ufo_path, &names, &request.layers).unwrap();
+        let layerset = LayerContents::load(ufo_path, &names, &request.layers, false, None).unwrap();
         assert_eq!(layerset.len(), 2);
         assert_eq!(layerset.default_layer().len(), 48);
 
         let request = DataRequest::none();
-        let layerset = LayerContents::load(ufo_path, &names, &request.layers).unwrap();
+        let layerset = LayerContents::load(ufo_path, &names, &request.layers, false, None).unwrap();
         // default layer is always present
         assert_eq!(layerset.len(), 1);
         assert_eq!(layerset.default_layer().len(), 0);
 
         let request = DataRequest::none().default_layer(true);
-        let layerset = LayerContents::load(ufo_path, &names, &request.layers).unwrap();
+        let layerset = LayerContents::load(ufo_path, &names, &request.layers, false, None).unwrap();
         assert_eq!(layerset.len(), 1);
         assert_eq!(layerset.default_layer().len(), 48);
 
         // all is overridden by default_layer
         let request = DataRequest::all().default_layer(true);
-        let layerset = LayerContents::load(ufo_path, &names, &request.layers).unwrap();
+        let layerset = LayerContents::load(ufo_path, &names, &request.layers, false, None).unwrap();
         // default layer is always present
         assert_eq!(layerset.len(), 1);
         assert_eq!(layerset.default_layer().len(), 48);
 
         let layer_name = String::from("background");
         let request = DataRequest::none().filter_layers(|name, _path| name == layer_name);
-        let layerset = LayerContents::load(ufo_path, &names, &request.layers).unwrap();
+        let layerset = LayerContents::load(ufo_path, &names, &request.layers, false, None).unwrap();
         // default layer is always present
         assert_eq!(layerset.len(), 2);
         assert_eq!(layerset.default_layer().len(), 0);
@@ -908,9 +1460,9 @@ mod tests {
         let mut layers = LayerContents {
             layers: vec![
                 Layer::default(),
-                Layer::new(Name::new("fizz").unwrap(), PathBuf::new()),
-                Layer::new(Name::new("buzz").unwrap(), PathBuf::new()),
-                Layer::new(Name::new("fizzbuzz").unwrap(), PathBuf::new()),
+                Layer::empty(Name::new("fizz").unwrap(), PathBuf::new()),
+                Layer::empty(Name::new("buzz").unwrap(), PathBuf::new()),
+                Layer::empty(Name::new("fizzbuzz").unwrap(), PathBuf::new()),
             ],
             ..Default::default()
         };
@@ -932,23 +1484,23 @@ mod tests {
                 Layer {
                     name: Name::new("fizz").unwrap(),
                     glyphs: maplit::btreemap! {
-                        Name::new("a").unwrap() => Glyph::new("a"),
+                        Name::new("a").unwrap() => Arc::new(Glyph::new("a")),
                     },
                     ..Default::default()
                 },
                 Layer {
                     name: Name::new("buzz").unwrap(),
                     glyphs: maplit::btreemap! {
-                        Name::new("b").unwrap() => Glyph::new("b"),
+                        Name::new("b").unwrap() => Arc::new(Glyph::new("b")),
                     },
                     ..Default::default()
                 },
-                Layer::new(Name::new("fizzbuzz").unwrap(), PathBuf::new()),
+                Layer::empty(Name::new("fizzbuzz").unwrap(), PathBuf::new()),
             ],
             ..Default::default()
         };
 
-        layers.remove_empty_layers();
+        assert_eq!(layers.remove_empty_layers(), vec![Name::new_raw("fizzbuzz")]);
 
         assert_eq!(layers.len(), 3, "wrong number of layers deleted");
         let names = layers.iter().map(|l| l.name().as_str()).collect::<Vec<_>>();
@@ -65,6 +65,8 @@
 #![warn(missing_docs)]
 #![deny(rustdoc::broken_intra_doc_links, unsafe_code)]
 
+#[cfg(feature = "cache")]
+mod cache;
 mod data_request;
 pub mod datastore;
 pub mod designspace;
@@ -86,19 +88,29 @@ pub(crate) mod util;
 mod write;
 
 pub use data_request::DataRequest;
-pub use font::{Font, FormatVersion, MetaInfo};
+pub use font::{
+    load_glyph_from_ufo, Font, FormatVersion, InterpolationReport, LayerIssue, MetaInfo,
+    ValidationIssue,
+};
 pub use fontinfo::FontInfo;
 pub use glyph::{
-    AffineTransform, Anchor, Codepoints, Component, Contour, ContourPoint, Glyph, Image, PointType,
+    AffineTransform, Anchor, Codepoints, Component, ComponentResolver, Contour, ContourBuilder,
+    ContourPoint, ControlBounds, DuplicateAnchors, Extrema, GlifVersion, Glyph, Image, ObjectRef,
+    ObjectRefMut, OutlineFilter, OutlineVisitor, PointSelection, PointTuple, PointType,
+    TrueTypeInstructions,
 };
 
 pub use name::Name;
 
 pub use groups::Groups;
 pub use guideline::{Guideline, Line};
-pub use identifier::Identifier;
+#[cfg(feature = "object-libs")]
+pub use identifier::UuidV4Identifiers;
+pub use identifier::{Identifier, IdentifierSource, SequentialIdentifiers};
 pub use kerning::Kerning;
-pub use layer::{Layer, LayerContents};
-pub use shared_types::{Color, Plist};
+pub use layer::{
+    GlyphEntry, GlyphMut, Layer, LayerContents, LayerWriter, OccupiedGlyphEntry, VacantGlyphEntry,
+};
+pub use shared_types::{keys, Color, GlyphCategory, PaletteColor, Plist, UnknownCategory};
 pub use util::user_name_to_file_name;
-pub use write::{QuoteChar, WriteOptions};
+pub use write::{PlistStyle, QuoteChar, WriteOptions};
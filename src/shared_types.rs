@@ -4,7 +4,63 @@ use serde::de::Deserializer;
 use serde::ser::Serializer;
 use serde::{Deserialize, Serialize};
 
-pub static PUBLIC_OBJECT_LIBS_KEY: &str = "public.objectLibs";
+pub(crate) use keys::{
+    GLYPH_ORDER as PUBLIC_GLYPH_ORDER_KEY, MARK_COLOR as PUBLIC_MARK_COLOR_KEY,
+    OBJECT_LIBS as PUBLIC_OBJECT_LIBS_KEY, OPENTYPE_CATEGORIES as PUBLIC_OPENTYPE_CATEGORIES_KEY,
+    SKIP_EXPORT_GLYPHS as PUBLIC_SKIP_EXPORT_GLYPHS_KEY,
+    VERTICAL_ORIGIN as PUBLIC_VERTICAL_ORIGIN_KEY,
+};
+
+/// Well-known lib keys reserved by the UFO specification.
+///
+/// See the specification of the [font lib] and of the [glyph lib] for the
+/// expected values.
+///
+/// [font lib]: https://unifiedfontobject.org/versions/ufo3/lib.plist/
+/// [glyph lib]: https://unifiedfontobject.org/versions/ufo3/glyphs/glif/#publicmarkcolor
+pub mod keys {
+    /// The font and glyph lib key holding the libs of objects such as global
+    /// guidelines, anchors and contours, by identifier.
+    ///
+    /// Norad moves these into the objects on load and back on save, so it is
+    /// not normally found in a loaded lib.
+    pub const OBJECT_LIBS: &str = "public.objectLibs";
+    /// The font lib key holding the preferred order of glyphs, as an array of
+    /// glyph names.
+    pub const GLYPH_ORDER: &str = "public.glyphOrder";
+    /// The font lib key mapping glyph names to the production names to use
+    /// in compiled fonts.
+    pub const POSTSCRIPT_NAMES: &str = "public.postscriptNames";
+    /// The font lib key listing the names of glyphs to leave out of compiled
+    /// fonts.
+    pub const SKIP_EXPORT_GLYPHS: &str = "public.skipExportGlyphs";
+    /// The font lib key mapping glyph names to their OpenType category, see
+    /// [`Font::glyph_category`](crate::Font::glyph_category).
+    pub const OPENTYPE_CATEGORIES: &str = "public.openTypeCategories";
+    /// The font lib key holding the data of the OpenType `meta` table.
+    pub const OPENTYPE_META: &str = "public.openTypeMeta";
+    /// The font lib key mapping Unicode variation sequences to glyph names.
+    pub const UNICODE_VARIATION_SEQUENCES: &str = "public.unicodeVariationSequences";
+    /// The glyph lib key holding the glyph's mark color, see
+    /// [`Glyph::mark_color`](crate::Glyph::mark_color).
+    pub const MARK_COLOR: &str = "public.markColor";
+    /// The glyph lib key holding the y coordinate of the glyph's origin in
+    /// vertical layout, see
+    /// [`Glyph::vertical_origin`](crate::Glyph::vertical_origin).
+    pub const VERTICAL_ORIGIN: &str = "public.verticalOrigin";
+    /// The glyph lib key holding the glyph's TrueType instructions, see
+    /// [`TrueTypeInstructions`](crate::TrueTypeInstructions).
+    pub const TRUETYPE_INSTRUCTIONS: &str = "public.truetype.instructions";
+    /// The glyph lib key telling whether the contours and components of the
+    /// glyph overlap.
+    pub const TRUETYPE_OVERLAP: &str = "public.truetype.overlap";
+    /// The component lib key telling whether the component's offset should be
+    /// rounded to the grid.
+    pub const TRUETYPE_ROUND_OFFSET_TO_GRID: &str = "public.truetype.roundOffsetToGrid";
+    /// The component lib key telling whether the glyph should take its
+    /// metrics from the component.
+    pub const TRUETYPE_USE_MY_METRICS: &str = "public.truetype.useMyMetrics";
+}
 
 /// A Plist dictionary.
 pub type Plist = plist::Dictionary;
@@ -12,6 +68,9 @@ pub type Plist = plist::Dictionary;
 /// A color in RGBA (Red-Green-Blue-Alpha) format.
 ///
 /// See <https://unifiedfontobject.org/versions/ufo3/conventions/#colors>.
+///
+/// With serde, a color is the same `"r,g,b,a"` string as in UFO files, with
+/// channels rounded to three decimal places.
 #[derive(Debug, Clone, PartialEq)]
 pub struct Color {
     /// Red channel value. Must be in the range 0 to 1, inclusive.
@@ -40,6 +99,104 @@ impl Color {
     pub fn channels(&self) -> (f64, f64, f64, f64) {
         (self.red, self.green, self.blue, self.alpha)
     }
+
+    /// Returns `true` if each channel of `self` differs from that of `other`
+    /// by at most `epsilon`.
+    pub fn approx_eq(&self, other: &Color, epsilon: f64) -> bool {
+        let (a, b) = (self.channels(), other.channels());
+        [(a.0, b.0), (a.1, b.1), (a.2, b.2), (a.3, b.3)]
+            .iter()
+            .all(|(a, b)| (a - b).abs() <= epsilon)
+    }
+
+    /// Returns the RGBA channel values in thousandths, rounded to the nearest
+    /// integer.
+    ///
+    /// This is the precision colors are written with, so colors that are
+    /// written the same have the same quantized form. Unlike `Color`, it can be
+    /// hashed, e.g. to group glyphs by mark color.
+    pub fn quantized(&self) -> [u16; 4] {
+        let (red, green, blue, alpha) = self.channels();
+        [red, green, blue, alpha].map(|c| (c * 1000.).round() as u16)
+    }
+}
+
+/// A named color in the font's mark color palette.
+///
+/// See [`Font::mark_color_palette`](crate::Font::mark_color_palette).
+#[derive(Debug, Clone, PartialEq)]
+pub struct PaletteColor {
+    /// The name of the color, as shown to the user.
+    pub name: String,
+    /// The color.
+    pub color: Color,
+}
+
+/// The OpenType category of a glyph, used to build the `GDEF` glyph class
+/// definitions.
+///
+/// See [`Font::glyph_category`](crate::Font::glyph_category) and the
+/// [`public.openTypeCategories`] lib key.
+///
+/// [`public.openTypeCategories`]: https://unifiedfontobject.org/versions/ufo3/lib.plist/#publicopentypecategories
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum GlyphCategory {
+    /// The glyph is not assigned a class.
+    Unassigned,
+    /// A base glyph, to which marks attach.
+    Base,
+    /// A combining mark.
+    Mark,
+    /// A ligature glyph.
+    Ligature,
+    /// A component of a ligature, not used in fonts compiled today.
+    Component,
+    /// A category not defined by the spec, kept as is.
+    Unknown(UnknownCategory),
+}
+
+/// The name of a glyph category not defined by the spec.
+///
+/// This can only be made with [`GlyphCategory::from`], which returns one of
+/// the other variants for the categories defined by the spec, so that each
+/// category has a single representation.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownCategory(String);
+
+impl UnknownCategory {
+    /// Returns the name of the category.
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+}
+
+impl GlyphCategory {
+    /// Returns the string this category is stored as.
+    pub fn as_str(&self) -> &str {
+        match self {
+            GlyphCategory::Unassigned => "unassigned",
+            GlyphCategory::Base => "base",
+            GlyphCategory::Mark => "mark",
+            GlyphCategory::Ligature => "ligature",
+            GlyphCategory::Component => "component",
+            GlyphCategory::Unknown(category) => category.as_str(),
+        }
+    }
+}
+
+impl From<&str> for GlyphCategory {
+    /// Returns the category stored as `value`, or [`GlyphCategory::Unknown`]
+    /// if it is not one of the categories defined by the spec.
+    fn from(value: &str) -> Self {
+        match value {
+            "unassigned" => GlyphCategory::Unassigned,
+            "base" => GlyphCategory::Base,
+            "mark" => GlyphCategory::Mark,
+            "ligature" => GlyphCategory::Ligature,
+            "component" => GlyphCategory::Component,
+            other => GlyphCategory::Unknown(UnknownCategory(other.to_owned())),
+        }
+    }
 }
 
 /// An error representing an invalid [`Color`] string.
@@ -121,4 +278,16 @@ mod tests {
         let c6 = Color { red: 0.123456789, green: 0.456789123, blue: 0.789123456, alpha: 0.1 };
         assert_de_tokens(&c6, &[Token::Str("0.123456789,0.456789123,0.789123456,0.1")]);
     }
+
+    #[test]
+    fn approximate_comparison() {
+        let c1 = Color::new(0.5, 0.2, 0.1, 1.0).unwrap();
+        let c2 = Color::new(0.5004, 0.2, 0.1, 1.0).unwrap();
+        assert_ne!(c1, c2);
+        assert!(c1.approx_eq(&c2, 0.001));
+        assert!(!c1.approx_eq(&c2, 0.0001));
+        assert_eq!(c1.quantized(), [500, 200, 100, 1000]);
+        assert_eq!(c1.quantized(), c2.quantized());
+        assert_ne!(c1.quantized(), Color::new(0.501, 0.2, 0.1, 1.0).unwrap().quantized());
+    }
 }
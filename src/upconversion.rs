@@ -1,4 +1,4 @@
-use std::collections::{BTreeMap, HashMap, HashSet};
+use std::collections::{BTreeMap, BTreeSet, HashMap, HashSet};
 use std::path::Path;
 
 use serde::Deserialize;
@@ -83,6 +83,81 @@ pub(crate) fn upconvert_kerning(
     (groups_new, kerning_new)
 }
 
+/// Migrate kerning groups of a font using the v3 conventions to the
+/// `public.kern1.`/`public.kern2.` prefixes, as in [`upconvert_kerning`].
+///
+/// Unlike [`upconvert_kerning`], groups that already have a kerning prefix are
+/// left alone, and a legacy group is not duplicated again if a group with the
+/// new name and the same members already exists, so this can be run
+/// repeatedly.
+///
+/// Also returns the names in kerning pairs that could not be classified: those
+/// that are neither a glyph in `glyph_set` nor a group, and kerning groups used
+/// on the wrong side of a pair.
+pub(crate) fn canonicalize_kerning_groups(
+    groups: &Groups,
+    kerning: &Kerning,
+    glyph_set: &NameList,
+) -> (Groups, Kerning, Vec<Name>) {
+    let (mut groups_first, mut groups_second) = find_known_kerning_groups(groups);
+    let mut unclassified: BTreeSet<Name> = BTreeSet::new();
+
+    let mut classify = |name: &Name, side_prefix: &str, other_prefix: &str| -> bool {
+        if name.starts_with(other_prefix) {
+            unclassified.insert(name.clone());
+        } else if glyph_set.contains(name) {
+            return false;
+        } else if !groups.contains_key(name) {
+            unclassified.insert(name.clone());
+        } else if !name.starts_with(side_prefix) {
+            return true;
+        }
+        false
+    };
+    for (first, seconds) in kerning {
+        if classify(first, "public.kern1.", "public.kern2.") {
+            groups_first.insert(first.clone());
+        }
+        for second in seconds.keys() {
+            if classify(second, "public.kern2.", "public.kern1.") {
+                groups_second.insert(second.clone());
+            }
+        }
+    }
+
+    let mut groups_new = groups.clone();
+    let mut rename = |old: &Name, prefix: &str, legacy_prefix: &str| -> Name {
+        let members = &groups[old];
+        let name = Name::new(&format!("{prefix}{}", old.replace(legacy_prefix, ""))).unwrap();
+        if groups_new.get(&name) == Some(members) {
+            return name;
+        }
+        let name = make_unique_group_name(name, &groups_new);
+        groups_new.insert(name.clone(), members.clone());
+        name
+    };
+    let first_old_to_new: HashMap<&Name, Name> =
+        groups_first.iter().map(|old| (old, rename(old, "public.kern1.", "@MMK_L_"))).collect();
+    let second_old_to_new: HashMap<&Name, Name> =
+        groups_second.iter().map(|old| (old, rename(old, "public.kern2.", "@MMK_R_"))).collect();
+
+    let kerning_new = kerning
+        .iter()
+        .map(|(first, seconds)| {
+            let first = first_old_to_new.get(first).unwrap_or(first).clone();
+            let seconds = seconds
+                .iter()
+                .map(|(second, value)| {
+                    (second_old_to_new.get(second).unwrap_or(second).clone(), *value)
+                })
+                .collect();
+            (first, seconds)
+        })
+        .collect();
+
+    (groups_new, kerning_new, unclassified.into_iter().collect())
+}
+
 fn make_unique_group_name(name: Name, existing_groups: &Groups) -> Name {
     if !existing_groups.contains_key(&name) {
         return name;
@@ -1,13 +1,8 @@
 //! Customize serialization behaviour
 
-use std::{borrow::Cow, fs::File, io::BufWriter, path::Path};
-
-#[cfg(target_family = "unix")]
-use std::os::unix::prelude::FileExt;
+use std::{borrow::Cow, fs::File, io::Write, path::Path};
 
 use close_already::FastCloseable;
-#[cfg(target_family = "windows")]
-use std::os::windows::prelude::*;
 
 use plist::XmlWriteOptions;
 
@@ -35,6 +30,10 @@ pub struct WriteOptions {
     pub(crate) indent_char: u8,
     pub(crate) indent_count: usize,
     pub(crate) quote_style: QuoteChar,
+    pub(crate) sort_anchors: bool,
+    pub(crate) full_color_precision: bool,
+    pub(crate) plist_style: PlistStyle,
+    pub(crate) only_write_changes: bool,
 }
 
 impl Default for WriteOptions {
@@ -44,6 +43,10 @@ impl Default for WriteOptions {
             indent_char: WriteOptions::TAB,
             indent_count: 1,
             quote_style: QuoteChar::Double,
+            sort_anchors: false,
+            full_color_precision: false,
+            plist_style: PlistStyle::Default,
+            only_write_changes: false,
         }
     }
 }
@@ -123,6 +126,56 @@ impl WriteOptions {
         self
     }
 
+    /// Builder-style method to write glyph anchors and guidelines in a canonical order.
+    ///
+    /// By default, anchors and guidelines are written in the order they appear in
+    /// the glyph. When this is set, anchors are sorted by name and then position,
+    /// and guidelines by name and then line, so that reordering them in an editor
+    /// does not produce spurious diffs. The in-memory glyph is left untouched.
+    pub fn sort_anchors(mut self, sort_anchors: bool) -> Self {
+        self.sort_anchors = sort_anchors;
+        self
+    }
+
+    /// Builder-style method to write the colors of glyph images, anchors and
+    /// guidelines with full precision.
+    ///
+    /// By default, color channels are written with three decimal places, see
+    /// [`Color::to_rgba_string`](crate::Color::to_rgba_string). When this is
+    /// set, each channel is written with as many digits as needed to read back
+    /// the exact same value, so colors loaded with more precision, such as an
+    /// image tint of `0.1234`, are written back unchanged.
+    pub fn full_color_precision(mut self, full_color_precision: bool) -> Self {
+        self.full_color_precision = full_color_precision;
+        self
+    }
+
+    /// Builder-style method to customize the layout of `.plist` files.
+    ///
+    /// By default, plists are written as the `plist` crate formats them. See
+    /// [`PlistStyle`] for the alternatives.
+    pub fn plist_style(mut self, plist_style: PlistStyle) -> Self {
+        self.plist_style = plist_style;
+        self
+    }
+
+    /// Builder-style method to only write the files whose contents changed
+    /// when saving over an existing UFO.
+    ///
+    /// By default, saving replaces the whole UFO directory. When this is set,
+    /// each file is serialized and compared with the one already on disk, and
+    /// only written if they differ; files the font no longer contains are
+    /// deleted. This preserves the modification times of unchanged files and
+    /// avoids churn in version control.
+    ///
+    /// Comparing requires reading every existing file, and files are written
+    /// one after the other, so this is slower when most files did change. See
+    /// [`Font::save_plan`](crate::Font::save_plan) to preview the changes.
+    pub fn only_write_changes(mut self, only_write_changes: bool) -> Self {
+        self.only_write_changes = only_write_changes;
+        self
+    }
+
     /// Return a reference to [`XmlWriteOptions`] for use with the `plist` crate.
     pub fn xml_options(&self) -> &XmlWriteOptions {
         &self.xml_opts
@@ -147,36 +200,97 @@ pub enum QuoteChar {
     Double,
 }
 
+/// The layout used when writing `.plist` files.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[non_exhaustive]
+pub enum PlistStyle {
+    /// The layout of the `plist` crate: the root element is not indented
+    /// relative to the enclosing `<plist>` element.
+    #[default]
+    Default,
+    /// The layout of Python's ufoLib and fontTools: everything inside the
+    /// `<plist>` element is indented by one level.
+    ///
+    /// To match ufoLib's output, combine this with
+    /// `.indent(WriteOptions::SPACE, 2)` and `.quote_char(QuoteChar::Single)`.
+    UfoLib,
+}
+
 /// Write any `Serialize` to file, providing custom options.
 pub(crate) fn write_xml_to_file(
     path: &Path,
     value: &impl serde::Serialize,
     options: &WriteOptions,
 ) -> Result<(), CustomSerializationError> {
+    let data = write_xml_to_bytes(value, options)?;
     let mut file = File::create(path).map_err(CustomSerializationError::CreateFile)?.fast_close();
-    let buf_writer = BufWriter::new(&mut file);
-    plist::to_writer_xml_with_options(buf_writer, value, options.xml_options())
-        .map_err(CustomSerializationError::SerializePlist)?;
-    write_quote_style(&file, options).map_err(CustomSerializationError::WriteQuotes)?;
+    file.write_all(&data).map_err(CustomSerializationError::WriteFile)?;
     file.sync_all().map_err(CustomSerializationError::Sync)?;
     Ok(())
 }
 
-/// Write XML declarations with custom quote formatting options.
-fn write_quote_style(file: &File, options: &WriteOptions) -> Result<(), std::io::Error> {
-    // Optionally modify the XML declaration quote style
+/// Serialize any `Serialize` to an XML plist in memory, providing custom options.
+pub(crate) fn write_xml_to_bytes(
+    value: &impl serde::Serialize,
+    options: &WriteOptions,
+) -> Result<Vec<u8>, CustomSerializationError> {
+    let mut data = Vec::new();
+    plist::to_writer_xml_with_options(&mut data, value, options.xml_options())
+        .map_err(CustomSerializationError::SerializePlist)?;
+    write_quote_style(&mut data, options);
+    if options.plist_style == PlistStyle::UfoLib {
+        data = indent_plist_body(&data, options);
+    }
+    Ok(data)
+}
+
+/// Indent everything between the `<plist>` tags by one level.
+///
+/// Line breaks inside `<key>` and `<string>` elements are part of the value and
+/// are left alone.
+fn indent_plist_body(data: &[u8], options: &WriteOptions) -> Vec<u8> {
+    const PLIST_START: &[u8] = b"<plist version=\"1.0\">\n";
+    const PLIST_END: &[u8] = b"</plist>";
+    let find = |needle: &[u8]| data.windows(needle.len()).position(|w| w == needle);
+    let (Some(start), Some(end)) = (find(PLIST_START), find(PLIST_END)) else {
+        return data.to_vec();
+    };
+    let body_start = start + PLIST_START.len();
+
+    let mut indented = Vec::with_capacity(data.len() * 11 / 10);
+    indented.extend_from_slice(&data[..body_start]);
+    let mut in_text = false;
+    let mut at_line_start = true;
+    for (idx, &byte) in data[..end].iter().enumerate().skip(body_start) {
+        if at_line_start {
+            // Writing to a Vec cannot fail.
+            options.write_indent(&mut indented).unwrap();
+        }
+        if byte == b'<' {
+            let rest = &data[idx..];
+            if rest.starts_with(b"<key>") || rest.starts_with(b"<string>") {
+                in_text = true;
+            } else if rest.starts_with(b"</key>") || rest.starts_with(b"</string>") {
+                in_text = false;
+            }
+        }
+        indented.push(byte);
+        at_line_start = byte == b'\n' && !in_text;
+    }
+    indented.extend_from_slice(&data[end..]);
+    indented
+}
+
+/// Rewrite the XML declaration with custom quote formatting options.
+fn write_quote_style(data: &mut [u8], options: &WriteOptions) {
+    const DOUBLE_QUOTED: &[u8] = b"<?xml version=\"1.0\" encoding=\"UTF-8\"?>";
+    const SINGLE_QUOTED: &[u8] = b"<?xml version='1.0' encoding='UTF-8'?>";
     match options.quote_style {
-        QuoteChar::Single => {
-            // Unix platform specific write
-            #[cfg(target_family = "unix")]
-            file.write_at(b"<?xml version='1.0' encoding='UTF-8'?>", 0)?;
-            // Windows platform specific write
-            #[cfg(target_family = "windows")]
-            file.seek_write(b"<?xml version='1.0' encoding='UTF-8'?>", 0)?;
+        QuoteChar::Single if data.starts_with(DOUBLE_QUOTED) => {
+            data[..SINGLE_QUOTED.len()].copy_from_slice(SINGLE_QUOTED);
         }
-        QuoteChar::Double => (), // double quote is the default style
+        _ => (), // double quote is the default style
     }
-    Ok(())
 }
 
 #[derive(Debug, thiserror::Error)]
@@ -186,8 +300,8 @@ pub enum CustomSerializationError {
     CreateFile(#[source] std::io::Error),
     #[error("failed to serialize Plist")]
     SerializePlist(#[source] plist::Error),
-    #[error("failed to rewrite quote style")]
-    WriteQuotes(#[source] std::io::Error),
+    #[error("failed to write file")]
+    WriteFile(#[source] std::io::Error),
     #[error("failed to sync file to disk")]
     Sync(#[source] std::io::Error),
 }
@@ -313,6 +427,46 @@ mod tests {
         tmp.close().unwrap();
     }
 
+    #[test]
+    fn write_plist_ufolib_style() {
+        let mut lib = plist::Dictionary::new();
+        lib.insert("a".into(), Value::Dictionary(Default::default()));
+        let mut nested = plist::Dictionary::new();
+        nested.insert("multiline".into(), Value::String("x\n</string>\ny\n".into()));
+        lib.insert("b".into(), Value::Dictionary(nested));
+        lib.insert("c".into(), Value::Array(vec![Value::Integer(1.into())]));
+
+        let opt = WriteOptions::default()
+            .indent(WriteOptions::SPACE, 2)
+            .quote_char(QuoteChar::Single)
+            .plist_style(PlistStyle::UfoLib);
+        let written = String::from_utf8(write_xml_to_bytes(&lib, &opt).unwrap()).unwrap();
+        assert_eq!(
+            written,
+            r#"<?xml version='1.0' encoding='UTF-8'?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+  <dict>
+    <key>a</key>
+    <dict/>
+    <key>b</key>
+    <dict>
+      <key>multiline</key>
+      <string>x
+&lt;/string&gt;
+y
+</string>
+    </dict>
+    <key>c</key>
+    <array>
+      <integer>1</integer>
+    </array>
+  </dict>
+</plist>"#
+        );
+        assert_eq!(Value::from_reader_xml(written.as_bytes()).unwrap(), Value::Dictionary(lib));
+    }
+
     #[test]
     fn write_fontinfo_plist_line_endings() {
         let opt = WriteOptions::default();
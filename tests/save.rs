@@ -1,6 +1,9 @@
 //! Testing saving files.
 
-use norad::{Codepoints, Font, FormatVersion, Glyph, Identifier, Plist};
+use norad::{
+    Codepoints, Font, FormatVersion, Glyph, Guideline, Identifier, Layer, Line, Plist,
+    SequentialIdentifiers,
+};
 use plist::Value;
 use tempfile::TempDir;
 
@@ -21,6 +24,23 @@ fn save_default() {
     assert_eq!(loaded.layers.len(), 1);
 }
 
+#[test]
+fn save_with_creator() {
+    let dir = TempDir::new().unwrap();
+    let robofab = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+    assert_eq!(robofab.meta.creator.as_deref(), Some("org.robofab.ufoLib"));
+    robofab.save(&dir).unwrap();
+    let loaded = Font::load(&dir).unwrap();
+    assert_eq!(loaded.meta.creator.as_deref(), Some("org.linebender.norad"));
+
+    let mut font = loaded;
+    font.set_creator("com.example.generator");
+    font.save(&dir).unwrap();
+    let loaded = Font::load(&dir).unwrap();
+    assert_eq!(loaded.meta.creator.as_deref(), Some("com.example.generator"));
+    assert_eq!(loaded.meta, font.meta);
+}
+
 #[test]
 fn save_new_file() {
     let mut my_ufo = Font::new();
@@ -180,6 +200,60 @@ fn roundtrip_object_libs() {
     );
 }
 
+#[test]
+fn object_libs_attached_after_load_round_trip() {
+    let mut ufo = Font::load("testdata/MutatorSansLightWide.ufo").unwrap();
+    let mut ids = SequentialIdentifiers::new("id").unwrap();
+    let lib = |value: &str| -> Plist {
+        [("com.test.foo".to_string(), Value::from(value))].into_iter().collect()
+    };
+
+    let glyph = ufo.get_glyph_mut("A").unwrap();
+    assert!(glyph.contours[0].identifier().is_none());
+    glyph.contours[0].replace_lib_with(lib("contour"), &mut ids);
+    glyph.contours[0].points[1].replace_lib_with(lib("point"), &mut ids);
+    let glyph = ufo.get_glyph_mut("Aacute").unwrap();
+    glyph.components[1].replace_lib_with(lib("component"), &mut ids);
+    glyph.components[1].lib_mut().unwrap().insert("com.test.bar".into(), true.into());
+
+    let dir = TempDir::new().unwrap();
+    ufo.save(&dir).unwrap();
+    let loaded = Font::load(&dir).unwrap();
+    assert_eq!(loaded.default_layer(), ufo.default_layer());
+
+    let glyph = loaded.get_glyph("A").unwrap();
+    assert!(!glyph.lib.contains_key("public.objectLibs"));
+    assert_eq!(glyph.contours[0].identifier(), Some(&Identifier::new("id0").unwrap()));
+    assert_eq!(glyph.contours[0].lib(), Some(&lib("contour")));
+    assert_eq!(glyph.contours[0].points[1].identifier(), Some(&Identifier::new("id1").unwrap()));
+    assert_eq!(glyph.contours[0].points[1].lib(), Some(&lib("point")));
+    let component = &loaded.get_glyph("Aacute").unwrap().components[1];
+    assert_eq!(component.identifier(), Some(&Identifier::new("id2").unwrap()));
+    assert_eq!(component.lib().unwrap().get("com.test.bar"), Some(&Value::Boolean(true)));
+}
+
+#[test]
+fn global_guideline_libs_round_trip() {
+    let mut ufo = Font::new();
+    let mut ids = SequentialIdentifiers::new("guide").unwrap();
+    let mut lib = Plist::new();
+    lib.insert("com.test.foo".into(), Value::from("bar"));
+    let mut guideline = Guideline::new(Line::Horizontal(500.0), None, None, None);
+    guideline.replace_lib_with(lib.clone(), &mut ids);
+    ufo.font_info.guidelines_mut().push(guideline);
+    ufo.font_info.guidelines_mut().push(Guideline::new(Line::Vertical(10.0), None, None, None));
+
+    let dir = TempDir::new().unwrap();
+    ufo.save(&dir).unwrap();
+    let loaded = Font::load(&dir).unwrap();
+    assert!(!loaded.lib.contains_key("public.objectLibs"));
+    assert_eq!(loaded.font_info.guidelines(), ufo.font_info.guidelines());
+    let guideline = &loaded.font_info.guidelines()[0];
+    assert_eq!(guideline.identifier(), Some(&Identifier::new("guide0").unwrap()));
+    assert_eq!(guideline.lib(), Some(&lib));
+    assert_eq!(loaded.font_info.guidelines()[1].lib(), None);
+}
+
 #[test]
 fn object_libs_reject_existing_key() {
     let dir = TempDir::new().unwrap();
@@ -198,3 +272,55 @@ fn object_libs_reject_existing_key() {
     ufo.default_layer_mut().insert_glyph(glyph);
     assert!(ufo.save(&dir).is_err());
 }
+
+#[test]
+fn glyphsapp_lib_round_trips() {
+    let dir = TempDir::new().unwrap();
+    Font::new().save(&dir).unwrap();
+    std::fs::copy("testdata/glyphsapp_lib.plist", dir.path().join("lib.plist")).unwrap();
+
+    let font = Font::load(&dir).unwrap();
+    let glyph_order: Vec<_> = font.lib["public.glyphOrder"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|name| name.as_string().unwrap())
+        .collect();
+    assert_eq!(glyph_order, [".notdef", "space", "A", "V"]);
+    assert_eq!(font.lib["com.schriftgestaltung.appVersion"].as_string(), Some("3259"));
+
+    let saved_dir = TempDir::new().unwrap();
+    font.save(&saved_dir).unwrap();
+    let original = Value::from_file("testdata/glyphsapp_lib.plist").unwrap();
+    let saved = Value::from_file(saved_dir.path().join("lib.plist")).unwrap();
+    // Keys are sorted on save, but no key or value may be lost or altered.
+    assert_eq!(saved, original);
+    assert_eq!(saved.as_dictionary().unwrap(), &font.lib);
+}
+
+#[test]
+fn save_new_layer() {
+    let glyphs = ["a", "A", "A.alt"].map(Glyph::new);
+    let mut layer = Layer::new("sketches", glyphs).unwrap();
+    layer.lib.insert("com.example.key".into(), Value::from("value"));
+
+    let dir = TempDir::new().unwrap();
+    let layer_dir = dir.path().join("sketches");
+    layer.save(&layer_dir).unwrap();
+    assert!(layer_dir.join("contents.plist").exists());
+    assert!(layer_dir.join("layerinfo.plist").exists());
+    assert_eq!(Glyph::load(layer_dir.join("A_.alt.glif")).unwrap().name().as_str(), "A.alt");
+
+    let mut font = Font::new();
+    font.layers.insert_layer(layer.clone()).unwrap();
+    let ufo = dir.path().join("Test.ufo");
+    font.save(&ufo).unwrap();
+    let loaded = Font::load(&ufo).unwrap();
+    let loaded_layer = loaded.layers.get("sketches").unwrap();
+    assert_eq!(loaded_layer.path(), layer.path());
+    assert_eq!(loaded_layer.lib, layer.lib);
+    assert_eq!(
+        loaded_layer.glyph_names().map(|n| n.as_str()).collect::<Vec<_>>(),
+        ["A", "A.alt", "a"]
+    );
+}